@@ -19,11 +19,14 @@
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, panic_with_error, symbol_short, token, Address, Env,
+    String, Vec,
+};
 
 pub use crate::types::{
     BatchConversionResult, ConversionEvents, ConversionRequest, ConversionResult, DataKey,
-    MAX_BATCH_SIZE,
+    TransactionRecord, TxType, MAX_BATCH_SIZE,
 };
 use crate::validation::{
     validate_address, validate_amount, validate_asset_pair, validate_min_output,
@@ -57,6 +60,16 @@ impl From<BatchConversionError> for soroban_sdk::Error {
     }
 }
 
+/// Recording entrypoint exposed by the batch-history contract.
+#[contractclient(name = "HistoryClient")]
+pub trait HistoryInterface {
+    fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32;
+}
+
 #[contract]
 pub struct BatchConversionContract;
 
@@ -78,6 +91,34 @@ impl BatchConversionContract {
             .set(&DataKey::TotalVolumeConverted, &0i128);
     }
 
+    /// Sets the batch-history contract that records both legs of every
+    /// successful conversion in the user's history. This contract must be on
+    /// its source allowlist.
+    ///
+    /// Recording is best effort: a failing history call never reverts the
+    /// conversions themselves.
+    pub fn set_history_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::HistoryContract, &contract);
+    }
+
+    /// Stops recording conversions in the batch-history contract.
+    pub fn clear_history_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::HistoryContract);
+    }
+
+    /// Returns the configured batch-history contract, if any.
+    pub fn get_history_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::HistoryContract)
+    }
+
     /// Executes batch currency conversions for multiple users.
     ///
     /// # Arguments
@@ -122,6 +163,10 @@ impl BatchConversionContract {
         let mut failed_count: u32 = 0;
         let mut total_converted: i128 = 0;
 
+        let history_contract: Option<Address> =
+            env.storage().instance().get(&DataKey::HistoryContract);
+        let mut history: Vec<(Address, TransactionRecord)> = Vec::new(&env);
+
         // First pass: Validate all requests
         let mut validated_requests: Vec<(ConversionRequest, bool, u32)> = Vec::new(&env);
 
@@ -212,6 +257,36 @@ impl BatchConversionContract {
                         request.amount_in,
                         amount_out,
                     );
+
+                    if history_contract.is_some() {
+                        let timestamp = env.ledger().timestamp();
+                        history.push_back((
+                            request.user.clone(),
+                            TransactionRecord {
+                                amount: -request.amount_in,
+                                timestamp,
+                                description: String::from_str(&env, "Conversion sold"),
+                                category: symbol_short!("convert"),
+                                counterparty: None,
+                                tx_type: TxType::Conversion,
+                                source_contract: Some(env.current_contract_address()),
+                                batch_id: Some(batch_id),
+                            },
+                        ));
+                        history.push_back((
+                            request.user.clone(),
+                            TransactionRecord {
+                                amount: amount_out,
+                                timestamp,
+                                description: String::from_str(&env, "Conversion bought"),
+                                category: symbol_short!("convert"),
+                                counterparty: None,
+                                tx_type: TxType::Conversion,
+                                source_contract: Some(env.current_contract_address()),
+                                batch_id: Some(batch_id),
+                            },
+                        ));
+                    }
                 }
                 Err(error_code) => {
                     // Conversion failed
@@ -236,6 +311,13 @@ impl BatchConversionContract {
             }
         }
 
+        if let Some(contract) = history_contract {
+            if !history.is_empty() {
+                let _ = HistoryClient::new(&env, &contract)
+                    .try_batch_record_from_contract(&env.current_contract_address(), &history);
+            }
+        }
+
         // Update storage (batched at the end for gas efficiency)
         let total_batches: u64 = env
             .storage()
@@ -309,6 +391,19 @@ impl BatchConversionContract {
             .unwrap_or(0)
     }
 
+    // Internal helper to check the caller is the admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, BatchConversionError::NotInitialized));
+
+        if *caller != admin {
+            panic_with_error!(env, BatchConversionError::Unauthorized);
+        }
+    }
+
     // Internal helper to execute a single conversion
     fn execute_conversion(env: &Env, request: &ConversionRequest) -> Result<i128, u32> {
         // TODO: Implement actual conversion mechanism
@@ -4,8 +4,10 @@
 
 use crate::{
     BatchConversionContract, BatchConversionContractClient, ConversionRequest, ConversionResult,
+    TransactionRecord,
 };
 use soroban_sdk::{
+    contract, contractimpl, symbol_short,
     testutils::{Address as _, Events as _, Ledger},
     token, Address, Env, Vec,
 };
@@ -309,3 +311,69 @@ fn test_batch_convert_empty_batch() {
     let conversions: Vec<ConversionRequest> = Vec::new(&env);
     client.batch_convert_currency(&conversions);
 }
+
+/// Minimal stand-in for the batch-history contract.
+#[contract]
+struct MockHistory;
+
+#[contractimpl]
+impl MockHistory {
+    pub fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32 {
+        source_contract.require_auth();
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("records"), &records);
+        records.len()
+    }
+
+    pub fn recorded(env: Env) -> Vec<(Address, TransactionRecord)> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("records"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_successful_conversions_are_recorded_in_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let from_asset = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let to_asset = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(BatchConversionContract, ());
+    let client = BatchConversionContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let history_id = env.register(MockHistory, ());
+    let history = MockHistoryClient::new(&env, &history_id);
+    client.set_history_contract(&admin, &history_id);
+    assert_eq!(client.get_history_contract(), Some(history_id));
+
+    let user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &from_asset).mint(&user, &1000);
+
+    let mut conversions: Vec<ConversionRequest> = Vec::new(&env);
+    conversions.push_back(create_conversion_request(
+        user.clone(),
+        from_asset.clone(),
+        to_asset.clone(),
+        100,
+        90,
+    ));
+    client.batch_convert_currency(&conversions);
+
+    let records = history.recorded();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records.get(0).unwrap().1.amount, -100);
+    assert_eq!(records.get(1).unwrap().1.amount, 90);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
 
@@ -29,6 +29,32 @@ pub struct BatchConversionResult {
     pub results: Vec<ConversionResult>,
 }
 
+/// Mirror of the batch-history contract's `TxType`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TxType {
+    Transfer,
+    Payment,
+    Conversion,
+    GoalContribution,
+    BudgetSpend,
+    Other,
+}
+
+/// Entry appended to a user's history in the batch-history contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TransactionRecord {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub description: String,
+    pub category: Symbol,
+    pub counterparty: Option<Address>,
+    pub tx_type: TxType,
+    pub source_contract: Option<Address>,
+    pub batch_id: Option<u64>,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -36,6 +62,8 @@ pub enum DataKey {
     TotalBatches,
     TotalConversionsProcessed,
     TotalVolumeConverted,
+    /// Batch-history contract that records successful conversions
+    HistoryContract,
 }
 
 pub struct ConversionEvents;
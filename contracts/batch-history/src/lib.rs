@@ -1,5 +1,8 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, panic_with_error, symbol_short, Address, Bytes, Env,
+    IntoVal, Symbol, Val, Vec,
+};
 
 mod logic;
 mod types;
@@ -7,21 +10,558 @@ mod types;
 #[cfg(test)]
 mod test;
 
-use crate::types::UserHistory;
+use crate::types::{
+    ArchiveInfo, Checkpoint, DataKey, MonthlySummary, TransactionRecord, TxType, UserHistory,
+    UserStats,
+};
+
+/// Error codes for the batch history contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum HistoryError {
+    /// Contract has not been initialized
+    NotInitialized = 1,
+    /// Contract was already initialized
+    AlreadyInitialized = 2,
+    /// Caller lacks the role the operation requires
+    Unauthorized = 3,
+    /// Query range start is after its end
+    InvalidRange = 4,
+    /// Calling contract is not on the source allowlist
+    SourceNotAllowed = 5,
+    /// Period is not a valid `YYYYMM` month
+    InvalidPeriod = 6,
+    /// Month has not ended yet
+    PeriodNotOver = 7,
+    /// Month was already closed
+    PeriodAlreadyClosed = 8,
+    /// Expiry is not in the future
+    InvalidExpiry = 9,
+    /// Description is longer than `MAX_DESCRIPTION_LEN` bytes
+    DescriptionTooLong = 10,
+}
 
 #[contract]
 pub struct BatchHistoryContract;
 
 #[contractimpl]
 impl BatchHistoryContract {
+    /// Sets the admin allowed to record transactions and manage writers.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, HistoryError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Allows `writer` (e.g. an indexing service) to record transactions.
+    pub fn add_writer(env: Env, admin: Address, writer: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Writer(writer), &true);
+    }
+
+    /// Revokes a writer's recording rights.
+    pub fn remove_writer(env: Env, admin: Address, writer: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().persistent().remove(&DataKey::Writer(writer));
+    }
+
+    /// Returns true if `address` is a registered writer.
+    pub fn is_writer(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Writer(address))
+    }
+
+    /// Appends a transaction to the user's history. Any `source_contract` on
+    /// the record is cleared; only `record_from_contract` can stamp one.
+    pub fn record_transaction(env: Env, caller: Address, user: Address, record: TransactionRecord) {
+        caller.require_auth();
+        Self::require_admin_or_writer(&env, &caller);
+
+        let mut record = record;
+        record.source_contract = None;
+        logic::record_transaction(&env, user, record)
+    }
+
+    /// Appends several transactions, each to its user's history, clearing
+    /// `source_contract` as `record_transaction` does. Returns the number of
+    /// records written.
+    pub fn batch_record_transactions(
+        env: Env,
+        caller: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32 {
+        caller.require_auth();
+        Self::require_admin_or_writer(&env, &caller);
+
+        for (user, mut record) in records.iter() {
+            record.source_contract = None;
+            logic::record_transaction(&env, user, record);
+        }
+        records.len()
+    }
+
+    /// Allows `auditor` to read any user's history through the history
+    /// readers until the `expiry` ledger timestamp, without handing over the
+    /// admin key. Every auditor read emits an `audit` event.
+    /// Calling it again replaces the expiry.
+    pub fn add_auditor(env: Env, admin: Address, auditor: Address, expiry: u64) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        if expiry <= env.ledger().timestamp() {
+            panic_with_error!(&env, HistoryError::InvalidExpiry);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Auditor(auditor), &expiry);
+    }
+
+    /// Revokes an auditor's read access.
+    pub fn remove_auditor(env: Env, admin: Address, auditor: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Auditor(auditor));
+    }
+
+    /// Returns true if `address` is an auditor whose access has not expired.
+    pub fn is_auditor(env: Env, address: Address) -> bool {
+        Self::auditor_expiry(&env, &address).is_some_and(|expiry| env.ledger().timestamp() < expiry)
+    }
+
+    /// Returns the ledger timestamp at which an auditor's access ends.
+    pub fn get_auditor_expiry(env: Env, address: Address) -> Option<u64> {
+        Self::auditor_expiry(&env, &address)
+    }
+
+    /// Allows a sibling contract (e.g. batch-transfer, batch-payment or
+    /// batch-conversion) to record through `record_from_contract`.
+    pub fn allow_source(env: Env, admin: Address, source_contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::AllowedSource(source_contract), &true);
+    }
+
+    /// Removes a contract from the source allowlist.
+    pub fn disallow_source(env: Env, admin: Address, source_contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedSource(source_contract));
+    }
+
+    /// Returns true if `source_contract` may call `record_from_contract`.
+    pub fn is_allowed_source(env: Env, source_contract: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::AllowedSource(source_contract))
+    }
+
+    /// Records a transaction on behalf of an allowlisted sibling contract.
+    /// `source_contract` must be the invoking contract; it is stamped on the
+    /// record.
+    pub fn record_from_contract(
+        env: Env,
+        source_contract: Address,
+        user: Address,
+        record: TransactionRecord,
+    ) {
+        source_contract.require_auth();
+        Self::require_allowed_source(&env, &source_contract);
+
+        let mut record = record;
+        record.source_contract = Some(source_contract);
+        logic::record_transaction(&env, user, record)
+    }
+
+    /// Records a settled batch on behalf of an allowlisted sibling contract
+    /// in one call. Returns the number of records written.
+    pub fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32 {
+        source_contract.require_auth();
+        Self::require_allowed_source(&env, &source_contract);
+
+        for (user, mut record) in records.iter() {
+            record.source_contract = Some(source_contract.clone());
+            logic::record_transaction(&env, user, record);
+        }
+        records.len()
+    }
+
+    /// Returns records `offset..offset + limit` of each user's recent
+    /// history. At most `MAX_RECORDS_PER_CALL` records are returned in total.
+    ///
+    /// The requester may only fetch their own history unless they are the
+    /// admin or an unexpired auditor; auditor reads emit an `audit` event.
     pub fn retrieve_histories(
         env: Env,
         requester: Address,
         users: Vec<Address>,
+        offset: u32,
+        limit: u32,
     ) -> Vec<UserHistory> {
         // Requirement: Validate user/requester
         requester.require_auth();
+        if users.iter().any(|user| user != requester) {
+            Self::require_admin_or_auditor(&env, &requester);
+            Self::audit(&env, &requester, (users.clone(), offset, limit));
+        }
+
+        logic::get_batch_history(env, users, offset, limit)
+    }
+
+    /// Returns one page of a user's recent history, oldest first.
+    pub fn retrieve_history_page(
+        env: Env,
+        requester: Address,
+        user: Address,
+        offset: u32,
+        limit: u32,
+    ) -> UserHistory {
+        Self::require_reader(&env, &requester, &user);
+        logic::history_page(&env, user, offset, limit)
+    }
+
+    /// Returns one page of the user's records in `category`, oldest first,
+    /// including archived ones. `total` counts the user's records in that
+    /// category.
+    pub fn get_history_by_category(
+        env: Env,
+        requester: Address,
+        user: Address,
+        category: Symbol,
+        offset: u32,
+        limit: u32,
+    ) -> UserHistory {
+        Self::require_reader(&env, &requester, &user);
+        logic::category_page(&env, user, category, offset, limit)
+    }
+
+    /// Returns one page of the user's records of `tx_type`, oldest first,
+    /// including archived ones. `total` counts the user's records of that
+    /// type.
+    pub fn get_history_by_type(
+        env: Env,
+        requester: Address,
+        user: Address,
+        tx_type: TxType,
+        offset: u32,
+        limit: u32,
+    ) -> UserHistory {
+        Self::require_reader(&env, &requester, &user);
+        logic::type_page(&env, user, tx_type, offset, limit)
+    }
+
+    /// Returns one page of the user's records with `counterparty` (e.g.
+    /// everything paid to a vendor), oldest first, including archived ones.
+    /// `total` counts the user's records with that counterparty.
+    pub fn get_history_with_counterparty(
+        env: Env,
+        requester: Address,
+        user: Address,
+        counterparty: Address,
+        offset: u32,
+        limit: u32,
+    ) -> UserHistory {
+        Self::require_reader(&env, &requester, &user);
+        logic::counterparty_page(&env, user, counterparty, offset, limit)
+    }
+
+    /// Returns the records resulting from `source`'s batch `batch_id`, with
+    /// the user each belongs to, so reconciliation can walk from a payout
+    /// batch to its history entries. At most `MAX_RECORDS_PER_CALL` records
+    /// are returned per call; use `offset` for the rest. The requester must be
+    /// `source`, the admin or an unexpired auditor.
+    pub fn get_records_by_batch(
+        env: Env,
+        requester: Address,
+        source: Address,
+        batch_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(Address, TransactionRecord)> {
+        requester.require_auth();
+        if requester != source {
+            Self::require_admin_or_auditor(&env, &requester);
+            Self::audit(&env, &requester, (source.clone(), batch_id));
+        }
+        logic::batch_records(&env, &source, batch_id, offset, limit)
+    }
+
+    /// Returns the user's recent records within a time range (inclusive, ledger
+    /// timestamps) whose absolute amount lies in `min_amount..=max_amount`
+    /// and, if `tx_type` is given, of that type, e.g. "all payments in March
+    /// over 100 XLM". At most
+    /// `MAX_RECORDS_PER_CALL` records are returned; narrow the range to see
+    /// the rest.
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_history(
+        env: Env,
+        requester: Address,
+        user: Address,
+        from_ts: u64,
+        to_ts: u64,
+        min_amount: i128,
+        max_amount: i128,
+        tx_type: Option<TxType>,
+    ) -> Vec<TransactionRecord> {
+        Self::require_reader(&env, &requester, &user);
+        if from_ts > to_ts || min_amount > max_amount {
+            panic_with_error!(&env, HistoryError::InvalidRange);
+        }
+
+        logic::query_history(&env, &user, from_ts, to_ts, min_amount, max_amount, tx_type)
+    }
+
+    /// Returns the user's running totals (record count, money in and out,
+    /// average amount and last activity) without reading their history.
+    pub fn get_user_stats(env: Env, requester: Address, user: Address) -> UserStats {
+        Self::require_reader(&env, &requester, &user);
+        logic::user_stats(&env, &user)
+    }
+
+    /// Persists the user's summary for a finished calendar month (`YYYYMM`,
+    /// UTC): net totals per category, money in and out and net flow. The
+    /// totals are kept up to date as records arrive, so closing is cheap;
+    /// records dated in a month after it is closed are not added to it.
+    /// The caller must be the user, the admin or a writer.
+    pub fn close_month(env: Env, caller: Address, user: Address, period: u32) -> MonthlySummary {
+        caller.require_auth();
+        if caller != user {
+            Self::require_admin_or_writer(&env, &caller);
+        }
+
+        logic::close_month(&env, &user, period)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+    }
+
+    /// Returns the user's `count` latest closed monthly summaries (at most
+    /// `MAX_SUMMARIES_PER_CALL`), oldest period first.
+    pub fn get_monthly_summaries(
+        env: Env,
+        requester: Address,
+        user: Address,
+        count: u32,
+    ) -> Vec<MonthlySummary> {
+        Self::require_reader(&env, &requester, &user);
+        logic::monthly_summaries(&env, &user, count)
+    }
+
+    /// Folds the user's records recorded since the last checkpoint (at most
+    /// `MAX_RECORDS_PER_CHECKPOINT`) into their hash chain, so exported
+    /// statements can be verified against an on-chain commitment. Anyone may
+    /// call this; it returns the new chain head.
+    pub fn checkpoint(env: Env, user: Address) -> Checkpoint {
+        logic::checkpoint(&env, &user)
+    }
+
+    /// Returns the user's latest checkpoint, if any.
+    pub fn get_checkpoint(env: Env, user: Address) -> Option<Checkpoint> {
+        logic::get_checkpoint(&env, &user)
+    }
+
+    /// Returns the user's records at positions `from..to` (oldest first,
+    /// counting archived records) in the compact binary layout documented on
+    /// `EXPORT_FORMAT_VERSION`, so indexers and statement generators can
+    /// bulk-pull history. At most `MAX_RECORDS_PER_EXPORT` records are
+    /// encoded; the header's record count says how many.
+    pub fn export_history(
+        env: Env,
+        requester: Address,
+        user: Address,
+        from: u32,
+        to: u32,
+    ) -> Bytes {
+        Self::require_reader(&env, &requester, &user);
+        if from > to {
+            panic_with_error!(&env, HistoryError::InvalidRange);
+        }
+
+        logic::export_history(&env, &user, from, to)
+    }
+
+    /// Caps the records kept per user; once a user exceeds it, recording
+    /// deletes their oldest records (ring-buffer style, regardless of the
+    /// retention period) and emits a `truncated` event. 0 removes the cap.
+    pub fn set_max_records_per_user(env: Env, admin: Address, max_records: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if max_records == 0 {
+            env.storage().instance().remove(&DataKey::MaxRecordsPerUser);
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::MaxRecordsPerUser, &max_records);
+        }
+    }
+
+    /// Returns the per-user record cap (0 if unlimited).
+    pub fn get_max_records_per_user(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxRecordsPerUser)
+            .unwrap_or(0)
+    }
+
+    /// Sets how long, in seconds, records must be kept before
+    /// `prune_history` may delete them.
+    pub fn set_min_retention(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinRetention, &seconds);
+    }
+
+    /// Returns the minimum retention period in seconds (0 if unset).
+    pub fn get_min_retention(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinRetention)
+            .unwrap_or(0)
+    }
+
+    /// Deletes the user's oldest records timestamped before `before_ts`,
+    /// archived ones first, to control storage rent. Records younger than
+    /// the minimum retention period are kept regardless. The caller must be
+    /// the user or the admin. Deletes at most `MAX_RECORDS_PER_PRUNE` records
+    /// per call and returns the number deleted.
+    pub fn prune_history(env: Env, caller: Address, user: Address, before_ts: u64) -> u32 {
+        caller.require_auth();
+        if caller != user {
+            Self::require_admin(&env, &caller);
+        }
+
+        let retained_from = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(Self::get_min_retention(env.clone()));
+        logic::prune_history(&env, &user, before_ts.min(retained_from))
+    }
+
+    /// Moves the user's oldest records timestamped before `before_ts` out of
+    /// recent history into a compacted archive chunk, so reads and appends
+    /// stay bounded as the history grows. Moves at most
+    /// `MAX_RECORDS_PER_ARCHIVE` records per call; returns the number moved.
+    pub fn archive_old_records(env: Env, caller: Address, user: Address, before_ts: u64) -> u32 {
+        caller.require_auth();
+        Self::require_admin_or_writer(&env, &caller);
+
+        logic::archive_old_records(&env, &user, before_ts)
+    }
+
+    /// Returns how many of the user's records are archived and where each
+    /// archive chunk starts.
+    pub fn get_archive_info(env: Env, user: Address) -> ArchiveInfo {
+        logic::archive_info(&env, &user)
+    }
+
+    /// Returns the records in one of the user's archive chunks, oldest first.
+    pub fn get_archived_records(
+        env: Env,
+        requester: Address,
+        user: Address,
+        chunk_id: u32,
+    ) -> Vec<TransactionRecord> {
+        Self::require_reader(&env, &requester, &user);
+        logic::archived_chunk(&env, &user, chunk_id)
+    }
+
+    /// Extends the TTL of the user's archive chunks. Anyone may pay to keep
+    /// an archive live.
+    pub fn extend_archive_ttl(env: Env, user: Address) {
+        logic::extend_archive_ttl(&env, &user)
+    }
+
+    fn stored_admin(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, HistoryError::NotInitialized))
+    }
+
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::stored_admin(env) {
+            panic_with_error!(env, HistoryError::Unauthorized);
+        }
+    }
+
+    fn is_admin(env: &Env, caller: &Address) -> bool {
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        admin.as_ref() == Some(caller)
+    }
+
+    fn auditor_expiry(env: &Env, address: &Address) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Auditor(address.clone()))
+    }
+
+    fn require_admin_or_auditor(env: &Env, caller: &Address) {
+        if !Self::is_admin(env, caller) && !Self::is_auditor(env.clone(), caller.clone()) {
+            panic_with_error!(env, HistoryError::Unauthorized);
+        }
+    }
+
+    // Internal helper letting `requester` read `user`'s data: the user
+    // themselves, the admin, or an unexpired auditor
+    fn require_reader(env: &Env, requester: &Address, user: &Address) {
+        requester.require_auth();
+        if requester != user {
+            Self::require_admin_or_auditor(env, requester);
+            Self::audit(env, requester, user.clone());
+        }
+    }
+
+    // Internal helper recording an auditor read; admin reads are not audited
+    fn audit<D>(env: &Env, requester: &Address, data: D)
+    where
+        D: IntoVal<Env, Val>,
+    {
+        if !Self::is_admin(env, requester) {
+            env.events()
+                .publish((symbol_short!("audit"), requester.clone()), data);
+        }
+    }
+
+    fn require_allowed_source(env: &Env, source_contract: &Address) {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::AllowedSource(source_contract.clone()))
+        {
+            panic_with_error!(env, HistoryError::SourceNotAllowed);
+        }
+    }
 
-        logic::get_batch_history(env, users)
+    fn require_admin_or_writer(env: &Env, caller: &Address) {
+        if *caller != Self::stored_admin(env)
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::Writer(caller.clone()))
+        {
+            panic_with_error!(env, HistoryError::Unauthorized);
+        }
     }
 }
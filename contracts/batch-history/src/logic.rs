@@ -1,9 +1,28 @@
-use crate::types::UserHistory;
-use soroban_sdk::{symbol_short, Address, Env, Vec};
+use crate::types::{
+    ArchiveInfo, CachedPage, Checkpoint, DataKey, MonthlySummary, TransactionRecord, TxType,
+    UserHistory, UserStats, EXPORT_FORMAT_VERSION, HISTORY_TTL_EXTEND, HISTORY_TTL_THRESHOLD,
+    MAX_DESCRIPTION_LEN, MAX_RECORDS_PER_ARCHIVE, MAX_RECORDS_PER_CALL, MAX_RECORDS_PER_CHECKPOINT,
+    MAX_RECORDS_PER_EXPORT, MAX_RECORDS_PER_PRUNE, MAX_SUMMARIES_PER_CALL, PAGE_CACHE_TTL_LEDGERS,
+};
+use crate::HistoryError;
+use soroban_sdk::{
+    panic_with_error, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, Symbol,
+    Val, Vec,
+};
 
-pub fn get_batch_history(env: Env, users: Vec<Address>) -> Vec<UserHistory> {
-    // Optimization: Pre-allocate capacity if possible to avoid re-allocations
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Returns the page `offset..offset + limit` of each user's history. Once
+/// `MAX_RECORDS_PER_CALL` records have been returned, later users get
+/// truncated (possibly empty) pages.
+pub fn get_batch_history(
+    env: Env,
+    users: Vec<Address>,
+    offset: u32,
+    limit: u32,
+) -> Vec<UserHistory> {
     let mut batch_results = Vec::new(&env);
+    let mut budget = MAX_RECORDS_PER_CALL;
 
     for user in users.iter() {
         // Requirement: Emit events for retrieval (helps with off-chain indexing)
@@ -12,13 +31,881 @@ pub fn get_batch_history(env: Env, users: Vec<Address>) -> Vec<UserHistory> {
             symbol_short!("retrieved"),
         );
 
-        // Optimization: In a production environment, you would use
-        // env.storage().temporary().get() here to fetch cached history.
-        batch_results.push_back(UserHistory {
-            user: user.clone(),
-            transactions: Vec::new(&env), // Placeholder for actual storage data
-        });
+        let page = history_page(&env, user, offset, limit.min(budget));
+        budget -= page.transactions.len();
+        batch_results.push_back(page);
     }
 
     batch_results
 }
+
+/// Appends `record` to `user`'s history and folds it into their stats.
+pub fn record_transaction(env: &Env, user: Address, record: TransactionRecord) {
+    if record.description.len() > MAX_DESCRIPTION_LEN {
+        panic_with_error!(env, HistoryError::DescriptionTooLong);
+    }
+
+    let mut records = history(env, &user);
+    records.push_back(record.clone());
+    store(env, &DataKey::History(user.clone()), &records);
+    bump_version(env, &user);
+
+    let position = next_position(env, &user);
+    let mut stats = user_stats(env, &user);
+
+    append_to_index(
+        env,
+        &DataKey::CategoryIndex(user.clone(), record.category.clone()),
+        position,
+    );
+    append_to_index(
+        env,
+        &DataKey::TypeIndex(user.clone(), record.tx_type),
+        position,
+    );
+    if let (Some(source), Some(batch_id)) = (&record.source_contract, record.batch_id) {
+        let key = DataKey::BatchIndex(source.clone(), batch_id);
+        let mut entries: Vec<(Address, u32)> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        entries.push_back((user.clone(), position));
+        store(env, &key, &entries);
+    }
+    if let Some(counterparty) = &record.counterparty {
+        append_to_index(
+            env,
+            &DataKey::CounterpartyIndex(user.clone(), counterparty.clone()),
+            position,
+        );
+    }
+
+    stats.count += 1;
+    if record.amount >= 0 {
+        stats.total_in = stats.total_in.saturating_add(record.amount);
+    } else {
+        stats.total_out = stats
+            .total_out
+            .saturating_add(record.amount.saturating_abs());
+    }
+    stats.last_activity = stats.last_activity.max(record.timestamp);
+    store_stats(env, &user, stats);
+
+    // Records dated in an already closed month don't reopen it
+    let period = period_of(record.timestamp);
+    if !env
+        .storage()
+        .persistent()
+        .has(&DataKey::MonthlySummary(user.clone(), period))
+    {
+        let key = DataKey::MonthTotals(user.clone(), period);
+        let mut month = month_totals(env, &key, period);
+        let category_total = month
+            .category_totals
+            .get(record.category.clone())
+            .unwrap_or(0);
+        month.category_totals.set(
+            record.category.clone(),
+            category_total.saturating_add(record.amount),
+        );
+        if record.amount >= 0 {
+            month.total_in = month.total_in.saturating_add(record.amount);
+        } else {
+            month.total_out = month
+                .total_out
+                .saturating_add(record.amount.saturating_abs());
+        }
+        month.net_flow = month.total_in.saturating_sub(month.total_out);
+        month.record_count += 1;
+        store(env, &key, &month);
+    }
+
+    env.events().publish(
+        (symbol_short!("recorded"), user.clone()),
+        (record.amount, record.timestamp),
+    );
+
+    // Ring-buffer mode: overwrite the oldest records beyond the cap
+    let max_records: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxRecordsPerUser)
+        .unwrap_or(0);
+    let stored = user_stats(env, &user).count;
+    if max_records > 0 && stored > max_records {
+        let excess = (stored - max_records).min(MAX_RECORDS_PER_PRUNE);
+        let deleted = delete_oldest(env, &user, u64::MAX, excess);
+        env.events()
+            .publish((symbol_short!("truncated"), user), deleted);
+    }
+}
+
+/// Returns up to `limit` (capped at `MAX_RECORDS_PER_CALL`) of `user`'s
+/// recent records starting at `offset`, served from the page cache while
+/// the history is unchanged.
+pub fn history_page(env: &Env, user: Address, offset: u32, limit: u32) -> UserHistory {
+    let limit = limit.min(MAX_RECORDS_PER_CALL);
+    let version = history_version(env, &user);
+    let cache_key = DataKey::PageCache(user.clone(), offset, limit);
+    if let Some(cached) = env.storage().temporary().get::<_, CachedPage>(&cache_key) {
+        if cached.version == version {
+            return cached.page;
+        }
+    }
+
+    let records = history(env, &user);
+    let total = records.len();
+    let end = total.min(offset.saturating_add(limit));
+    let transactions = if offset < end {
+        records.slice(offset..end)
+    } else {
+        Vec::new(env)
+    };
+    let page = UserHistory {
+        user,
+        transactions,
+        total,
+    };
+
+    env.storage().temporary().set(
+        &cache_key,
+        &CachedPage {
+            version,
+            page: page.clone(),
+        },
+    );
+    env.storage().temporary().extend_ttl(
+        &cache_key,
+        PAGE_CACHE_TTL_LEDGERS,
+        PAGE_CACHE_TTL_LEDGERS,
+    );
+    page
+}
+
+/// Returns up to `limit` (capped at `MAX_RECORDS_PER_CALL`) of `user`'s
+/// remaining records in `category`, starting at the `offset`-th such record.
+pub fn category_page(
+    env: &Env,
+    user: Address,
+    category: Symbol,
+    offset: u32,
+    limit: u32,
+) -> UserHistory {
+    let key = DataKey::CategoryIndex(user.clone(), category);
+    indexed_page(env, user, &key, offset, limit)
+}
+
+/// Returns up to `limit` (capped at `MAX_RECORDS_PER_CALL`) of `user`'s
+/// remaining records of `tx_type`, starting at the `offset`-th such record.
+pub fn type_page(
+    env: &Env,
+    user: Address,
+    tx_type: TxType,
+    offset: u32,
+    limit: u32,
+) -> UserHistory {
+    let key = DataKey::TypeIndex(user.clone(), tx_type);
+    indexed_page(env, user, &key, offset, limit)
+}
+
+/// Returns up to `limit` (capped at `MAX_RECORDS_PER_CALL`) of `user`'s
+/// remaining records with `counterparty`, starting at the `offset`-th such
+/// record.
+pub fn counterparty_page(
+    env: &Env,
+    user: Address,
+    counterparty: Address,
+    offset: u32,
+    limit: u32,
+) -> UserHistory {
+    let key = DataKey::CounterpartyIndex(user.clone(), counterparty);
+    indexed_page(env, user, &key, offset, limit)
+}
+
+/// Returns a page of the records listed in a position index of `user`'s.
+fn indexed_page(env: &Env, user: Address, key: &DataKey, offset: u32, limit: u32) -> UserHistory {
+    // Pruning drops deleted positions from the index, so all are live
+    let index: Vec<u32> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+
+    let total = index.len();
+    let end = total.min(offset.saturating_add(limit.min(MAX_RECORDS_PER_CALL)));
+
+    let transactions = if offset < end {
+        records_at(env, &user, index.slice(offset..end))
+    } else {
+        Vec::new(env)
+    };
+
+    UserHistory {
+        user,
+        transactions,
+        total,
+    }
+}
+
+/// Returns up to `limit` (capped at `MAX_RECORDS_PER_CALL`) of the remaining
+/// records from `source`'s batch `batch_id`, with their users, starting at
+/// the `offset`-th such record.
+pub fn batch_records(
+    env: &Env,
+    source: &Address,
+    batch_id: u64,
+    offset: u32,
+    limit: u32,
+) -> Vec<(Address, TransactionRecord)> {
+    // Pruning drops deleted entries from the index, so all are live
+    let entries: Vec<(Address, u32)> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::BatchIndex(source.clone(), batch_id))
+        .unwrap_or(Vec::new(env));
+    let end = entries
+        .len()
+        .min(offset.saturating_add(limit.min(MAX_RECORDS_PER_CALL)));
+    if offset >= end {
+        return Vec::new(env);
+    }
+    let entries = entries.slice(offset..end);
+
+    // Look each user's records up in one pass
+    let mut positions: Map<Address, Vec<u32>> = Map::new(env);
+    for (user, position) in entries.iter() {
+        let mut user_positions = positions.get(user.clone()).unwrap_or(Vec::new(env));
+        user_positions.push_back(position);
+        positions.set(user, user_positions);
+    }
+    let mut found: Map<Address, Vec<TransactionRecord>> = Map::new(env);
+    for (user, user_positions) in positions.iter() {
+        found.set(user.clone(), records_at(env, &user, user_positions));
+    }
+
+    let mut records = Vec::new(env);
+    let mut taken: Map<Address, u32> = Map::new(env);
+    for (user, _) in entries.iter() {
+        let next = taken.get(user.clone()).unwrap_or(0);
+        let record = found.get_unchecked(user.clone()).get_unchecked(next);
+        taken.set(user.clone(), next + 1);
+        records.push_back((user, record));
+    }
+    records
+}
+
+/// Returns `user`'s recent records with `from_ts <= timestamp <= to_ts` and
+/// `min_amount <= |amount| <= max_amount`, of `tx_type` if given, oldest
+/// first, stopping at `MAX_RECORDS_PER_CALL` matches.
+pub fn query_history(
+    env: &Env,
+    user: &Address,
+    from_ts: u64,
+    to_ts: u64,
+    min_amount: i128,
+    max_amount: i128,
+    tx_type: Option<TxType>,
+) -> Vec<TransactionRecord> {
+    let mut matches = Vec::new(env);
+    for record in history(env, user).iter() {
+        if matches.len() == MAX_RECORDS_PER_CALL {
+            break;
+        }
+        let amount = record.amount.saturating_abs();
+        if (from_ts..=to_ts).contains(&record.timestamp)
+            && (min_amount..=max_amount).contains(&amount)
+            && (tx_type.is_none() || tx_type == Some(record.tx_type))
+        {
+            matches.push_back(record);
+        }
+    }
+    matches
+}
+
+/// Returns `user`'s aggregate statistics, zeroed if nothing was recorded.
+pub fn user_stats(env: &Env, user: &Address) -> UserStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Stats(user.clone()))
+        .unwrap_or_default()
+}
+
+/// Extends `user`'s hash chain over up to `MAX_RECORDS_PER_CHECKPOINT`
+/// records recorded since the last checkpoint, and returns the new head.
+pub fn checkpoint(env: &Env, user: &Address) -> Checkpoint {
+    let key = DataKey::Checkpoint(user.clone());
+    let mut head = get_checkpoint(env, user).unwrap_or(Checkpoint {
+        hash: BytesN::from_array(env, &[0; 32]),
+        record_count: 0,
+        timestamp: 0,
+    });
+
+    let start = head.record_count.max(archive_info(env, user).pruned);
+    let end = next_position(env, user).min(start + MAX_RECORDS_PER_CHECKPOINT);
+    if end == head.record_count {
+        return head;
+    }
+
+    if start < end {
+        let mut positions = Vec::new(env);
+        for position in start..end {
+            positions.push_back(position);
+        }
+        let records = records_at(env, user, positions);
+
+        let mut preimage = Bytes::from_array(env, &head.hash.to_array());
+        preimage.append(&records.to_xdr(env));
+        head.hash = env.crypto().sha256(&preimage).into();
+    }
+    head.record_count = end;
+    head.timestamp = env.ledger().timestamp();
+    store(env, &key, &head);
+
+    env.events().publish(
+        (symbol_short!("checkpt"), user.clone()),
+        (head.record_count, head.hash.clone()),
+    );
+    head
+}
+
+/// Returns `user`'s latest checkpoint, if any.
+pub fn get_checkpoint(env: &Env, user: &Address) -> Option<Checkpoint> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Checkpoint(user.clone()))
+}
+
+/// Encodes `user`'s records at positions `from..to` (counting archived
+/// records, skipping pruned ones, capped at `MAX_RECORDS_PER_EXPORT`) in the
+/// layout documented on `EXPORT_FORMAT_VERSION`.
+pub fn export_history(env: &Env, user: &Address, from: u32, to: u32) -> Bytes {
+    let from = from.max(archive_info(env, user).pruned);
+    let end = to
+        .min(next_position(env, user))
+        .min(from.saturating_add(MAX_RECORDS_PER_EXPORT));
+    let mut positions = Vec::new(env);
+    for position in from..end.max(from) {
+        positions.push_back(position);
+    }
+    let records = records_at(env, user, positions);
+
+    let mut out = Bytes::new(env);
+    out.push_back(EXPORT_FORMAT_VERSION);
+    out.extend_from_array(&records.len().to_be_bytes());
+    for record in records.iter() {
+        out.extend_from_array(&record.amount.to_be_bytes());
+        out.extend_from_array(&record.timestamp.to_be_bytes());
+
+        let category = xdr_payload(env, record.category.to_xdr(env));
+        out.push_back(category.len() as u8);
+        out.append(&category);
+
+        // Descriptions are capped at `MAX_DESCRIPTION_LEN` when recorded
+        let description = xdr_payload(env, record.description.to_xdr(env));
+        out.extend_from_array(&(description.len() as u16).to_be_bytes());
+        out.append(&description);
+
+        out.push_back(tx_type_index(record.tx_type));
+        append_address(env, &mut out, &record.counterparty);
+        append_address(env, &mut out, &record.source_contract);
+        match record.batch_id {
+            Some(batch_id) => {
+                out.push_back(1);
+                out.extend_from_array(&batch_id.to_be_bytes());
+            }
+            None => out.push_back(0),
+        }
+    }
+    out
+}
+
+/// Position of `tx_type` in the `TxType` declaration, as exported.
+fn tx_type_index(tx_type: TxType) -> u8 {
+    match tx_type {
+        TxType::Transfer => 0,
+        TxType::Payment => 1,
+        TxType::Conversion => 2,
+        TxType::GoalContribution => 3,
+        TxType::BudgetSpend => 4,
+        TxType::Other => 5,
+    }
+}
+
+/// Appends an optional address as a length byte and its `ScAddress` XDR.
+fn append_address(env: &Env, out: &mut Bytes, address: &Option<Address>) {
+    match address {
+        Some(address) => {
+            // Skip the `ScVal` tag, leaving the `ScAddress`
+            let xdr = address.to_xdr(env);
+            let xdr = xdr.slice(4..xdr.len());
+            out.push_back(xdr.len() as u8);
+            out.append(&xdr);
+        }
+        None => out.push_back(0),
+    }
+}
+
+/// Moves the oldest of `user`'s recent records with a timestamp before
+/// `before_ts` into a new archive chunk, stopping at the first newer record
+/// or after `MAX_RECORDS_PER_ARCHIVE` records. Returns the number moved.
+pub fn archive_old_records(env: &Env, user: &Address, before_ts: u64) -> u32 {
+    let records = history(env, user);
+    let mut moved = 0;
+    for record in records.iter() {
+        if moved == MAX_RECORDS_PER_ARCHIVE || record.timestamp >= before_ts {
+            break;
+        }
+        moved += 1;
+    }
+    if moved == 0 {
+        return 0;
+    }
+
+    let mut info = archive_info(env, user);
+    let chunk_id = info.chunk_starts.len();
+    store(
+        env,
+        &DataKey::Archive(user.clone(), chunk_id),
+        &records.slice(0..moved),
+    );
+    info.chunk_starts.push_back(info.archived);
+    info.archived += moved;
+    store(env, &DataKey::ArchiveInfo(user.clone()), &info);
+
+    let history_key = DataKey::History(user.clone());
+    if moved == records.len() {
+        env.storage().persistent().remove(&history_key);
+    } else {
+        store(env, &history_key, &records.slice(moved..records.len()));
+    }
+    bump_version(env, user);
+
+    env.events()
+        .publish((symbol_short!("archived"), user.clone()), (chunk_id, moved));
+    moved
+}
+
+/// Deletes `user`'s oldest records, archived first, with a timestamp before
+/// `cutoff`, stopping at the first newer record or after
+/// `MAX_RECORDS_PER_PRUNE` records, and removes them from the user's stats.
+/// Returns the number deleted.
+pub fn prune_history(env: &Env, user: &Address, cutoff: u64) -> u32 {
+    let deleted = delete_oldest(env, user, cutoff, MAX_RECORDS_PER_PRUNE);
+    if deleted > 0 {
+        env.events()
+            .publish((symbol_short!("pruned"), user.clone()), deleted);
+    }
+    deleted
+}
+
+/// Deletes up to `limit` of `user`'s oldest records, archived first, with a
+/// timestamp before `cutoff`, stopping at the first newer record, and
+/// removes them from the user's stats. Returns the number deleted.
+fn delete_oldest(env: &Env, user: &Address, cutoff: u64, limit: u32) -> u32 {
+    let mut info = archive_info(env, user);
+    let mut stats = user_stats(env, user);
+    let mut deleted = 0;
+    // Entries to drop from each index the deleted records are listed in
+    let mut trims: Map<DataKey, u32> = Map::new(env);
+
+    // Archived records, chunk by chunk
+    let mut newer_found = false;
+    for chunk_id in 0..info.chunk_starts.len() {
+        let start = info.chunk_starts.get_unchecked(chunk_id);
+        let chunk_key = DataKey::Archive(user.clone(), chunk_id);
+        let chunk = archived_chunk(env, user, chunk_id);
+        if chunk.is_empty() {
+            continue;
+        }
+
+        let taken = take_prunable(&chunk, cutoff, limit - deleted, &mut stats);
+        note_index_entries(user, &chunk.slice(0..taken), &mut trims);
+        deleted += taken;
+        if taken == chunk.len() {
+            env.storage().persistent().remove(&chunk_key);
+        } else {
+            store(env, &chunk_key, &chunk.slice(taken..chunk.len()));
+            newer_found = true;
+        }
+        info.chunk_starts.set(chunk_id, start + taken);
+        info.pruned = start + taken;
+        if newer_found {
+            break;
+        }
+    }
+
+    // Then recent records, once the whole archive is gone
+    if !newer_found && deleted < limit {
+        let records = history(env, user);
+        let taken = take_prunable(&records, cutoff, limit - deleted, &mut stats);
+        note_index_entries(user, &records.slice(0..taken), &mut trims);
+        if taken > 0 {
+            deleted += taken;
+            info.archived += taken;
+            info.pruned = info.archived;
+
+            let history_key = DataKey::History(user.clone());
+            if taken == records.len() {
+                env.storage().persistent().remove(&history_key);
+            } else {
+                store(env, &history_key, &records.slice(taken..records.len()));
+            }
+        }
+    }
+
+    if deleted == 0 {
+        return 0;
+    }
+    store(env, &DataKey::ArchiveInfo(user.clone()), &info);
+    store_stats(env, user, stats);
+    trim_indexes(env, user, info.pruned, &trims);
+    bump_version(env, user);
+    deleted
+}
+
+/// Counts, per index key, the entries `deleted` records have in `user`'s
+/// indexes.
+fn note_index_entries(
+    user: &Address,
+    deleted: &Vec<TransactionRecord>,
+    trims: &mut Map<DataKey, u32>,
+) {
+    for record in deleted.iter() {
+        let mut keys = Vec::from_array(
+            deleted.env(),
+            [
+                DataKey::CategoryIndex(user.clone(), record.category.clone()),
+                DataKey::TypeIndex(user.clone(), record.tx_type),
+            ],
+        );
+        if let Some(counterparty) = record.counterparty {
+            keys.push_back(DataKey::CounterpartyIndex(user.clone(), counterparty));
+        }
+        if let (Some(source), Some(batch_id)) = (record.source_contract, record.batch_id) {
+            keys.push_back(DataKey::BatchIndex(source, batch_id));
+        }
+        for key in keys.iter() {
+            let count = trims.get(key.clone()).unwrap_or(0);
+            trims.set(key, count + 1);
+        }
+    }
+}
+
+/// Drops pruned positions (below `pruned`) from the indexes in `trims`.
+fn trim_indexes(env: &Env, user: &Address, pruned: u32, trims: &Map<DataKey, u32>) {
+    for (key, count) in trims.iter() {
+        if let DataKey::BatchIndex(..) = key {
+            // Batch entries of several users interleave
+            let entries: Vec<(Address, u32)> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(env));
+            let mut kept = Vec::new(env);
+            for (entry_user, position) in entries.iter() {
+                if entry_user != *user || position >= pruned {
+                    kept.push_back((entry_user, position));
+                }
+            }
+            store_or_remove(env, &key, &kept);
+        } else {
+            // Positions ascend, so the deleted ones lead the index
+            let index: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(env));
+            store_or_remove(env, &key, &index.slice(count.min(index.len())..index.len()));
+        }
+    }
+}
+
+/// Writes a non-empty index, or removes it once empty.
+fn store_or_remove<T>(env: &Env, key: &DataKey, index: &Vec<T>)
+where
+    T: IntoVal<Env, Val> + soroban_sdk::TryFromVal<Env, Val>,
+{
+    if index.is_empty() {
+        env.storage().persistent().remove(key);
+    } else {
+        store(env, key, index);
+    }
+}
+
+/// Persists `user`'s summary for `period` (`YYYYMM`) once the month is over.
+pub fn close_month(env: &Env, user: &Address, period: u32) -> Result<MonthlySummary, HistoryError> {
+    let (_, end) = month_bounds(period).ok_or(HistoryError::InvalidPeriod)?;
+    if env.ledger().timestamp() < end {
+        return Err(HistoryError::PeriodNotOver);
+    }
+    let summary_key = DataKey::MonthlySummary(user.clone(), period);
+    if env.storage().persistent().has(&summary_key) {
+        return Err(HistoryError::PeriodAlreadyClosed);
+    }
+
+    let totals_key = DataKey::MonthTotals(user.clone(), period);
+    let summary = month_totals(env, &totals_key, period);
+    env.storage().persistent().remove(&totals_key);
+    store(env, &summary_key, &summary);
+
+    let closed_key = DataKey::ClosedMonths(user.clone());
+    let mut closed: Vec<u32> = env
+        .storage()
+        .persistent()
+        .get(&closed_key)
+        .unwrap_or(Vec::new(env));
+    // Kept in period order, whatever order months are closed in
+    let position = closed
+        .binary_search(period)
+        .unwrap_or_else(|position| position);
+    closed.insert(position, period);
+    store(env, &closed_key, &closed);
+
+    env.events().publish(
+        (symbol_short!("closed"), user.clone()),
+        (period, summary.net_flow),
+    );
+    Ok(summary)
+}
+
+/// Returns `user`'s `count` (capped at `MAX_SUMMARIES_PER_CALL`) latest
+/// closed monthly summaries, oldest period first.
+pub fn monthly_summaries(env: &Env, user: &Address, count: u32) -> Vec<MonthlySummary> {
+    let closed: Vec<u32> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ClosedMonths(user.clone()))
+        .unwrap_or(Vec::new(env));
+    let start = closed
+        .len()
+        .saturating_sub(count.min(MAX_SUMMARIES_PER_CALL));
+
+    let mut summaries = Vec::new(env);
+    for period in closed.slice(start..closed.len()).iter() {
+        if let Some(summary) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MonthlySummary(user.clone(), period))
+        {
+            summaries.push_back(summary);
+        }
+    }
+    summaries
+}
+
+/// Returns where `user`'s archived records live.
+pub fn archive_info(env: &Env, user: &Address) -> ArchiveInfo {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ArchiveInfo(user.clone()))
+        .unwrap_or(ArchiveInfo {
+            archived: 0,
+            pruned: 0,
+            chunk_starts: Vec::new(env),
+        })
+}
+
+/// Returns the records in one of `user`'s archive chunks, oldest first.
+pub fn archived_chunk(env: &Env, user: &Address, chunk_id: u32) -> Vec<TransactionRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Archive(user.clone(), chunk_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Extends the TTL of `user`'s archive chunks so they stay live.
+pub fn extend_archive_ttl(env: &Env, user: &Address) {
+    let info = archive_info(env, user);
+    for chunk_id in 0..info.chunk_starts.len() {
+        env.storage().persistent().extend_ttl(
+            &DataKey::Archive(user.clone(), chunk_id),
+            HISTORY_TTL_THRESHOLD,
+            HISTORY_TTL_EXTEND,
+        );
+    }
+    if !info.chunk_starts.is_empty() {
+        env.storage().persistent().extend_ttl(
+            &DataKey::ArchiveInfo(user.clone()),
+            HISTORY_TTL_THRESHOLD,
+            HISTORY_TTL_EXTEND,
+        );
+    }
+}
+
+/// Counts the leading `records` older than `cutoff`, at most `limit`, and
+/// removes them from `stats`.
+fn take_prunable(
+    records: &Vec<TransactionRecord>,
+    cutoff: u64,
+    limit: u32,
+    stats: &mut UserStats,
+) -> u32 {
+    let mut taken = 0;
+    for record in records.iter() {
+        if taken == limit || record.timestamp >= cutoff {
+            break;
+        }
+        stats.count -= 1;
+        if record.amount >= 0 {
+            stats.total_in = stats.total_in.saturating_sub(record.amount);
+        } else {
+            stats.total_out = stats
+                .total_out
+                .saturating_sub(record.amount.saturating_abs());
+        }
+        taken += 1;
+    }
+    taken
+}
+
+/// Returns the running totals stored under `key`, or empty ones.
+fn month_totals(env: &Env, key: &DataKey, period: u32) -> MonthlySummary {
+    env.storage()
+        .persistent()
+        .get(key)
+        .unwrap_or(MonthlySummary {
+            period,
+            category_totals: Map::new(env),
+            total_in: 0,
+            total_out: 0,
+            net_flow: 0,
+            record_count: 0,
+        })
+}
+
+/// Returns the `YYYYMM` period containing a ledger timestamp (UTC).
+fn period_of(timestamp: u64) -> u32 {
+    // Civil-from-days, after Howard Hinnant's date algorithms
+    let z = (timestamp / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year * 100 + month) as u32
+}
+
+/// Returns the first second of `period` and of the month after it, or
+/// `None` if `period` is not a valid `YYYYMM` from 1970 on.
+fn month_bounds(period: u32) -> Option<(u64, u64)> {
+    let (year, month) = (period / 100, period % 100);
+    if year < 1970 || !(1..=12).contains(&month) {
+        return None;
+    }
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    Some((
+        days_from_civil(year, month) * SECONDS_PER_DAY,
+        days_from_civil(next_year, next_month) * SECONDS_PER_DAY,
+    ))
+}
+
+/// Days from 1970-01-01 to the first of a month.
+fn days_from_civil(year: u32, month: u32) -> u64 {
+    let year = (year - if month <= 2 { 1 } else { 0 }) as u64;
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * ((month as u64 + 9) % 12) + 2) / 5;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns `user`'s history version.
+fn history_version(env: &Env, user: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::HistoryVersion(user.clone()))
+        .unwrap_or(0)
+}
+
+/// Invalidates `user`'s cached pages.
+fn bump_version(env: &Env, user: &Address) {
+    let version = history_version(env, user).wrapping_add(1);
+    store(env, &DataKey::HistoryVersion(user.clone()), &version);
+}
+
+/// Appends a record position to a position index.
+fn append_to_index(env: &Env, key: &DataKey, position: u32) {
+    let mut index: Vec<u32> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+    index.push_back(position);
+    store(env, key, &index);
+}
+
+/// Position the next record appended for `user` will take.
+fn next_position(env: &Env, user: &Address) -> u32 {
+    archive_info(env, user).pruned + user_stats(env, user).count
+}
+
+/// Recomputes the average and writes `user`'s stats.
+fn store_stats(env: &Env, user: &Address, mut stats: UserStats) {
+    stats.average_amount = if stats.count == 0 {
+        0
+    } else {
+        stats.total_in.saturating_add(stats.total_out) / stats.count as i128
+    };
+    store(env, &DataKey::Stats(user.clone()), &stats);
+}
+
+/// Looks up `user`'s records by position, counting archived records, in
+/// the order given. Positions must not be pruned.
+fn records_at(env: &Env, user: &Address, positions: Vec<u32>) -> Vec<TransactionRecord> {
+    let recent = history(env, user);
+    let info = archive_info(env, user);
+    let mut records = Vec::new(env);
+    // Archive chunk most recently loaded, as (chunk ID, records)
+    let mut chunk: Option<(u32, Vec<TransactionRecord>)> = None;
+
+    for position in positions.iter() {
+        if position >= info.archived {
+            records.push_back(recent.get_unchecked(position - info.archived));
+            continue;
+        }
+
+        let mut chunk_id = 0;
+        for (id, start) in info.chunk_starts.iter().enumerate() {
+            if start <= position {
+                chunk_id = id as u32;
+            }
+        }
+        if chunk.as_ref().map(|(id, _)| *id) != Some(chunk_id) {
+            chunk = Some((chunk_id, archived_chunk(env, user, chunk_id)));
+        }
+        if let Some((_, archived)) = &chunk {
+            let start = info.chunk_starts.get_unchecked(chunk_id);
+            records.push_back(archived.get_unchecked(position - start));
+        }
+    }
+    records
+}
+
+/// Returns the raw bytes of an XDR-encoded `ScVal` symbol or string: a
+/// 4-byte type tag and 4-byte length precede the (padded) content.
+fn xdr_payload(env: &Env, xdr: Bytes) -> Bytes {
+    let mut len = [0u8; 4];
+    xdr.slice(4..8).copy_into_slice(&mut len);
+    let len = u32::from_be_bytes(len);
+    if len == 0 {
+        return Bytes::new(env);
+    }
+    xdr.slice(8..8 + len)
+}
+
+/// Writes a persistent entry and extends its TTL.
+fn store<V>(env: &Env, key: &DataKey, value: &V)
+where
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, HISTORY_TTL_THRESHOLD, HISTORY_TTL_EXTEND);
+}
+
+fn history(env: &Env, user: &Address) -> Vec<TransactionRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::History(user.clone()))
+        .unwrap_or(Vec::new(env))
+}
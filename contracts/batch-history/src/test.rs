@@ -1,5 +1,15 @@
-use crate::{BatchHistoryContract, BatchHistoryContractClient};
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use crate::types::{
+    ArchiveInfo, CachedPage, DataKey, TransactionRecord, TxType, UserStats, MAX_DESCRIPTION_LEN,
+    MAX_RECORDS_PER_CALL,
+};
+use crate::{BatchHistoryContract, BatchHistoryContractClient, HistoryError};
+use soroban_sdk::{
+    map, symbol_short,
+    testutils::{Address as _, Events as _, Ledger},
+    vec,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, String,
+};
 
 #[test]
 fn test_batch_retrieval() {
@@ -9,12 +19,1091 @@ fn test_batch_retrieval() {
     let contract_id = env.register(BatchHistoryContract, ());
     let client = BatchHistoryContractClient::new(&env, &contract_id);
 
-    let requester = Address::generate(&env);
     let user_1 = Address::generate(&env);
 
     let users = vec![&env, user_1.clone()];
-    let results = client.retrieve_histories(&requester, &users);
+    let results = client.retrieve_histories(&user_1, &users, &0, &10);
 
     assert_eq!(results.len(), 1);
     assert_eq!(results.get(0).unwrap().user, user_1);
 }
+
+#[test]
+fn test_reading_other_histories_requires_admin_or_auditor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let user_1 = Address::generate(&env);
+    let user_2 = Address::generate(&env);
+    client.initialize(&admin);
+
+    let users = vec![&env, user_1.clone(), user_2.clone()];
+    let result = client.try_retrieve_histories(&user_1, &users, &0, &10);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+    let result = client.try_retrieve_histories(&auditor, &users, &0, &10);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+
+    env.ledger().set_timestamp(1_000);
+    let result = client.try_add_auditor(&admin, &auditor, &1_000);
+    assert_eq!(result, Err(Ok(HistoryError::InvalidExpiry.into())));
+    client.add_auditor(&admin, &auditor, &2_000);
+    assert!(client.is_auditor(&auditor));
+    assert_eq!(client.get_auditor_expiry(&auditor), Some(2_000));
+    assert_eq!(
+        client.retrieve_histories(&auditor, &users, &0, &10).len(),
+        2
+    );
+    assert_eq!(client.retrieve_histories(&admin, &users, &0, &10).len(), 2);
+
+    // Access lapses at the expiry
+    env.ledger().set_timestamp(2_000);
+    assert!(!client.is_auditor(&auditor));
+    let result = client.try_retrieve_histories(&auditor, &users, &0, &10);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+
+    client.add_auditor(&admin, &auditor, &3_000);
+    client.remove_auditor(&admin, &auditor);
+    let result = client.try_retrieve_histories(&auditor, &users, &0, &10);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+}
+
+#[test]
+fn test_user_scoped_readers_require_self_admin_or_auditor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.initialize(&admin);
+
+    let unauthorized = Some(Ok(HistoryError::Unauthorized.into()));
+    assert_eq!(
+        client
+            .try_retrieve_history_page(&other, &user, &0, &10)
+            .err(),
+        unauthorized
+    );
+    assert_eq!(client.try_get_user_stats(&other, &user).err(), unauthorized);
+    assert_eq!(
+        client.try_export_history(&other, &user, &0, &10).err(),
+        unauthorized
+    );
+    assert_eq!(
+        client
+            .try_get_history_by_category(&other, &user, &symbol_short!("food"), &0, &10)
+            .err(),
+        unauthorized
+    );
+    assert_eq!(
+        client
+            .try_get_records_by_batch(&other, &admin, &1, &0, &10)
+            .err(),
+        unauthorized
+    );
+
+    assert_eq!(client.get_user_stats(&user, &user), UserStats::default());
+    assert_eq!(client.get_user_stats(&admin, &user), UserStats::default());
+
+    // Auditor reads are allowed and leave an audit trail
+    client.add_auditor(&admin, &auditor, &1_000);
+    client.get_monthly_summaries(&user, &user, &1);
+    assert_eq!(env.events().all().len(), 0);
+    client.get_monthly_summaries(&auditor, &user, &1);
+    assert_eq!(env.events().all().len(), 1);
+}
+
+#[test]
+fn test_recorded_transactions_are_retrieved() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let writer = Address::generate(&env);
+    let user_1 = Address::generate(&env);
+    let user_2 = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, description: &str| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, description),
+        category: symbol_short!("general"),
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+
+    let result = client.try_record_transaction(&writer, &user_1, &record(100, 1, "Salary"));
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+
+    client.add_writer(&admin, &writer);
+    client.record_transaction(&writer, &user_1, &record(100, 1, "Salary"));
+    let written = client.batch_record_transactions(
+        &writer,
+        &vec![
+            &env,
+            (user_1.clone(), record(-30, 2, "Groceries")),
+            (user_2.clone(), record(50, 3, "Refund")),
+        ],
+    );
+    assert_eq!(written, 2);
+
+    let users = vec![&env, user_1.clone(), user_2.clone()];
+    let results = client.retrieve_histories(&admin, &users, &0, &10);
+    assert_eq!(
+        results.get(0).unwrap().transactions,
+        vec![&env, record(100, 1, "Salary"), record(-30, 2, "Groceries")]
+    );
+    assert_eq!(
+        results.get(1).unwrap().transactions,
+        vec![&env, record(50, 3, "Refund")]
+    );
+
+    // Writers cannot pass records off as another contract's batch
+    let batch_payment = Address::generate(&env);
+    let forged = TransactionRecord {
+        source_contract: Some(batch_payment.clone()),
+        batch_id: Some(7),
+        ..record(-5, 4, "Forged")
+    };
+    client.record_transaction(&writer, &user_2, &forged);
+    client.batch_record_transactions(&writer, &vec![&env, (user_2.clone(), forged)]);
+    assert!(client
+        .get_records_by_batch(&admin, &batch_payment, &7, &0, &10)
+        .is_empty());
+    let history = client.retrieve_history_page(&user_2, &user_2, &0, &10);
+    assert!(history
+        .transactions
+        .iter()
+        .all(|record| record.source_contract.is_none()));
+}
+
+#[test]
+fn test_history_pages_and_call_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user_1 = Address::generate(&env);
+    let user_2 = Address::generate(&env);
+    client.initialize(&admin);
+
+    for i in 0..MAX_RECORDS_PER_CALL {
+        let record = TransactionRecord {
+            amount: i as i128,
+            timestamp: i as u64,
+            description: String::from_str(&env, "Coffee"),
+            category: symbol_short!("food"),
+            counterparty: None,
+            tx_type: TxType::Other,
+            source_contract: None,
+            batch_id: None,
+        };
+        let mut records = vec![&env, (user_1.clone(), record.clone())];
+        if i < 5 {
+            records.push_back((user_2.clone(), record));
+        }
+        client.batch_record_transactions(&admin, &records);
+    }
+
+    let page = client.retrieve_history_page(&user_1, &user_1, &10, &5);
+    assert_eq!(page.total, MAX_RECORDS_PER_CALL);
+    assert_eq!(page.transactions.len(), 5);
+    assert_eq!(page.transactions.get(0).unwrap().amount, 10);
+
+    // user_1 uses up the per-call cap, leaving nothing for user_2
+    let users = vec![&env, user_1.clone(), user_2.clone()];
+    let results = client.retrieve_histories(&admin, &users, &0, &MAX_RECORDS_PER_CALL);
+    assert_eq!(
+        results.get(0).unwrap().transactions.len(),
+        MAX_RECORDS_PER_CALL
+    );
+    assert_eq!(results.get(1).unwrap().transactions.len(), 0);
+    assert_eq!(results.get(1).unwrap().total, 5);
+}
+
+#[test]
+fn test_query_history_filters_by_time_and_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Transfer"),
+        category: symbol_short!("transfer"),
+        counterparty: None,
+        tx_type: TxType::Transfer,
+        source_contract: None,
+        batch_id: None,
+    };
+    let records = vec![
+        &env,
+        (user.clone(), record(500, 10)),
+        (user.clone(), record(-250, 20)),
+        (user.clone(), record(-50, 25)),
+        (user.clone(), record(1_000, 40)),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    let matches = client.query_history(&user, &user, &10, &30, &100, &i128::MAX, &None);
+    assert_eq!(matches, vec![&env, record(500, 10), record(-250, 20)]);
+    let matches = client.query_history(
+        &user,
+        &user,
+        &10,
+        &30,
+        &100,
+        &i128::MAX,
+        &Some(TxType::Transfer),
+    );
+    assert_eq!(matches.len(), 2);
+    let matches = client.query_history(
+        &user,
+        &user,
+        &10,
+        &30,
+        &100,
+        &i128::MAX,
+        &Some(TxType::Payment),
+    );
+    assert!(matches.is_empty());
+
+    let result = client.try_query_history(&user, &user, &30, &10, &0, &100, &None);
+    assert_eq!(result, Err(Ok(HistoryError::InvalidRange.into())));
+}
+
+#[test]
+fn test_record_from_contract_requires_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let batch_payment = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = TransactionRecord {
+        amount: -75,
+        timestamp: 1,
+        description: String::from_str(&env, "Payment sent"),
+        category: symbol_short!("payment"),
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+
+    let result = client.try_record_from_contract(&batch_payment, &user, &record);
+    assert_eq!(result, Err(Ok(HistoryError::SourceNotAllowed.into())));
+
+    client.allow_source(&admin, &batch_payment);
+    assert!(client.is_allowed_source(&batch_payment));
+    client.record_from_contract(&batch_payment, &user, &record);
+    let stamped = TransactionRecord {
+        source_contract: Some(batch_payment.clone()),
+        ..record
+    };
+    assert_eq!(
+        client
+            .retrieve_history_page(&user, &user, &0, &10)
+            .transactions,
+        vec![&env, stamped]
+    );
+
+    client.disallow_source(&admin, &batch_payment);
+    assert!(!client.is_allowed_source(&batch_payment));
+}
+
+#[test]
+fn test_user_stats_track_recorded_transactions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.get_user_stats(&user, &user), UserStats::default());
+
+    let record = |amount: i128, timestamp: u64| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Transfer"),
+        category: symbol_short!("transfer"),
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+
+    let records = vec![
+        &env,
+        (user.clone(), record(500, 10)),
+        (user.clone(), record(-250, 30)),
+        (user.clone(), record(-50, 20)),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    assert_eq!(
+        client.get_user_stats(&user, &user),
+        UserStats {
+            count: 3,
+            total_in: 500,
+            total_out: 300,
+            average_amount: 266,
+            last_activity: 30,
+        }
+    );
+}
+
+#[test]
+fn test_history_by_category() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, category| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Card payment"),
+        category,
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let food = symbol_short!("food");
+    let rent = symbol_short!("rent");
+    let records = vec![
+        &env,
+        (user.clone(), record(-20, 1, food.clone())),
+        (user.clone(), record(-900, 2, rent.clone())),
+        (user.clone(), record(-35, 3, food.clone())),
+        (user.clone(), record(-15, 4, food.clone())),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    let page = client.get_history_by_category(&user, &user, &food, &1, &10);
+    assert_eq!(page.total, 3);
+    assert_eq!(
+        page.transactions,
+        vec![&env, record(-35, 3, food.clone()), record(-15, 4, food)]
+    );
+
+    let page = client.get_history_by_category(&user, &user, &rent, &0, &10);
+    assert_eq!(page.transactions, vec![&env, record(-900, 2, rent)]);
+
+    let page = client.get_history_by_category(&user, &user, &symbol_short!("travel"), &0, &10);
+    assert_eq!(page.total, 0);
+    assert!(page.transactions.is_empty());
+}
+
+#[test]
+fn test_archive_old_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, category| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Card payment"),
+        category,
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let food = symbol_short!("food");
+    let rent = symbol_short!("rent");
+    let records = vec![
+        &env,
+        (user.clone(), record(-20, 10, food.clone())),
+        (user.clone(), record(-900, 20, rent.clone())),
+        (user.clone(), record(-35, 30, food.clone())),
+        (user.clone(), record(-15, 40, food.clone())),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    assert_eq!(client.archive_old_records(&admin, &user, &10), 0);
+    assert_eq!(client.archive_old_records(&admin, &user, &25), 2);
+    assert_eq!(client.archive_old_records(&admin, &user, &35), 1);
+    assert_eq!(
+        client.get_archive_info(&user),
+        ArchiveInfo {
+            archived: 3,
+            pruned: 0,
+            chunk_starts: vec![&env, 0, 2],
+        }
+    );
+    assert_eq!(
+        client.get_archived_records(&user, &user, &0),
+        vec![&env, record(-20, 10, food.clone()), record(-900, 20, rent)]
+    );
+
+    let recent = client.retrieve_history_page(&user, &user, &0, &10);
+    assert_eq!(
+        recent.transactions,
+        vec![&env, record(-15, 40, food.clone())]
+    );
+
+    // Category pages span archive chunks and recent history
+    let page = client.get_history_by_category(&user, &user, &food, &0, &10);
+    assert_eq!(
+        page.transactions,
+        vec![
+            &env,
+            record(-20, 10, food.clone()),
+            record(-35, 30, food.clone()),
+            record(-15, 40, food.clone()),
+        ]
+    );
+
+    // Stats still cover archived records
+    assert_eq!(client.get_user_stats(&user, &user).count, 4);
+    client.extend_archive_ttl(&user);
+
+    client.record_transaction(&admin, &user, &record(-5, 50, food.clone()));
+    let page = client.get_history_by_category(&user, &user, &food, &3, &10);
+    assert_eq!(page.transactions, vec![&env, record(-5, 50, food)]);
+}
+
+#[test]
+fn test_checkpoints_chain_record_hashes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.get_checkpoint(&user), None);
+
+    let record = |amount: i128, timestamp: u64| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Transfer"),
+        category: symbol_short!("transfer"),
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let first = vec![&env, record(100, 1), record(-40, 2)];
+    for item in first.iter() {
+        client.record_transaction(&admin, &user, &item);
+    }
+    let head = client.checkpoint(&user);
+    assert_eq!(head.record_count, 2);
+
+    let mut preimage = Bytes::from_array(&env, &[0; 32]);
+    preimage.append(&first.to_xdr(&env));
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(head.hash, expected);
+
+    // Nothing new: the head is unchanged
+    assert_eq!(client.checkpoint(&user), head);
+
+    // Archived records are still chained in order
+    let second = vec![&env, record(-10, 3)];
+    client.record_transaction(&admin, &user, &record(-10, 3));
+    client.archive_old_records(&admin, &user, &10);
+    let next = client.checkpoint(&user);
+    assert_eq!(next.record_count, 3);
+
+    let mut preimage = Bytes::from_array(&env, &head.hash.to_array());
+    preimage.append(&second.to_xdr(&env));
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(next.hash, expected);
+    assert_eq!(client.get_checkpoint(&user), Some(next));
+}
+
+#[test]
+fn test_export_history_encoding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let employer = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.record_transaction(
+        &admin,
+        &user,
+        &TransactionRecord {
+            amount: -250,
+            timestamp: 7,
+            description: String::from_str(&env, "Rent"),
+            category: symbol_short!("housing"),
+            counterparty: None,
+            tx_type: TxType::Other,
+            source_contract: None,
+            batch_id: None,
+        },
+    );
+    client.record_transaction(
+        &admin,
+        &user,
+        &TransactionRecord {
+            amount: 1_000,
+            timestamp: 9,
+            description: String::from_str(&env, ""),
+            category: symbol_short!("salary"),
+            counterparty: Some(employer.clone()),
+            tx_type: TxType::Payment,
+            source_contract: None,
+            batch_id: Some(3),
+        },
+    );
+
+    let employer_xdr = employer.clone().to_xdr(&env);
+    let employer_xdr = employer_xdr.slice(4..employer_xdr.len());
+    let mut expected = Bytes::new(&env);
+    expected.push_back(2);
+    expected.extend_from_array(&1u32.to_be_bytes());
+    expected.extend_from_array(&1_000i128.to_be_bytes());
+    expected.extend_from_array(&9u64.to_be_bytes());
+    expected.push_back(6);
+    expected.extend_from_slice(b"salary");
+    expected.extend_from_array(&0u16.to_be_bytes());
+    expected.push_back(1);
+    expected.push_back(employer_xdr.len() as u8);
+    expected.append(&employer_xdr);
+    expected.push_back(0);
+    expected.push_back(1);
+    expected.extend_from_array(&3u64.to_be_bytes());
+    assert_eq!(client.export_history(&user, &user, &1, &10), expected);
+
+    let full = client.export_history(&user, &user, &0, &2);
+    let mut header = [0u8; 5];
+    full.slice(0..5).copy_into_slice(&mut header);
+    assert_eq!(header, [2, 0, 0, 0, 2]);
+    assert_eq!(
+        full.len(),
+        5 + (16 + 8 + 1 + 7 + 2 + 4 + 4) + (expected.len() - 5)
+    );
+
+    // Descriptions beyond the cap are rejected rather than truncated on export
+    let long = TransactionRecord {
+        amount: 1,
+        timestamp: 10,
+        description: String::from_bytes(&env, &[b'a'; MAX_DESCRIPTION_LEN as usize + 1]),
+        category: symbol_short!("misc"),
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    assert_eq!(
+        client.try_record_transaction(&admin, &user, &long).err(),
+        Some(Ok(HistoryError::DescriptionTooLong.into()))
+    );
+
+    let result = client.try_export_history(&user, &user, &2, &1);
+    assert_eq!(result, Err(Ok(HistoryError::InvalidRange.into())));
+}
+
+#[test]
+fn test_prune_history_honors_retention() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, category| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Card payment"),
+        category,
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let food = symbol_short!("food");
+    let rent = symbol_short!("rent");
+    let records = vec![
+        &env,
+        (user.clone(), record(-20, 10, food.clone())),
+        (user.clone(), record(-900, 20, rent.clone())),
+        (user.clone(), record(500, 30, food.clone())),
+        (user.clone(), record(-15, 40, food.clone())),
+    ];
+    client.batch_record_transactions(&admin, &records);
+    client.archive_old_records(&admin, &user, &25);
+
+    let result = client.try_prune_history(&stranger, &user, &100);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+
+    // Only records older than 100 - 65 = 35 may go
+    env.ledger().set_timestamp(100);
+    client.set_min_retention(&admin, &65);
+    assert_eq!(client.prune_history(&user, &user, &100), 3);
+    assert_eq!(client.prune_history(&admin, &user, &100), 0);
+
+    assert_eq!(
+        client.get_user_stats(&user, &user),
+        UserStats {
+            count: 1,
+            total_in: 0,
+            total_out: 15,
+            average_amount: 15,
+            last_activity: 40,
+        }
+    );
+    assert_eq!(
+        client
+            .retrieve_history_page(&user, &user, &0, &10)
+            .transactions,
+        vec![&env, record(-15, 40, food.clone())]
+    );
+    let page = client.get_history_by_category(&user, &user, &food, &0, &10);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.transactions, vec![&env, record(-15, 40, food.clone())]);
+    assert_eq!(
+        client
+            .get_history_by_category(&user, &user, &rent, &0, &10)
+            .total,
+        0
+    );
+
+    // New records keep their own positions
+    client.record_transaction(&admin, &user, &record(-5, 110, rent.clone()));
+    let page = client.get_history_by_category(&user, &user, &rent, &0, &10);
+    assert_eq!(page.transactions, vec![&env, record(-5, 110, rent)]);
+    assert_eq!(client.checkpoint(&user).record_count, 5);
+}
+
+#[test]
+fn test_history_with_counterparty() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let vendor = Address::generate(&env);
+    let landlord = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, counterparty: Option<Address>| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Payment sent"),
+        category: symbol_short!("payment"),
+        counterparty,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let records = vec![
+        &env,
+        (user.clone(), record(-20, 1, Some(vendor.clone()))),
+        (user.clone(), record(-900, 2, Some(landlord.clone()))),
+        (user.clone(), record(-5, 3, None)),
+        (user.clone(), record(-35, 4, Some(vendor.clone()))),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    let page = client.get_history_with_counterparty(&user, &user, &vendor, &0, &10);
+    assert_eq!(page.total, 2);
+    assert_eq!(
+        page.transactions,
+        vec![
+            &env,
+            record(-20, 1, Some(vendor.clone())),
+            record(-35, 4, Some(vendor.clone())),
+        ]
+    );
+    let page = client.get_history_with_counterparty(&user, &user, &landlord, &0, &10);
+    assert_eq!(
+        page.transactions,
+        vec![&env, record(-900, 2, Some(landlord))]
+    );
+}
+
+#[test]
+fn test_close_month_summarizes_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    // 2024-01-01, 2024-02-01 and 2024-03-01, UTC
+    let (jan, feb, mar) = (1_704_067_200, 1_706_745_600, 1_709_251_200);
+    let record = |amount: i128, timestamp: u64, category| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Card payment"),
+        category,
+        counterparty: None,
+        tx_type: TxType::Other,
+        source_contract: None,
+        batch_id: None,
+    };
+    let food = symbol_short!("food");
+    let salary = symbol_short!("salary");
+    let records = vec![
+        &env,
+        (user.clone(), record(3_000, jan, salary.clone())),
+        (user.clone(), record(-40, jan + 86_400, food.clone())),
+        (user.clone(), record(-60, feb - 1, food.clone())),
+        (user.clone(), record(-25, feb, food.clone())),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    env.ledger().set_timestamp(feb - 1);
+    let result = client.try_close_month(&user, &user, &202401);
+    assert_eq!(result, Err(Ok(HistoryError::PeriodNotOver.into())));
+    let result = client.try_close_month(&user, &user, &202413);
+    assert_eq!(result, Err(Ok(HistoryError::InvalidPeriod.into())));
+
+    env.ledger().set_timestamp(mar);
+    let stranger = Address::generate(&env);
+    let result = client.try_close_month(&stranger, &user, &202401);
+    assert_eq!(result, Err(Ok(HistoryError::Unauthorized.into())));
+
+    // Summaries are listed by period, not by the order months were closed
+    let february = client.close_month(&user, &user, &202402);
+    assert_eq!(february.net_flow, -25);
+    let january = client.close_month(&admin, &user, &202401);
+    assert_eq!(
+        january.category_totals,
+        map![&env, (food.clone(), -100), (salary, 3_000)]
+    );
+    assert_eq!(january.total_in, 3_000);
+    assert_eq!(january.total_out, 100);
+    assert_eq!(january.net_flow, 2_900);
+    assert_eq!(january.record_count, 3);
+    let result = client.try_close_month(&user, &user, &202401);
+    assert_eq!(result, Err(Ok(HistoryError::PeriodAlreadyClosed.into())));
+
+    assert_eq!(
+        client.get_monthly_summaries(&user, &user, &5),
+        vec![&env, january, february.clone()]
+    );
+    assert_eq!(
+        client.get_monthly_summaries(&user, &user, &1),
+        vec![&env, february]
+    );
+}
+
+#[test]
+fn test_history_by_type() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128, timestamp: u64, tx_type| TransactionRecord {
+        amount,
+        timestamp,
+        description: String::from_str(&env, "Savings"),
+        category: symbol_short!("savings"),
+        counterparty: None,
+        tx_type,
+        source_contract: None,
+        batch_id: None,
+    };
+    let records = vec![
+        &env,
+        (user.clone(), record(-50, 1, TxType::GoalContribution)),
+        (user.clone(), record(-20, 2, TxType::BudgetSpend)),
+        (user.clone(), record(-75, 3, TxType::GoalContribution)),
+    ];
+    client.batch_record_transactions(&admin, &records);
+
+    let page = client.get_history_by_type(&user, &user, &TxType::GoalContribution, &0, &10);
+    assert_eq!(page.total, 2);
+    assert_eq!(
+        page.transactions,
+        vec![
+            &env,
+            record(-50, 1, TxType::GoalContribution),
+            record(-75, 3, TxType::GoalContribution),
+        ]
+    );
+    assert_eq!(
+        client
+            .get_history_by_type(&user, &user, &TxType::Conversion, &0, &10)
+            .total,
+        0
+    );
+}
+
+#[test]
+fn test_records_by_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let batch_transfer = Address::generate(&env);
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.initialize(&admin);
+    client.allow_source(&admin, &batch_transfer);
+
+    let record =
+        |amount: i128, batch_id: u64, source_contract: Option<Address>| TransactionRecord {
+            amount,
+            timestamp: 1,
+            description: String::from_str(&env, "Batch transfer"),
+            category: symbol_short!("transfer"),
+            counterparty: None,
+            tx_type: TxType::Transfer,
+            source_contract,
+            batch_id: Some(batch_id),
+        };
+    let records = vec![
+        &env,
+        (sender.clone(), record(-100, 7, None)),
+        (recipient.clone(), record(100, 7, None)),
+        (sender.clone(), record(-40, 8, None)),
+    ];
+    client.batch_record_from_contract(&batch_transfer, &records);
+
+    let stamped = Some(batch_transfer.clone());
+    assert_eq!(
+        client.get_records_by_batch(&admin, &batch_transfer, &7, &0, &10),
+        vec![
+            &env,
+            (sender.clone(), record(-100, 7, stamped.clone())),
+            (recipient, record(100, 7, stamped.clone())),
+        ]
+    );
+    assert_eq!(
+        client.get_records_by_batch(&admin, &batch_transfer, &8, &0, &10),
+        vec![&env, (sender, record(-40, 8, stamped))]
+    );
+    assert_eq!(
+        client
+            .get_records_by_batch(&admin, &batch_transfer, &7, &1, &10)
+            .len(),
+        1
+    );
+    assert!(client
+        .get_records_by_batch(&admin, &admin, &7, &0, &10)
+        .is_empty());
+}
+
+#[test]
+fn test_max_records_per_user_overwrites_oldest() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_max_records_per_user(&admin, &3);
+    assert_eq!(client.get_max_records_per_user(), 3);
+
+    let record = |amount: i128| TransactionRecord {
+        amount,
+        timestamp: amount as u64,
+        description: String::from_str(&env, "Coffee"),
+        category: symbol_short!("food"),
+        counterparty: None,
+        tx_type: TxType::Payment,
+        source_contract: None,
+        batch_id: None,
+    };
+    for amount in 1..=5 {
+        client.record_transaction(&admin, &user, &record(amount));
+    }
+
+    assert_eq!(
+        client
+            .retrieve_history_page(&user, &user, &0, &10)
+            .transactions,
+        vec![&env, record(3), record(4), record(5)]
+    );
+    let stats = client.get_user_stats(&user, &user);
+    assert_eq!(stats.count, 3);
+    assert_eq!(stats.total_in, 12);
+    let page = client.get_history_by_category(&user, &user, &symbol_short!("food"), &0, &10);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.transactions.get(0).unwrap(), record(3));
+
+    client.set_max_records_per_user(&admin, &0);
+    client.record_transaction(&admin, &user, &record(6));
+    assert_eq!(client.get_user_stats(&user, &user).count, 4);
+}
+
+#[test]
+fn test_deleted_records_leave_indexes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let batch_transfer = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.initialize(&admin);
+    client.allow_source(&admin, &batch_transfer);
+    client.set_max_records_per_user(&admin, &2);
+
+    let record = |amount: i128| TransactionRecord {
+        amount,
+        timestamp: amount as u64,
+        description: String::from_str(&env, "Batch transfer"),
+        category: symbol_short!("transfer"),
+        counterparty: None,
+        tx_type: TxType::Transfer,
+        source_contract: None,
+        batch_id: Some(7),
+    };
+    let records = vec![
+        &env,
+        (user.clone(), record(1)),
+        (other.clone(), record(1)),
+        (user.clone(), record(2)),
+        (user.clone(), record(3)),
+    ];
+    client.batch_record_from_contract(&batch_transfer, &records);
+
+    let batch = client.get_records_by_batch(&admin, &batch_transfer, &7, &0, &10);
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch.get(0).unwrap().0, other);
+    assert_eq!(batch.get(1).unwrap().1.amount, 2);
+    assert_eq!(batch.get(2).unwrap().1.amount, 3);
+
+    let (category, batch_entries) = env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        let category: soroban_sdk::Vec<u32> = storage
+            .get(&DataKey::CategoryIndex(
+                user.clone(),
+                symbol_short!("transfer"),
+            ))
+            .unwrap();
+        let batch_entries: soroban_sdk::Vec<(Address, u32)> = storage
+            .get(&DataKey::BatchIndex(batch_transfer.clone(), 7))
+            .unwrap();
+        (category, batch_entries)
+    });
+    assert_eq!(category, vec![&env, 1, 2]);
+    assert_eq!(batch_entries.len(), 3);
+}
+
+#[test]
+fn test_history_pages_are_cached_until_history_changes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchHistoryContract, ());
+    let client = BatchHistoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let record = |amount: i128| TransactionRecord {
+        amount,
+        timestamp: amount as u64,
+        description: String::from_str(&env, "Coffee"),
+        category: symbol_short!("food"),
+        counterparty: None,
+        tx_type: TxType::Payment,
+        source_contract: None,
+        batch_id: None,
+    };
+    client.record_transaction(&admin, &user, &record(1));
+    client.record_transaction(&admin, &user, &record(2));
+
+    let page = client.retrieve_history_page(&user, &user, &0, &10);
+    assert_eq!(page.total, 2);
+    let cached: Option<CachedPage> = env.as_contract(&contract_id, || {
+        env.storage()
+            .temporary()
+            .get(&DataKey::PageCache(user.clone(), 0, 10))
+    });
+    assert_eq!(cached.unwrap().page, page);
+    assert_eq!(client.retrieve_history_page(&user, &user, &0, &10), page);
+
+    // New records invalidate the cached page
+    client.record_transaction(&admin, &user, &record(3));
+    let page = client.retrieve_history_page(&user, &user, &0, &10);
+    assert_eq!(
+        page.transactions,
+        vec![&env, record(1), record(2), record(3)]
+    );
+
+    // So does archiving
+    client.archive_old_records(&admin, &user, &3);
+    let page = client.retrieve_history_page(&user, &user, &0, &10);
+    assert_eq!(page.transactions, vec![&env, record(3)]);
+}
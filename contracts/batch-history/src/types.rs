@@ -1,4 +1,59 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Symbol, Vec};
+
+/// Maximum transaction records returned by a single retrieval call, across
+/// all requested users.
+pub const MAX_RECORDS_PER_CALL: u32 = 50;
+
+/// Maximum records moved into one archive chunk per `archive_old_records`
+/// call, keeping each chunk within ledger entry size limits.
+pub const MAX_RECORDS_PER_ARCHIVE: u32 = 100;
+
+/// Maximum records deleted per `prune_history` call.
+pub const MAX_RECORDS_PER_PRUNE: u32 = 100;
+
+/// Maximum records folded into the hash chain per `checkpoint` call.
+pub const MAX_RECORDS_PER_CHECKPOINT: u32 = 100;
+
+/// Maximum records encoded by a single `export_history` call.
+pub const MAX_RECORDS_PER_EXPORT: u32 = 100;
+
+/// Maximum length in bytes of a record description.
+pub const MAX_DESCRIPTION_LEN: u32 = 256;
+
+/// Version byte leading every `export_history` blob.
+///
+/// Layout, all integers big-endian:
+/// - header: version `u8`, record count `u32`
+/// - per record: amount `i128` (16 bytes), timestamp `u64` (8 bytes),
+///   category length `u8` + UTF-8 bytes, description length `u16` + UTF-8
+///   bytes, `tx_type` as its variant index `u8`, counterparty and source
+///   contract each as length `u8` + `ScAddress` XDR bytes (length 0 when
+///   absent), batch ID as a presence flag `u8` followed by a `u64` when set
+pub const EXPORT_FORMAT_VERSION: u8 = 2;
+
+/// Maximum monthly summaries returned by `get_monthly_summaries`.
+pub const MAX_SUMMARIES_PER_CALL: u32 = 24;
+
+/// Ledgers a cached history page stays in temporary storage (~10 minutes).
+pub const PAGE_CACHE_TTL_LEDGERS: u32 = 120;
+
+/// Ledgers before expiry at which a stored history entry's TTL is extended.
+pub const HISTORY_TTL_THRESHOLD: u32 = 100_000;
+
+/// TTL, in ledgers, given to stored history entries (~30 days).
+pub const HISTORY_TTL_EXTEND: u32 = 518_400;
+
+/// Kind of transaction a record describes.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxType {
+    Transfer,
+    Payment,
+    Conversion,
+    GoalContribution,
+    BudgetSpend,
+    Other,
+}
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -6,11 +61,139 @@ pub struct TransactionRecord {
     pub amount: i128,
     pub timestamp: u64,
     pub description: String,
+    /// Spend classification, e.g. `food` or `transfer`
+    pub category: Symbol,
+    /// Other party to the transaction, e.g. the payee of a payment
+    pub counterparty: Option<Address>,
+    pub tx_type: TxType,
+    /// Contract that recorded it; stamped by `record_from_contract`
+    pub source_contract: Option<Address>,
+    /// Batch of the source contract the transaction settled in
+    pub batch_id: Option<u64>,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UserHistory {
     pub user: Address,
+    /// The requested page of records, oldest first
     pub transactions: Vec<TransactionRecord>,
+    /// Records stored for the user, for paging through the rest
+    pub total: u32,
+}
+
+/// Running totals over all of a user's records.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserStats {
+    /// Records stored for the user, excluding pruned ones
+    pub count: u32,
+    /// Sum of incoming (positive) amounts
+    pub total_in: i128,
+    /// Sum of outgoing amounts, as a positive magnitude
+    pub total_out: i128,
+    /// Mean absolute amount per record
+    pub average_amount: i128,
+    /// Latest record timestamp
+    pub last_activity: u64,
+}
+
+/// Where a user's archived records live.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchiveInfo {
+    /// Records moved out of recent history; they precede every recent record
+    pub archived: u32,
+    /// Records deleted by pruning; positions below this no longer exist
+    pub pruned: u32,
+    /// Position of the first record in each archive chunk, by chunk ID
+    pub chunk_starts: Vec<u32>,
+}
+
+/// Head of a user's hash chain over their records.
+///
+/// Each `checkpoint` call folds the records recorded since the previous one
+/// in: `hash = sha256(prev_hash || xdr(Vec<TransactionRecord>))`, starting
+/// from 32 zero bytes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub hash: BytesN<32>,
+    /// Record positions covered by the chain; records pruned before being
+    /// checkpointed are skipped
+    pub record_count: u32,
+    /// Ledger timestamp of the latest checkpoint
+    pub timestamp: u64,
+}
+
+/// A user's totals for one calendar month (UTC).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MonthlySummary {
+    /// Month as `YYYYMM`, e.g. `202403`
+    pub period: u32,
+    /// Net (signed) amount per category
+    pub category_totals: Map<Symbol, i128>,
+    pub total_in: i128,
+    /// Outgoing amounts, as a positive magnitude
+    pub total_out: i128,
+    /// `total_in - total_out`
+    pub net_flow: i128,
+    pub record_count: u32,
+}
+
+/// A history page cached in temporary storage.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedPage {
+    /// User's history version the page was assembled at
+    pub version: u32,
+    pub page: UserHistory,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Contract administrator
+    Admin,
+    /// Address allowed to record transactions besides the admin
+    Writer(Address),
+    /// Ledger timestamp until which an address may read any user's history
+    Auditor(Address),
+    /// A user's recent transaction records, oldest first
+    History(Address),
+    /// Bumped whenever a user's recent history changes, invalidating cached
+    /// pages
+    HistoryVersion(Address),
+    /// Cached `(offset, limit)` page of a user's recent history (temporary)
+    PageCache(Address, u32, u32),
+    /// Locations of a user's archived records
+    ArchiveInfo(Address),
+    /// One compacted chunk of a user's archived records, by chunk ID
+    Archive(Address, u32),
+    /// Sibling contract allowed to call `record_from_contract`
+    AllowedSource(Address),
+    /// A user's aggregate statistics
+    Stats(Address),
+    /// Latest checkpoint of a user's records
+    Checkpoint(Address),
+    /// Users and positions of the records from a source contract's batch
+    BatchIndex(Address, u64),
+    /// Running totals of a month not yet closed, by `YYYYMM` period
+    MonthTotals(Address, u32),
+    /// Closed monthly summary, by `YYYYMM` period
+    MonthlySummary(Address, u32),
+    /// Periods of a user's closed months, in closing order
+    ClosedMonths(Address),
+    /// Records kept per user before the oldest are overwritten
+    MaxRecordsPerUser,
+    /// Seconds a record must be kept before it may be pruned
+    MinRetention,
+    /// Positions (counting archived records) of a user's records in a category
+    CategoryIndex(Address, Symbol),
+    /// Positions (counting archived records) of a user's records of a type
+    TypeIndex(Address, TxType),
+    /// Positions (counting archived records) of a user's records with a
+    /// counterparty
+    CounterpartyIndex(Address, Address),
 }
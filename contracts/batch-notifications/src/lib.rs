@@ -1,5 +1,7 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, panic_with_error, Address, Env, String, Symbol, Vec,
+};
 
 mod logic;
 mod types;
@@ -7,13 +9,129 @@ mod types;
 #[cfg(test)]
 mod test;
 
-use crate::types::{BatchResult, NotificationPayload};
+use crate::types::{
+    BatchResult, Broadcast, DataKey, Notification, NotificationPayload, PendingDigest, Preferences,
+    RateLimits, ScheduledNotifications, Template, BROADCAST_CHUNK_SIZE, MAX_SCHEDULE_LEDGERS,
+};
+
+/// Error codes for the batch notification contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum NotificationError {
+    /// Contract has not been initialized
+    NotInitialized = 1,
+    /// Contract was already initialized
+    AlreadyInitialized = 2,
+    /// Caller is neither the admin nor an operator
+    Unauthorized = 3,
+    /// Another broadcast is still being delivered
+    BroadcastInProgress = 4,
+    /// There is no broadcast to continue
+    NoActiveBroadcast = 5,
+    /// Message or template is empty
+    EmptyMessage = 6,
+    /// Message or template exceeds `MAX_MESSAGE_LEN`
+    MessageTooLong = 7,
+    /// Template does not exist
+    TemplateNotFound = 8,
+    /// Scheduled batch has no payloads
+    EmptyBatch = 9,
+    /// Delivery ledger must be in the future
+    InvalidDeliveryLedger = 10,
+    /// Calling contract is not on the source allowlist
+    SourceNotAllowed = 11,
+    /// Notification is not in the user's inbox
+    NotificationNotFound = 12,
+    /// Notification was already acknowledged
+    AlreadyAcknowledged = 13,
+    /// Batch exceeds the configured `max_batch_size`
+    BatchTooLarge = 14,
+    /// Rate limits must be non-zero and admit a full broadcast chunk
+    InvalidRateLimits = 15,
+    /// User already received `max_per_user` notifications this window
+    RateLimited = 16,
+    /// Message contains control characters
+    InvalidCharacters = 17,
+    /// Parameters do not fit the template's placeholders
+    TemplateMismatch = 18,
+    /// No digest window is configured
+    DigestDisabled = 19,
+    /// Delegated sender may not notify about this category
+    CategoryNotAllowed = 20,
+    /// Structured body has invalid amounts or is a reserved `Digest`
+    InvalidBody = 21,
+    /// User's inbox already holds `MAX_INBOX_SIZE` notifications
+    InboxFull = 22,
+    /// User's pending digest already holds `MAX_DIGEST_ITEMS` notifications
+    DigestFull = 23,
+    /// Delivery ledger is more than `MAX_SCHEDULE_LEDGERS` ahead
+    ScheduleTooFar = 24,
+}
 
 #[contract]
 pub struct BatchNotificationContract;
 
 #[contractimpl]
 impl BatchNotificationContract {
+    /// Sets the admin allowed to dispatch notifications and manage operators.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, NotificationError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Allows `operator` (e.g. a payout contract) to dispatch notifications.
+    pub fn add_operator(env: Env, admin: Address, operator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Operator(operator), &true);
+    }
+
+    /// Revokes an operator's dispatch rights.
+    pub fn remove_operator(env: Env, admin: Address, operator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Operator(operator));
+    }
+
+    /// Lets `sender` (e.g. an internal service) dispatch notifications in
+    /// `allowed_categories` only. Calling again replaces the categories.
+    pub fn add_sender(env: Env, admin: Address, sender: Address, allowed_categories: Vec<Symbol>) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Sender(sender), &allowed_categories);
+    }
+
+    /// Revokes a delegated sender.
+    pub fn remove_sender(env: Env, admin: Address, sender: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().persistent().remove(&DataKey::Sender(sender));
+    }
+
+    /// Returns the categories a delegated sender may use, if registered.
+    pub fn get_sender_categories(env: Env, sender: Address) -> Option<Vec<Symbol>> {
+        env.storage().persistent().get(&DataKey::Sender(sender))
+    }
+
+    /// Returns true if `address` is a registered operator.
+    pub fn is_operator(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Operator(address))
+    }
+
     pub fn batch_notify(
         env: Env,
         admin: Address,
@@ -21,8 +139,411 @@ impl BatchNotificationContract {
     ) -> BatchResult {
         // Requirement: Validate user/admin addresses
         admin.require_auth();
+        let allowed = Self::authorize_sender(&env, &admin);
 
         // Run the batch logic
-        logic::execute_dispatch(env, payloads)
+        logic::execute_dispatch(env.clone(), &admin, &allowed, payloads)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+    }
+
+    /// Caps how many notifications a user receives per `window_ledgers` and
+    /// how many payloads a single dispatch call may carry.
+    pub fn set_rate_limits(env: Env, admin: Address, limits: RateLimits) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if limits.max_per_user == 0
+            || limits.window_ledgers == 0
+            || limits.max_batch_size < BROADCAST_CHUNK_SIZE
+        {
+            panic_with_error!(&env, NotificationError::InvalidRateLimits);
+        }
+
+        env.storage().instance().set(&DataKey::RateLimits, &limits);
+    }
+
+    /// Removes all dispatch limits.
+    pub fn clear_rate_limits(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::RateLimits);
+    }
+
+    /// Returns the active dispatch limits, if any.
+    pub fn get_rate_limits(env: Env) -> Option<RateLimits> {
+        env.storage().instance().get(&DataKey::RateLimits)
+    }
+
+    /// Allows a sibling contract (e.g. budget-allocation, savings-goals or
+    /// batch-transfer) to publish through `notify_from_contract`.
+    pub fn allow_source(env: Env, admin: Address, source_contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::AllowedSource(source_contract), &true);
+    }
+
+    /// Removes a contract from the source allowlist.
+    pub fn disallow_source(env: Env, admin: Address, source_contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedSource(source_contract));
+    }
+
+    /// Returns true if `source_contract` may call `notify_from_contract`.
+    pub fn is_allowed_source(env: Env, source_contract: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::AllowedSource(source_contract))
+    }
+
+    /// Publishes notifications on behalf of an allowlisted sibling contract.
+    /// `source_contract` must be the invoking contract.
+    pub fn notify_from_contract(
+        env: Env,
+        source_contract: Address,
+        payloads: Vec<NotificationPayload>,
+    ) -> BatchResult {
+        source_contract.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::AllowedSource(source_contract.clone()))
+        {
+            panic_with_error!(&env, NotificationError::SourceNotAllowed);
+        }
+
+        logic::execute_dispatch(env.clone(), &source_contract, &None, payloads)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+    }
+
+    /// Queues notifications for delivery from `deliver_after_ledger` onward,
+    /// at most `MAX_SCHEDULE_LEDGERS` ahead. Queued payloads are dispatched by
+    /// `deliver_due`.
+    pub fn schedule_notifications(
+        env: Env,
+        admin: Address,
+        payloads: Vec<NotificationPayload>,
+        deliver_after_ledger: u32,
+    ) -> u64 {
+        admin.require_auth();
+        let allowed = Self::authorize_sender(&env, &admin);
+
+        if payloads.is_empty() {
+            panic_with_error!(&env, NotificationError::EmptyBatch);
+        }
+        if deliver_after_ledger <= env.ledger().sequence() {
+            panic_with_error!(&env, NotificationError::InvalidDeliveryLedger);
+        }
+        if deliver_after_ledger - env.ledger().sequence() > MAX_SCHEDULE_LEDGERS {
+            panic_with_error!(&env, NotificationError::ScheduleTooFar);
+        }
+        logic::check_batch_size(&env, &payloads)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+        for payload in payloads.iter() {
+            if !logic::category_allowed(&allowed, &payload.category) {
+                panic_with_error!(&env, NotificationError::CategoryNotAllowed);
+            }
+        }
+
+        logic::schedule(env, admin, payloads, deliver_after_ledger)
+    }
+
+    /// Delivers up to `limit` scheduled batches whose delivery ledger has
+    /// been reached. Callable by anyone so a keeper can drive delivery.
+    pub fn deliver_due(env: Env, limit: u32) -> u32 {
+        logic::deliver_due(env, limit)
+    }
+
+    /// Returns a scheduled batch that has not been delivered yet.
+    pub fn get_scheduled(env: Env, schedule_id: u64) -> Option<ScheduledNotifications> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Scheduled(schedule_id))
+    }
+
+    /// Enables digest mode: notifications sent with `queue_digest` are
+    /// bundled per user and delivered once every `window_ledgers`.
+    pub fn set_digest_window(env: Env, admin: Address, window_ledgers: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if window_ledgers == 0 {
+            env.storage().instance().remove(&DataKey::DigestWindow);
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::DigestWindow, &window_ledgers);
+        }
+    }
+
+    /// Returns the digest window in ledgers, if digest mode is enabled.
+    pub fn get_digest_window(env: Env) -> Option<u32> {
+        env.storage().instance().get(&DataKey::DigestWindow)
+    }
+
+    /// Queues notifications into each recipient's pending digest. Intended
+    /// for chatty integrations whose messages need not arrive individually.
+    /// Each queued notification counts against the recipient's rate limit.
+    pub fn queue_digest(
+        env: Env,
+        admin: Address,
+        payloads: Vec<NotificationPayload>,
+    ) -> BatchResult {
+        admin.require_auth();
+        let allowed = Self::authorize_sender(&env, &admin);
+
+        if !env.storage().instance().has(&DataKey::DigestWindow) {
+            panic_with_error!(&env, NotificationError::DigestDisabled);
+        }
+        logic::check_batch_size(&env, &payloads)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        logic::queue_digest(env, &admin, &allowed, payloads)
+    }
+
+    /// Delivers up to `limit` digests whose window has elapsed. Callable by
+    /// anyone so a keeper can drive delivery.
+    pub fn flush_digests(env: Env, limit: u32) -> u32 {
+        let window_ledgers: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DigestWindow)
+            .unwrap_or_else(|| panic_with_error!(&env, NotificationError::DigestDisabled));
+
+        logic::flush_digests(env, window_ledgers, limit)
+    }
+
+    /// Returns the notifications waiting in a user's next digest.
+    pub fn get_pending_digest(env: Env, user: Address) -> Option<PendingDigest> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingDigest(user))
+    }
+
+    /// Stores (or replaces) a message template, e.g.
+    /// `"Your budget for {month} is {amount}"`.
+    pub fn create_template(
+        env: Env,
+        admin: Address,
+        template_id: Symbol,
+        text: String,
+        category: Symbol,
+        priority: u32,
+    ) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        logic::validate_message(&text).unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        let template = Template {
+            text,
+            category,
+            priority,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Template(template_id), &template);
+    }
+
+    /// Returns a message template by ID.
+    pub fn get_template(env: Env, template_id: Symbol) -> Option<Template> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Template(template_id))
+    }
+
+    /// Sends a templated message to each recipient, filling the template's
+    /// placeholders in order from that recipient's parameters. Recipients
+    /// whose parameters do not fit the template are reported as failed with
+    /// `TemplateMismatch`.
+    pub fn batch_notify_from_template(
+        env: Env,
+        admin: Address,
+        template_id: Symbol,
+        recipients: Vec<(Address, Vec<String>)>,
+    ) -> BatchResult {
+        admin.require_auth();
+        let allowed = Self::authorize_sender(&env, &admin);
+
+        let template: Template = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Template(template_id))
+            .unwrap_or_else(|| panic_with_error!(&env, NotificationError::TemplateNotFound));
+
+        let (payloads, failures) = logic::render_payloads(&env, &template, recipients);
+        let mut result = logic::execute_dispatch(env.clone(), &admin, &allowed, payloads)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+        result.failures.append(&failures);
+        result
+    }
+
+    /// Adds the caller to the broadcast registry. Users are also registered
+    /// automatically when they receive their first notification.
+    pub fn register(env: Env, user: Address) {
+        user.require_auth();
+
+        logic::register_user(&env, &user)
+    }
+
+    /// Returns the number of registered users.
+    pub fn get_user_count(env: Env) -> u32 {
+        logic::user_count(&env)
+    }
+
+    /// Starts an announcement to every registered user and delivers the first
+    /// chunk. Returns the number of users still to be notified; the rest are
+    /// reached with `continue_broadcast`.
+    pub fn broadcast(env: Env, admin: Address, message: String, category: Symbol) -> u32 {
+        admin.require_auth();
+        let allowed = Self::authorize_sender(&env, &admin);
+
+        if env.storage().instance().has(&DataKey::ActiveBroadcast) {
+            panic_with_error!(&env, NotificationError::BroadcastInProgress);
+        }
+        if !logic::category_allowed(&allowed, &category) {
+            panic_with_error!(&env, NotificationError::CategoryNotAllowed);
+        }
+        logic::validate_message(&message).unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        let broadcast = Broadcast {
+            sender: admin,
+            message,
+            category,
+            cursor: 0,
+            total: logic::user_count(&env),
+        };
+        logic::deliver_broadcast_chunk(env, broadcast)
+    }
+
+    /// Delivers the next chunk of the active broadcast and returns the number
+    /// of users still to be notified.
+    pub fn continue_broadcast(env: Env, admin: Address) -> u32 {
+        admin.require_auth();
+        Self::authorize_sender(&env, &admin);
+
+        let broadcast: Broadcast = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveBroadcast)
+            .unwrap_or_else(|| panic_with_error!(&env, NotificationError::NoActiveBroadcast));
+        logic::deliver_broadcast_chunk(env, broadcast)
+    }
+
+    /// Returns the broadcast still being delivered, if any.
+    pub fn get_active_broadcast(env: Env) -> Option<Broadcast> {
+        env.storage().instance().get(&DataKey::ActiveBroadcast)
+    }
+
+    /// Records that the user received a notification, giving compliance
+    /// notices provable receipt. Returns the acknowledgment timestamp.
+    pub fn acknowledge(env: Env, user: Address, notification_id: u64) -> u64 {
+        user.require_auth();
+
+        logic::acknowledge(env.clone(), user, notification_id)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+    }
+
+    /// Permanently removes notifications from the user's inbox.
+    pub fn delete_notifications(env: Env, user: Address, ids: Vec<u64>) -> u32 {
+        user.require_auth();
+
+        logic::delete_notifications(env, user, ids)
+    }
+
+    /// Permanently removes every notification in the user's inbox.
+    pub fn clear_inbox(env: Env, user: Address) -> u32 {
+        user.require_auth();
+
+        logic::clear_inbox(env, user)
+    }
+
+    /// Drops expired notifications from a user's inbox. Callable by anyone.
+    pub fn prune_expired(env: Env, user: Address, limit: u32) -> u32 {
+        logic::prune_expired(env, user, limit)
+    }
+
+    /// Opts the user in to (`true`) or out of (`false`) notification
+    /// categories. Categories never set are delivered.
+    pub fn set_preferences(env: Env, user: Address, preferences: Vec<(Symbol, bool)>) {
+        user.require_auth();
+
+        logic::set_preferences(env, user, preferences)
+    }
+
+    /// Returns the user's category preferences.
+    pub fn get_preferences(env: Env, user: Address) -> Preferences {
+        logic::get_preferences(env, user)
+    }
+
+    /// Returns a page of the user's unexpired notifications, oldest first.
+    pub fn get_notifications(
+        env: Env,
+        user: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Notification> {
+        logic::get_notifications(env, user, offset, limit)
+    }
+
+    /// Returns a page of the user's unexpired notifications in one category.
+    pub fn get_notifications_by_category(
+        env: Env,
+        user: Address,
+        category: Symbol,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Notification> {
+        logic::get_notifications_by_category(env, user, category, offset, limit)
+    }
+
+    /// Marks notifications in the user's inbox as read.
+    pub fn mark_read(env: Env, user: Address, ids: Vec<u64>) -> u32 {
+        user.require_auth();
+
+        logic::mark_read(env, user, ids)
+    }
+
+    fn stored_admin(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(env, NotificationError::NotInitialized))
+    }
+
+    fn require_admin(env: &Env, caller: &Address) {
+        if *caller != Self::stored_admin(env) {
+            panic_with_error!(env, NotificationError::Unauthorized);
+        }
+    }
+
+    // Admins and operators may use any category (`None`); delegated senders
+    // only their own.
+    fn authorize_sender(env: &Env, caller: &Address) -> Option<Vec<Symbol>> {
+        if *caller == Self::stored_admin(env)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Operator(caller.clone()))
+        {
+            return None;
+        }
+
+        match env
+            .storage()
+            .persistent()
+            .get(&DataKey::Sender(caller.clone()))
+        {
+            Some(categories) => Some(categories),
+            None => panic_with_error!(env, NotificationError::Unauthorized),
+        }
     }
 }
@@ -1,28 +1,813 @@
-use crate::types::{BatchResult, NotificationPayload};
-use soroban_sdk::{symbol_short, Env, Vec};
+use crate::types::{
+    BatchResult, Broadcast, DataKey, DigestItem, FailedNotification, Notification,
+    NotificationBody, NotificationPayload, PendingDigest, Preferences, RateLimits,
+    ScheduledNotifications, Template, UserQuota, BROADCAST_CHUNK_SIZE, INBOX_TTL_LEDGERS,
+    MAX_DIGEST_ITEMS, MAX_INBOX_SIZE, MAX_MESSAGE_LEN, NOTIFICATION_LIFETIME, SCHEDULE_TTL_BUFFER,
+};
+use crate::NotificationError;
+use soroban_sdk::{symbol_short, Address, Env, String, Symbol, Vec};
 
-pub fn execute_dispatch(env: Env, payloads: Vec<NotificationPayload>) -> BatchResult {
+/// Dispatches a caller-submitted batch, rejecting it outright if it exceeds
+/// the configured `max_batch_size`.
+/// `allowed` restricts the categories a delegated sender may use; `None`
+/// means unrestricted.
+pub fn execute_dispatch(
+    env: Env,
+    sender: &Address,
+    allowed: &Option<Vec<Symbol>>,
+    payloads: Vec<NotificationPayload>,
+) -> Result<BatchResult, NotificationError> {
+    check_batch_size(&env, &payloads)?;
+    Ok(dispatch(env, sender, allowed, payloads))
+}
+
+pub fn check_batch_size(
+    env: &Env,
+    payloads: &Vec<NotificationPayload>,
+) -> Result<(), NotificationError> {
+    match rate_limits(env) {
+        Some(limits) if payloads.len() > limits.max_batch_size => {
+            Err(NotificationError::BatchTooLarge)
+        }
+        _ => Ok(()),
+    }
+}
+
+// Delivers each payload, skipping opted-out users and reporting failures.
+// Batch size is checked by the caller; per-user limits are applied here.
+fn dispatch(
+    env: Env,
+    sender: &Address,
+    allowed: &Option<Vec<Symbol>>,
+    payloads: Vec<NotificationPayload>,
+) -> BatchResult {
+    let limits = rate_limits(&env);
     let mut success_count = 0;
+    let mut suppressed_count = 0;
     let mut failures = Vec::new(&env);
 
     for payload in payloads.iter() {
-        // Requirement: Handle partial failure gracefully
-        // We consider an empty message a "soft failure" instead of panicking
-        if !payload.message.is_empty() {
-            // Requirement: Emit events for notification delivery
+        if !category_allowed(allowed, &payload.category) {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: NotificationError::CategoryNotAllowed as u32,
+            });
+            continue;
+        }
+
+        // Honor opt-outs before anything is stored or emitted
+        if !is_opted_in(&env, &payload.user, &payload.category) {
             env.events().publish(
-                (symbol_short!("notif"), payload.user.clone()),
-                payload.message,
+                (Symbol::new(&env, "suppressed"), payload.user.clone()),
+                payload.category,
             );
-            success_count += 1;
-        } else {
-            // If it fails, add the user to the failure list
-            failures.push_back(payload.user);
+            suppressed_count += 1;
+            continue;
+        }
+
+        // Requirement: Handle partial failure gracefully
+        // Invalid bodies are reported per payload instead of panicking
+        if let Err(error) = validate_body(&payload.body) {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: error as u32,
+            });
+            continue;
+        }
+        if inbox(&env, &payload.user).len() >= MAX_INBOX_SIZE {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: NotificationError::InboxFull as u32,
+            });
+            continue;
         }
+
+        if let Some(limits) = &limits {
+            if !take_quota(&env, &payload.user, limits) {
+                env.events().publish(
+                    (Symbol::new(&env, "rate_limited"), payload.user.clone()),
+                    payload.category,
+                );
+                failures.push_back(FailedNotification {
+                    user: payload.user,
+                    error: NotificationError::RateLimited as u32,
+                });
+                continue;
+            }
+        }
+
+        store_notification(&env, sender, &payload);
+
+        // Requirement: Emit events for notification delivery
+        env.events().publish(
+            (
+                symbol_short!("notif"),
+                payload.user.clone(),
+                payload.category.clone(),
+            ),
+            (sender.clone(), payload.body, payload.priority),
+        );
+        success_count += 1;
     }
 
     BatchResult {
         successful_count: success_count,
-        failed_addresses: failures,
+        failures,
+        suppressed_count,
+    }
+}
+
+/// Returns false if a delegated sender may not use `category`.
+pub fn category_allowed(allowed: &Option<Vec<Symbol>>, category: &Symbol) -> bool {
+    match allowed {
+        Some(categories) => categories.contains(category),
+        None => true,
+    }
+}
+
+fn rate_limits(env: &Env) -> Option<RateLimits> {
+    env.storage().instance().get(&DataKey::RateLimits)
+}
+
+// Counts one delivery against the user's window; false once the window is full.
+fn take_quota(env: &Env, user: &Address, limits: &RateLimits) -> bool {
+    let now = env.ledger().sequence();
+    let key = DataKey::UserQuota(user.clone());
+    let mut quota: UserQuota = env
+        .storage()
+        .temporary()
+        .get(&key)
+        .filter(|quota: &UserQuota| now < quota.window_start + limits.window_ledgers)
+        .unwrap_or(UserQuota {
+            window_start: now,
+            sent: 0,
+        });
+
+    if quota.sent >= limits.max_per_user {
+        return false;
+    }
+
+    quota.sent += 1;
+    env.storage().temporary().set(&key, &quota);
+    env.storage()
+        .temporary()
+        .extend_ttl(&key, limits.window_ledgers, limits.window_ledgers);
+    true
+}
+
+/// Returns up to `limit` unexpired notifications from `user`'s inbox,
+/// skipping the first `offset`.
+pub fn get_notifications(env: Env, user: Address, offset: u32, limit: u32) -> Vec<Notification> {
+    live_page(&env, &user, None, offset, limit)
+}
+
+/// Returns up to `limit` unexpired notifications of one category from
+/// `user`'s inbox, skipping the first `offset` matches.
+pub fn get_notifications_by_category(
+    env: Env,
+    user: Address,
+    category: Symbol,
+    offset: u32,
+    limit: u32,
+) -> Vec<Notification> {
+    live_page(&env, &user, Some(&category), offset, limit)
+}
+
+// Pages through the inbox, leaving out notifications that have expired but
+// not been pruned yet.
+fn live_page(
+    env: &Env,
+    user: &Address,
+    category: Option<&Symbol>,
+    offset: u32,
+    limit: u32,
+) -> Vec<Notification> {
+    let now = env.ledger().timestamp();
+    let mut page = Vec::new(env);
+    let mut skipped = 0;
+
+    for notification in inbox(env, user).iter() {
+        if page.len() >= limit {
+            break;
+        }
+        if notification.expires_at <= now
+            || category.is_some_and(|category| notification.category != *category)
+        {
+            continue;
+        }
+        if skipped < offset {
+            skipped += 1;
+            continue;
+        }
+        page.push_back(notification);
+    }
+    page
+}
+
+/// Marks the given notifications as read and returns how many changed.
+pub fn mark_read(env: Env, user: Address, ids: Vec<u64>) -> u32 {
+    let mut inbox = inbox(&env, &user);
+    let mut marked = 0;
+
+    for index in 0..inbox.len() {
+        let mut notification = inbox.get(index).unwrap();
+        if !notification.read && ids.contains(notification.id) {
+            notification.read = true;
+            inbox.set(index, notification);
+            marked += 1;
+        }
+    }
+
+    if marked > 0 {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Inbox(user), &inbox);
+    }
+    marked
+}
+
+/// Records the user's acknowledgment of a notification (also marking it read)
+/// and returns the acknowledgment timestamp.
+pub fn acknowledge(env: Env, user: Address, id: u64) -> Result<u64, NotificationError> {
+    let mut inbox = inbox(&env, &user);
+    let index = inbox
+        .iter()
+        .position(|notification| notification.id == id)
+        .ok_or(NotificationError::NotificationNotFound)? as u32;
+
+    let mut notification = inbox.get(index).unwrap();
+    if notification.acknowledged_at.is_some() {
+        return Err(NotificationError::AlreadyAcknowledged);
+    }
+
+    let now = env.ledger().timestamp();
+    notification.acknowledged_at = Some(now);
+    notification.read = true;
+    inbox.set(index, notification);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Inbox(user.clone()), &inbox);
+
+    env.events()
+        .publish((symbol_short!("notif_ack"), user), (id, now));
+    Ok(now)
+}
+
+/// Removes up to `limit` expired notifications from `user`'s inbox and
+/// returns how many were removed.
+pub fn prune_expired(env: Env, user: Address, limit: u32) -> u32 {
+    let mut inbox = inbox(&env, &user);
+    let now = env.ledger().timestamp();
+    let mut pruned = 0;
+
+    // Notifications share one lifetime, so expired ones are always oldest
+    while pruned < limit {
+        match inbox.first() {
+            Some(notification) if notification.expires_at <= now => {
+                inbox.pop_front();
+                pruned += 1;
+            }
+            _ => break,
+        }
+    }
+
+    let key = DataKey::Inbox(user);
+    if inbox.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else if pruned > 0 {
+        env.storage().persistent().set(&key, &inbox);
+    }
+    pruned
+}
+
+/// Removes the listed notifications from `user`'s inbox and returns how many
+/// were removed.
+pub fn delete_notifications(env: Env, user: Address, ids: Vec<u64>) -> u32 {
+    let inbox = inbox(&env, &user);
+    let mut kept = Vec::new(&env);
+    let mut deleted = Vec::new(&env);
+
+    for notification in inbox.iter() {
+        if ids.contains(notification.id) {
+            deleted.push_back(notification.id);
+        } else {
+            kept.push_back(notification);
+        }
+    }
+
+    if deleted.is_empty() {
+        return 0;
+    }
+
+    let key = DataKey::Inbox(user.clone());
+    if kept.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &kept);
+    }
+
+    let count = deleted.len();
+    env.events()
+        .publish((symbol_short!("notif_del"), user), deleted);
+    count
+}
+
+/// Deletes `user`'s whole inbox and returns how many notifications it held.
+pub fn clear_inbox(env: Env, user: Address) -> u32 {
+    let count = inbox(&env, &user).len();
+    if count == 0 {
+        return 0;
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Inbox(user.clone()));
+    env.events()
+        .publish((symbol_short!("inbox_clr"), user), count);
+    count
+}
+
+/// Stores the user's opt-in (`true`) or opt-out (`false`) per category.
+pub fn set_preferences(env: Env, user: Address, preferences: Vec<(Symbol, bool)>) {
+    let mut stored = get_preferences(env.clone(), user.clone());
+    for (category, enabled) in preferences.iter() {
+        stored.set(category, enabled);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Preferences(user), &stored);
+}
+
+/// Returns the user's stored category preferences.
+pub fn get_preferences(env: Env, user: Address) -> Preferences {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Preferences(user))
+        .unwrap_or(Preferences::new(&env))
+}
+
+fn is_opted_in(env: &Env, user: &Address, category: &Symbol) -> bool {
+    get_preferences(env.clone(), user.clone())
+        .get(category.clone())
+        .unwrap_or(true)
+}
+
+/// Builds one text payload per recipient from a template. Recipients whose
+/// message cannot be rendered are returned as failures.
+pub fn render_payloads(
+    env: &Env,
+    template: &Template,
+    recipients: Vec<(Address, Vec<String>)>,
+) -> (Vec<NotificationPayload>, Vec<FailedNotification>) {
+    let mut payloads = Vec::new(env);
+    let mut failures = Vec::new(env);
+    for (user, params) in recipients.iter() {
+        match render(env, &template.text, &params) {
+            Ok(message) => payloads.push_back(NotificationPayload {
+                user,
+                body: NotificationBody::Text(message),
+                category: template.category.clone(),
+                priority: template.priority,
+            }),
+            Err(error) => failures.push_back(FailedNotification {
+                user,
+                error: error as u32,
+            }),
+        }
+    }
+    (payloads, failures)
+}
+
+// Fills each `{...}` placeholder with the next parameter. Fails with
+// `TemplateMismatch` if a placeholder is unterminated or the parameters do
+// not match the placeholders one to one, and with `MessageTooLong` if the
+// template or result is too long.
+fn render(env: &Env, text: &String, params: &Vec<String>) -> Result<String, NotificationError> {
+    const MAX: usize = MAX_MESSAGE_LEN as usize;
+    let len = text.len() as usize;
+    if len > MAX {
+        return Err(NotificationError::MessageTooLong);
+    }
+    let mut source = [0u8; MAX];
+    text.copy_into_slice(&mut source[..len]);
+
+    let mut out = [0u8; MAX];
+    let mut out_len = 0;
+    let mut next_param = 0;
+    let mut i = 0;
+    while i < len {
+        if source[i] == b'{' {
+            let close = i + source[i..len]
+                .iter()
+                .position(|b| *b == b'}')
+                .ok_or(NotificationError::TemplateMismatch)?;
+            let param = params
+                .get(next_param)
+                .ok_or(NotificationError::TemplateMismatch)?;
+            next_param += 1;
+
+            let param_len = param.len() as usize;
+            if out_len + param_len > MAX {
+                return Err(NotificationError::MessageTooLong);
+            }
+            param.copy_into_slice(&mut out[out_len..out_len + param_len]);
+            out_len += param_len;
+            i = close + 1;
+        } else {
+            if out_len == MAX {
+                return Err(NotificationError::MessageTooLong);
+            }
+            out[out_len] = source[i];
+            out_len += 1;
+            i += 1;
+        }
+    }
+
+    if next_param != params.len() {
+        return Err(NotificationError::TemplateMismatch);
+    }
+    Ok(String::from_bytes(env, &out[..out_len]))
+}
+
+/// Checks a payload body: text must pass `validate_message` and structured
+/// amounts must be positive.
+pub fn validate_body(body: &NotificationBody) -> Result<(), NotificationError> {
+    let valid = match body {
+        NotificationBody::Text(message) => return validate_message(message),
+        NotificationBody::BudgetAlert(alert) => alert.spent >= 0 && alert.limit > 0,
+        NotificationBody::PaymentReceived(payment) => payment.amount > 0,
+        NotificationBody::GoalReached(goal) => goal.target > 0,
+        NotificationBody::Digest(_) => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(NotificationError::InvalidBody)
+    }
+}
+
+/// Rejects empty or oversized messages and messages with control characters.
+pub fn validate_message(message: &String) -> Result<(), NotificationError> {
+    const MAX: usize = MAX_MESSAGE_LEN as usize;
+    let len = message.len() as usize;
+    if len == 0 {
+        return Err(NotificationError::EmptyMessage);
+    }
+    if len > MAX {
+        return Err(NotificationError::MessageTooLong);
+    }
+
+    let mut bytes = [0u8; MAX];
+    message.copy_into_slice(&mut bytes[..len]);
+    if bytes[..len].iter().any(|b| b.is_ascii_control()) {
+        return Err(NotificationError::InvalidCharacters);
+    }
+    Ok(())
+}
+
+/// Queues payloads for delivery once `deliver_after_ledger` is reached,
+/// keeping the entries live until a keeper has had time to deliver them.
+pub fn schedule(
+    env: Env,
+    sender: Address,
+    payloads: Vec<NotificationPayload>,
+    deliver_after_ledger: u32,
+) -> u64 {
+    let schedule_id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ScheduleCounter)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::ScheduleCounter, &schedule_id);
+
+    let scheduled = ScheduledNotifications {
+        schedule_id,
+        sender,
+        payloads,
+        deliver_after_ledger,
+    };
+    let live_for = deliver_after_ledger - env.ledger().sequence() + SCHEDULE_TTL_BUFFER;
+    let key = DataKey::Scheduled(schedule_id);
+    env.storage().persistent().set(&key, &scheduled);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, live_for, live_for);
+
+    // Keep pending schedules ordered by delivery ledger
+    let mut pending = pending_schedules(&env);
+    let entry = (deliver_after_ledger, schedule_id);
+    let index = pending.binary_search(entry).unwrap_or_else(|index| index);
+    pending.insert(index, entry);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingSchedules, &pending);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::PendingSchedules, live_for, live_for);
+
+    schedule_id
+}
+
+/// Dispatches up to `limit` due schedules and returns how many were delivered.
+///
+/// Pending schedules are ordered by delivery ledger, so only the due ones at
+/// the front are loaded.
+pub fn deliver_due(env: Env, limit: u32) -> u32 {
+    let now = env.ledger().sequence();
+    let pending = pending_schedules(&env);
+    let mut delivered = 0;
+
+    for (deliver_after_ledger, schedule_id) in pending.iter() {
+        if delivered >= limit || deliver_after_ledger > now {
+            break;
+        }
+        let key = DataKey::Scheduled(schedule_id);
+        let scheduled: ScheduledNotifications = env.storage().persistent().get(&key).unwrap();
+
+        // Batch size and categories were checked when the payloads were queued
+        dispatch(env.clone(), &scheduled.sender, &None, scheduled.payloads);
+        env.storage().persistent().remove(&key);
+        delivered += 1;
+
+        env.events()
+            .publish((symbol_short!("sched_dlv"), schedule_id), now);
+    }
+
+    if delivered > 0 {
+        env.storage().persistent().set(
+            &DataKey::PendingSchedules,
+            &pending.slice(delivered..pending.len()),
+        );
+    }
+    delivered
+}
+
+/// Queues payloads into each recipient's pending digest instead of delivering
+/// them one by one. Opt-outs, invalid bodies and rate limits are applied as in
+/// `dispatch`, and a digest holding `MAX_DIGEST_ITEMS` takes no more.
+pub fn queue_digest(
+    env: Env,
+    sender: &Address,
+    allowed: &Option<Vec<Symbol>>,
+    payloads: Vec<NotificationPayload>,
+) -> BatchResult {
+    let now = env.ledger().sequence();
+    let limits = rate_limits(&env);
+    let mut queue = digest_queue(&env);
+    let mut queued = 0;
+    let mut suppressed_count = 0;
+    let mut failures = Vec::new(&env);
+
+    for payload in payloads.iter() {
+        if !category_allowed(allowed, &payload.category) {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: NotificationError::CategoryNotAllowed as u32,
+            });
+            continue;
+        }
+        if !is_opted_in(&env, &payload.user, &payload.category) {
+            env.events().publish(
+                (Symbol::new(&env, "suppressed"), payload.user.clone()),
+                payload.category,
+            );
+            suppressed_count += 1;
+            continue;
+        }
+        if let Err(error) = validate_body(&payload.body) {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: error as u32,
+            });
+            continue;
+        }
+        if inbox(&env, &payload.user).len() >= MAX_INBOX_SIZE {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: NotificationError::InboxFull as u32,
+            });
+            continue;
+        }
+
+        let key = DataKey::PendingDigest(payload.user.clone());
+        let digest: Option<PendingDigest> = env.storage().persistent().get(&key);
+        if digest
+            .as_ref()
+            .is_some_and(|digest| digest.items.len() >= MAX_DIGEST_ITEMS)
+        {
+            failures.push_back(FailedNotification {
+                user: payload.user,
+                error: NotificationError::DigestFull as u32,
+            });
+            continue;
+        }
+        if let Some(limits) = &limits {
+            if !take_quota(&env, &payload.user, limits) {
+                env.events().publish(
+                    (Symbol::new(&env, "rate_limited"), payload.user.clone()),
+                    payload.category,
+                );
+                failures.push_back(FailedNotification {
+                    user: payload.user,
+                    error: NotificationError::RateLimited as u32,
+                });
+                continue;
+            }
+        }
+
+        let mut digest = digest.unwrap_or_else(|| {
+            queue.push_back(payload.user.clone());
+            PendingDigest {
+                opened_at: now,
+                items: Vec::new(&env),
+            }
+        });
+        digest.items.push_back(DigestItem {
+            sender: sender.clone(),
+            payload,
+        });
+        env.storage().persistent().set(&key, &digest);
+        queued += 1;
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::DigestQueue, &queue);
+    BatchResult {
+        successful_count: queued,
+        failures,
+        suppressed_count,
+    }
+}
+
+/// Delivers up to `limit` digests whose window has elapsed, each as a single
+/// inbox entry and event, and returns how many were delivered.
+///
+/// The queue is in the order digests opened, so flushing stops at the first
+/// digest whose window is still running.
+pub fn flush_digests(env: Env, window_ledgers: u32, limit: u32) -> u32 {
+    let now = env.ledger().sequence();
+    let queue = digest_queue(&env);
+    let mut flushed = 0;
+
+    for user in queue.iter() {
+        if flushed >= limit {
+            break;
+        }
+        let key = DataKey::PendingDigest(user.clone());
+        let digest: PendingDigest = env.storage().persistent().get(&key).unwrap();
+        if now < digest.opened_at.saturating_add(window_ledgers) {
+            break;
+        }
+
+        let mut bodies = Vec::new(&env);
+        let mut priority = 0;
+        for item in digest.items.iter() {
+            bodies.push_back((item.sender, item.payload.body));
+            priority = priority.max(item.payload.priority);
+        }
+
+        // Digests bundle several senders, so the contract itself signs them
+        // and each bundled body keeps its own sender
+        store_notification(
+            &env,
+            &env.current_contract_address(),
+            &NotificationPayload {
+                user: user.clone(),
+                body: NotificationBody::Digest(bodies.clone()),
+                category: symbol_short!("digest"),
+                priority,
+            },
+        );
+        env.storage().persistent().remove(&key);
+        flushed += 1;
+
+        env.events()
+            .publish((symbol_short!("digest"), user), bodies);
+    }
+
+    if flushed > 0 {
+        env.storage()
+            .persistent()
+            .set(&DataKey::DigestQueue, &queue.slice(flushed..queue.len()));
     }
+    flushed
+}
+
+fn digest_queue(env: &Env) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DigestQueue)
+        .unwrap_or(Vec::new(env))
+}
+
+fn pending_schedules(env: &Env) -> Vec<(u32, u64)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingSchedules)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Adds `user` to the broadcast registry if not already present.
+pub fn register_user(env: &Env, user: &Address) {
+    let key = DataKey::Registered(user.clone());
+    if env.storage().persistent().has(&key) {
+        return;
+    }
+
+    let count = user_count(env);
+    env.storage().persistent().set(&DataKey::User(count), user);
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .instance()
+        .set(&DataKey::UserCount, &(count + 1));
+}
+
+pub fn user_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::UserCount)
+        .unwrap_or(0)
+}
+
+/// Notifies the next chunk of registered users and returns how many are left.
+pub fn deliver_broadcast_chunk(env: Env, mut broadcast: Broadcast) -> u32 {
+    let end = broadcast
+        .total
+        .min(broadcast.cursor.saturating_add(BROADCAST_CHUNK_SIZE));
+
+    let mut payloads = Vec::new(&env);
+    for index in broadcast.cursor..end {
+        let user: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::User(index))
+            .unwrap();
+        payloads.push_back(NotificationPayload {
+            user,
+            body: NotificationBody::Text(broadcast.message.clone()),
+            category: broadcast.category.clone(),
+            priority: 0,
+        });
+    }
+    dispatch(env.clone(), &broadcast.sender, &None, payloads);
+
+    broadcast.cursor = end;
+    if broadcast.cursor >= broadcast.total {
+        env.storage().instance().remove(&DataKey::ActiveBroadcast);
+    } else {
+        env.storage()
+            .instance()
+            .set(&DataKey::ActiveBroadcast, &broadcast);
+    }
+    broadcast.total - broadcast.cursor
+}
+
+// Appends a delivered notification to the recipient's inbox and keeps the
+// inbox live until the notification expires. Dispatch rejects payloads for
+// full inboxes, so only a digest flush can find the inbox full; the oldest
+// entry then makes way for it.
+fn store_notification(env: &Env, sender: &Address, payload: &NotificationPayload) {
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::NotificationCounter)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::NotificationCounter, &id);
+
+    register_user(env, &payload.user);
+
+    let mut inbox = inbox(env, &payload.user);
+    if inbox.len() >= MAX_INBOX_SIZE {
+        inbox.pop_front();
+    }
+    inbox.push_back(Notification {
+        id,
+        sender: sender.clone(),
+        body: payload.body.clone(),
+        category: payload.category.clone(),
+        priority: payload.priority,
+        timestamp: env.ledger().timestamp(),
+        expires_at: env.ledger().timestamp() + NOTIFICATION_LIFETIME,
+        read: false,
+        acknowledged_at: None,
+    });
+    let key = DataKey::Inbox(payload.user.clone());
+    env.storage().persistent().set(&key, &inbox);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, INBOX_TTL_LEDGERS, INBOX_TTL_LEDGERS);
+}
+
+fn inbox(env: &Env, user: &Address) -> Vec<Notification> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Inbox(user.clone()))
+        .unwrap_or(Vec::new(env))
 }
@@ -1,6 +1,14 @@
-use crate::types::NotificationPayload;
-use crate::{BatchNotificationContract, BatchNotificationContractClient};
-use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+use crate::types::{
+    BudgetAlert, DataKey, FailedNotification, NotificationBody, NotificationPayload, RateLimits,
+    BROADCAST_CHUNK_SIZE, INBOX_TTL_LEDGERS, MAX_DIGEST_ITEMS, MAX_INBOX_SIZE, MAX_MESSAGE_LEN,
+    MAX_SCHEDULE_LEDGERS, NOTIFICATION_LIFETIME,
+};
+use crate::{BatchNotificationContract, BatchNotificationContractClient, NotificationError};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger},
+    vec, Address, Env, String, Symbol, Vec,
+};
 
 #[test]
 fn test_batch_dispatch_mixed_results() {
@@ -11,6 +19,7 @@ fn test_batch_dispatch_mixed_results() {
     let client = BatchNotificationContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    client.initialize(&admin);
     let user_1 = Address::generate(&env);
     let user_2 = Address::generate(&env);
 
@@ -18,17 +27,958 @@ fn test_batch_dispatch_mixed_results() {
         &env,
         NotificationPayload {
             user: user_1.clone(),
-            message: String::from_str(&env, "Successful Message"),
+            body: NotificationBody::Text(String::from_str(&env, "Successful Message")),
+            category: symbol_short!("budget"),
+            priority: 1,
         },
         NotificationPayload {
             user: user_2.clone(),
-            message: String::from_str(&env, ""), // This will trigger a "Failure"
+            body: NotificationBody::Text(String::from_str(&env, "")), // This will trigger a "Failure"
+            category: symbol_short!("budget"),
+            priority: 1,
         },
     ];
 
     let result = client.batch_notify(&admin, &payloads);
 
     assert_eq!(result.successful_count, 1);
-    assert_eq!(result.failed_addresses.len(), 1);
-    assert_eq!(result.failed_addresses.get(0).unwrap(), user_2);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user_2,
+                error: NotificationError::EmptyMessage as u32,
+            }
+        ]
+    );
+
+    // The inbox stays live for as long as its notifications
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Inbox(user_1.clone())),
+            INBOX_TTL_LEDGERS
+        );
+    });
+}
+
+#[test]
+fn test_full_inbox_rejects_new_notifications() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let payload = NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Hello")),
+        category: symbol_short!("budget"),
+        priority: 1,
+    };
+    let mut payloads = Vec::new(&env);
+    for _ in 0..MAX_INBOX_SIZE {
+        payloads.push_back(payload.clone());
+    }
+    let result = client.batch_notify(&admin, &payloads);
+    assert_eq!(result.successful_count, MAX_INBOX_SIZE);
+
+    let result = client.batch_notify(&admin, &vec![&env, payload.clone()]);
+    assert_eq!(result.successful_count, 0);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user.clone(),
+                error: NotificationError::InboxFull as u32,
+            }
+        ]
+    );
+
+    // Clearing the inbox makes room again
+    client.clear_inbox(&user);
+    let result = client.batch_notify(&admin, &vec![&env, payload]);
+    assert_eq!(result.successful_count, 1);
+}
+
+#[test]
+fn test_inbox_stores_and_marks_read() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let user = Address::generate(&env);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Budget exceeded")),
+            category: symbol_short!("budget"),
+            priority: 1,
+        },
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Payment received")),
+            category: symbol_short!("budget"),
+            priority: 1,
+        },
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "")),
+            category: symbol_short!("budget"),
+            priority: 1,
+        },
+    ];
+    client.batch_notify(&admin, &payloads);
+
+    let inbox = client.get_notifications(&user, &0, &10);
+    assert_eq!(inbox.len(), 2);
+    assert_eq!(
+        inbox.get(1).unwrap().body,
+        NotificationBody::Text(String::from_str(&env, "Payment received"))
+    );
+    assert!(!inbox.get(0).unwrap().read);
+
+    let first_id = inbox.get(0).unwrap().id;
+    assert_eq!(client.mark_read(&user, &vec![&env, first_id]), 1);
+    assert_eq!(client.mark_read(&user, &vec![&env, first_id]), 0);
+
+    let page = client.get_notifications(&user, &0, &1);
+    assert_eq!(page.len(), 1);
+    assert!(page.get(0).unwrap().read);
+    assert_eq!(client.get_notifications(&user, &5, &10).len(), 0);
+}
+
+#[test]
+fn test_batch_notify_requires_admin_or_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let spoofer = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: Address::generate(&env),
+            body: NotificationBody::Text(String::from_str(&env, "Hello")),
+            category: symbol_short!("budget"),
+            priority: 1,
+        },
+    ];
+
+    let result = client.try_batch_notify(&spoofer, &payloads);
+    assert_eq!(result, Err(Ok(NotificationError::Unauthorized.into())));
+
+    client.add_operator(&admin, &operator);
+    assert!(client.is_operator(&operator));
+    assert_eq!(
+        client.batch_notify(&operator, &payloads).successful_count,
+        1
+    );
+
+    client.remove_operator(&admin, &operator);
+    let result = client.try_batch_notify(&operator, &payloads);
+    assert_eq!(result, Err(Ok(NotificationError::Unauthorized.into())));
+
+    let result = client.try_initialize(&spoofer);
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::AlreadyInitialized.into()))
+    );
+}
+
+#[test]
+fn test_notifications_filtered_by_category() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Spring sale")),
+            category: symbol_short!("marketing"),
+            priority: 0,
+        },
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Groceries at 90%")),
+            category: symbol_short!("budget"),
+            priority: 2,
+        },
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Dining at 100%")),
+            category: symbol_short!("budget"),
+            priority: 3,
+        },
+    ];
+    client.batch_notify(&admin, &payloads);
+
+    let budget = client.get_notifications_by_category(&user, &symbol_short!("budget"), &0, &10);
+    assert_eq!(budget.len(), 2);
+    assert_eq!(budget.get(0).unwrap().priority, 2);
+
+    let page = client.get_notifications_by_category(&user, &symbol_short!("budget"), &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(
+        page.get(0).unwrap().body,
+        NotificationBody::Text(String::from_str(&env, "Dining at 100%"))
+    );
+}
+
+#[test]
+fn test_opted_out_categories_are_suppressed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_preferences(
+        &user,
+        &vec![
+            &env,
+            (symbol_short!("marketing"), false),
+            (symbol_short!("budget"), true),
+        ],
+    );
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Spring sale")),
+            category: symbol_short!("marketing"),
+            priority: 0,
+        },
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Groceries at 90%")),
+            category: symbol_short!("budget"),
+            priority: 2,
+        },
+    ];
+    let result = client.batch_notify(&admin, &payloads);
+
+    assert_eq!(result.successful_count, 1);
+    assert_eq!(result.suppressed_count, 1);
+    assert_eq!(result.failures.len(), 0);
+    let inbox = client.get_notifications(&user, &0, &10);
+    assert_eq!(inbox.len(), 1);
+    assert_eq!(inbox.get(0).unwrap().category, symbol_short!("budget"));
+}
+
+#[test]
+fn test_prune_expired_notifications() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payload = NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Reminder")),
+        category: symbol_short!("budget"),
+        priority: 1,
+    };
+    client.batch_notify(&admin, &vec![&env, payload.clone(), payload.clone()]);
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    client.batch_notify(&admin, &vec![&env, payload]);
+
+    // Nothing has expired yet
+    assert_eq!(client.prune_expired(&user, &10), 0);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += NOTIFICATION_LIFETIME - 100);
+
+    // Expired notifications are hidden before they are pruned
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 1);
+    let budget = client.get_notifications_by_category(&user, &symbol_short!("budget"), &0, &10);
+    assert_eq!(budget.len(), 1);
+    assert_eq!(client.get_notifications(&user, &1, &10).len(), 0);
+
+    assert_eq!(client.prune_expired(&user, &1), 1);
+    assert_eq!(client.prune_expired(&user, &10), 1);
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 1);
+}
+
+#[test]
+fn test_broadcast_resumes_across_chunks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user_total = BROADCAST_CHUNK_SIZE + 5;
+    let first = Address::generate(&env);
+    client.register(&first);
+    client.register(&first);
+    for _ in 1..user_total {
+        client.register(&Address::generate(&env));
+    }
+    assert_eq!(client.get_user_count(), user_total);
+
+    let message = String::from_str(&env, "Scheduled maintenance tonight");
+    let remaining = client.broadcast(&admin, &message, &symbol_short!("system"));
+    assert_eq!(remaining, 5);
+    assert_eq!(
+        client.get_active_broadcast().unwrap().cursor,
+        BROADCAST_CHUNK_SIZE
+    );
+
+    let result = client.try_broadcast(&admin, &message, &symbol_short!("system"));
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::BroadcastInProgress.into()))
+    );
+
+    assert_eq!(client.continue_broadcast(&admin), 0);
+    assert!(client.get_active_broadcast().is_none());
+    assert_eq!(client.get_notifications(&first, &0, &10).len(), 1);
+
+    let result = client.try_continue_broadcast(&admin);
+    assert_eq!(result, Err(Ok(NotificationError::NoActiveBroadcast.into())));
+}
+
+#[test]
+fn test_batch_notify_from_template() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user_1 = Address::generate(&env);
+    let user_2 = Address::generate(&env);
+    let user_3 = Address::generate(&env);
+    client.initialize(&admin);
+
+    let template_id = symbol_short!("budget");
+    client.create_template(
+        &admin,
+        &template_id,
+        &String::from_str(&env, "Your budget for {month} is {amount}"),
+        &symbol_short!("budget"),
+        &1,
+    );
+
+    let recipients = vec![
+        &env,
+        (
+            user_1.clone(),
+            vec![
+                &env,
+                String::from_str(&env, "May"),
+                String::from_str(&env, "500 USDC"),
+            ],
+        ),
+        // Missing the second parameter
+        (user_2.clone(), vec![&env, String::from_str(&env, "May")]),
+        // One parameter too many
+        (
+            user_3.clone(),
+            vec![
+                &env,
+                String::from_str(&env, "May"),
+                String::from_str(&env, "500 USDC"),
+                String::from_str(&env, "extra"),
+            ],
+        ),
+    ];
+    let result = client.batch_notify_from_template(&admin, &template_id, &recipients);
+
+    assert_eq!(result.successful_count, 1);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user_2,
+                error: NotificationError::TemplateMismatch as u32,
+            },
+            FailedNotification {
+                user: user_3,
+                error: NotificationError::TemplateMismatch as u32,
+            }
+        ]
+    );
+    let inbox = client.get_notifications(&user_1, &0, &1);
+    assert_eq!(
+        inbox.get(0).unwrap().body,
+        NotificationBody::Text(String::from_str(&env, "Your budget for May is 500 USDC"))
+    );
+
+    let result = client.try_batch_notify_from_template(&admin, &symbol_short!("none"), &recipients);
+    assert_eq!(result, Err(Ok(NotificationError::TemplateNotFound.into())));
+}
+
+#[test]
+fn test_scheduled_notifications_delivered_when_due() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Goal deadline in 3 days")),
+            category: symbol_short!("goal"),
+            priority: 2,
+        },
+    ];
+    let deliver_at = env.ledger().sequence() + 100;
+    let late = client.schedule_notifications(&admin, &payloads, &(deliver_at + 50));
+    let first = client.schedule_notifications(&admin, &payloads, &deliver_at);
+    let second = client.schedule_notifications(&admin, &payloads, &deliver_at);
+
+    // Not due yet
+    assert_eq!(client.deliver_due(&10), 0);
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 0);
+
+    env.ledger().with_mut(|li| li.sequence_number = deliver_at);
+    assert_eq!(client.deliver_due(&1), 1);
+    assert!(client.get_scheduled(&first).is_none());
+    assert!(client.get_scheduled(&second).is_some());
+    // Schedules are delivered in ledger order, whatever order they were made in
+    assert_eq!(client.deliver_due(&10), 1);
+    assert!(client.get_scheduled(&late).is_some());
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 2);
+
+    let result = client.try_schedule_notifications(&admin, &payloads, &deliver_at);
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::InvalidDeliveryLedger.into()))
+    );
+}
+
+#[test]
+fn test_scheduled_notifications_outlive_their_delivery_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Goal deadline in 3 days")),
+            category: symbol_short!("goal"),
+            priority: 2,
+        },
+    ];
+    let deliver_at = env.ledger().sequence() + MAX_SCHEDULE_LEDGERS;
+    let schedule_id = client.schedule_notifications(&admin, &payloads, &deliver_at);
+    let result = client.try_schedule_notifications(&admin, &payloads, &(deliver_at + 1));
+    assert_eq!(result, Err(Ok(NotificationError::ScheduleTooFar.into())));
+
+    // Both the batch and the queue stay live past the delivery ledger
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        assert!(storage.get_ttl(&DataKey::Scheduled(schedule_id)) > MAX_SCHEDULE_LEDGERS);
+        assert!(storage.get_ttl(&DataKey::PendingSchedules) > MAX_SCHEDULE_LEDGERS);
+    });
+}
+
+#[test]
+fn test_notify_from_contract_requires_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let savings_goals = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Goal reached")),
+            category: symbol_short!("goal"),
+            priority: 1,
+        },
+    ];
+
+    let result = client.try_notify_from_contract(&savings_goals, &payloads);
+    assert_eq!(result, Err(Ok(NotificationError::SourceNotAllowed.into())));
+
+    client.allow_source(&admin, &savings_goals);
+    assert!(client.is_allowed_source(&savings_goals));
+    let result = client.notify_from_contract(&savings_goals, &payloads);
+    assert_eq!(result.successful_count, 1);
+
+    client.disallow_source(&admin, &savings_goals);
+    assert!(!client.is_allowed_source(&savings_goals));
+}
+
+#[test]
+fn test_acknowledge_records_receipt() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payloads = vec![
+        &env,
+        NotificationPayload {
+            user: user.clone(),
+            body: NotificationBody::Text(String::from_str(&env, "Terms updated")),
+            category: symbol_short!("legal"),
+            priority: 3,
+        },
+    ];
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+    client.batch_notify(&admin, &payloads);
+    let id = client.get_notifications(&user, &0, &1).get(0).unwrap().id;
+
+    assert_eq!(client.acknowledge(&user, &id), 1_700_000_000);
+
+    let notification = client.get_notifications(&user, &0, &1).get(0).unwrap();
+    assert_eq!(notification.acknowledged_at, Some(1_700_000_000));
+    assert!(notification.read);
+
+    let result = client.try_acknowledge(&user, &id);
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::AlreadyAcknowledged.into()))
+    );
+    let result = client.try_acknowledge(&user, &(id + 1));
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::NotificationNotFound.into()))
+    );
+}
+
+#[test]
+fn test_rate_limits_cap_batch_and_per_user_volume() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let limits = RateLimits {
+        max_per_user: 2,
+        window_ledgers: 100,
+        max_batch_size: BROADCAST_CHUNK_SIZE,
+    };
+    client.set_rate_limits(&admin, &limits);
+    assert_eq!(client.get_rate_limits(), Some(limits));
+
+    let payload = NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Duplicate alert")),
+        category: symbol_short!("budget"),
+        priority: 1,
+    };
+    let payloads = vec![&env, payload.clone(), payload.clone(), payload.clone()];
+    let result = client.batch_notify(&admin, &payloads);
+    assert_eq!(result.successful_count, 2);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user.clone(),
+                error: NotificationError::RateLimited as u32,
+            }
+        ]
+    );
+
+    // A new window restores the user's quota
+    env.ledger().with_mut(|li| li.sequence_number += 100);
+    let result = client.batch_notify(&admin, &vec![&env, payload.clone()]);
+    assert_eq!(result.successful_count, 1);
+
+    let mut oversized = Vec::new(&env);
+    for _ in 0..=BROADCAST_CHUNK_SIZE {
+        oversized.push_back(payload.clone());
+    }
+    let result = client.try_batch_notify(&admin, &oversized);
+    assert_eq!(result, Err(Ok(NotificationError::BatchTooLarge.into())));
+}
+
+#[test]
+fn test_invalid_messages_report_error_codes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let too_long = [b'a'; MAX_MESSAGE_LEN as usize + 1];
+    let payload = |message: String| NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(message),
+        category: symbol_short!("budget"),
+        priority: 1,
+    };
+    let payloads = vec![
+        &env,
+        payload(String::from_bytes(&env, &too_long)),
+        payload(String::from_str(&env, "Line one\nLine two")),
+        payload(String::from_str(&env, "Budget exceeded")),
+    ];
+
+    let result = client.batch_notify(&admin, &payloads);
+    assert_eq!(result.successful_count, 1);
+    assert_eq!(
+        result.failures.get(0).unwrap().error,
+        NotificationError::MessageTooLong as u32
+    );
+    assert_eq!(
+        result.failures.get(1).unwrap().error,
+        NotificationError::InvalidCharacters as u32
+    );
+}
+
+#[test]
+fn test_delete_notifications_and_clear_inbox() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payload = NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Weekly summary")),
+        category: symbol_short!("summary"),
+        priority: 0,
+    };
+    let payloads = vec![&env, payload.clone(), payload.clone(), payload];
+    client.batch_notify(&admin, &payloads);
+
+    let inbox = client.get_notifications(&user, &0, &10);
+    let first = inbox.get(0).unwrap().id;
+    let last = inbox.get(2).unwrap().id;
+
+    assert_eq!(
+        client.delete_notifications(&user, &vec![&env, first, 999]),
+        1
+    );
+    let inbox = client.get_notifications(&user, &0, &10);
+    assert_eq!(inbox.len(), 2);
+    assert_eq!(inbox.get(1).unwrap().id, last);
+
+    assert_eq!(client.clear_inbox(&user), 2);
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 0);
+    assert_eq!(client.clear_inbox(&user), 0);
+}
+
+#[test]
+fn test_digest_bundles_notifications_per_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payload = |message: &str, priority: u32| NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, message)),
+        category: symbol_short!("spend"),
+        priority,
+    };
+    let payloads = vec![&env, payload("Coffee 4 XLM", 1), payload("Lunch 12 XLM", 2)];
+
+    let result = client.try_queue_digest(&admin, &payloads);
+    assert_eq!(result, Err(Ok(NotificationError::DigestDisabled.into())));
+
+    client.set_digest_window(&admin, &100);
+    let result = client.queue_digest(&admin, &payloads);
+    assert_eq!(result.successful_count, 2);
+    assert_eq!(client.get_pending_digest(&user).unwrap().items.len(), 2);
+
+    // Opted-out recipients are suppressed with an event
+    let late = Address::generate(&env);
+    client.set_preferences(&late, &vec![&env, (symbol_short!("spend"), false)]);
+    let mut late_payload = payload("Dinner 20 XLM", 1);
+    late_payload.user = late.clone();
+    let result = client.queue_digest(&admin, &vec![&env, late_payload.clone()]);
+    assert_eq!(result.suppressed_count, 1);
+    assert_eq!(env.events().all().len(), 1);
+
+    // Nothing is delivered before the window elapses
+    assert_eq!(client.flush_digests(&10), 0);
+    assert_eq!(client.get_notifications(&user, &0, &10).len(), 0);
+
+    // A digest opened later is still collecting when the first is flushed
+    env.ledger().with_mut(|li| li.sequence_number += 50);
+    client.set_preferences(&late, &vec![&env, (symbol_short!("spend"), true)]);
+    client.queue_digest(&admin, &vec![&env, late_payload]);
+    env.ledger().with_mut(|li| li.sequence_number += 50);
+    assert_eq!(client.flush_digests(&10), 1);
+    assert!(client.get_pending_digest(&late).is_some());
+
+    let inbox = client.get_notifications(&user, &0, &10);
+    assert_eq!(inbox.len(), 1);
+    let digest = inbox.get(0).unwrap();
+    assert_eq!(digest.category, symbol_short!("digest"));
+    assert_eq!(digest.priority, 2);
+    assert_eq!(
+        digest.body,
+        NotificationBody::Digest(vec![
+            &env,
+            (
+                admin.clone(),
+                NotificationBody::Text(String::from_str(&env, "Coffee 4 XLM"))
+            ),
+            (
+                admin.clone(),
+                NotificationBody::Text(String::from_str(&env, "Lunch 12 XLM"))
+            ),
+        ])
+    );
+    assert_eq!(client.get_pending_digest(&user), None);
+}
+
+#[test]
+fn test_digest_respects_rate_limits_and_item_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_digest_window(&admin, &100);
+
+    let payload = NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Coffee 4 XLM")),
+        category: symbol_short!("spend"),
+        priority: 1,
+    };
+
+    // A full digest takes no more items
+    let mut payloads = Vec::new(&env);
+    for _ in 0..=MAX_DIGEST_ITEMS {
+        payloads.push_back(payload.clone());
+    }
+    let result = client.queue_digest(&admin, &payloads);
+    assert_eq!(result.successful_count, MAX_DIGEST_ITEMS);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user.clone(),
+                error: NotificationError::DigestFull as u32,
+            }
+        ]
+    );
+
+    // Each queued item counts against the recipient's rate limit
+    let other = Address::generate(&env);
+    client.set_rate_limits(
+        &admin,
+        &RateLimits {
+            max_per_user: 2,
+            window_ledgers: 100,
+            max_batch_size: BROADCAST_CHUNK_SIZE,
+        },
+    );
+    let mut limited = payload.clone();
+    limited.user = other.clone();
+    let payloads = vec![&env, limited.clone(), limited.clone(), limited];
+    let result = client.queue_digest(&admin, &payloads);
+    assert_eq!(result.successful_count, 2);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: other.clone(),
+                error: NotificationError::RateLimited as u32,
+            }
+        ]
+    );
+    assert_eq!(client.get_pending_digest(&other).unwrap().items.len(), 2);
+}
+
+#[test]
+fn test_delegated_sender_limited_to_own_categories() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let budget_service = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let payload = |category: Symbol| NotificationPayload {
+        user: user.clone(),
+        body: NotificationBody::Text(String::from_str(&env, "Heads up")),
+        category,
+        priority: 1,
+    };
+    let payloads = vec![
+        &env,
+        payload(symbol_short!("budget")),
+        payload(symbol_short!("promo")),
+    ];
+
+    let result = client.try_batch_notify(&budget_service, &payloads);
+    assert_eq!(result, Err(Ok(NotificationError::Unauthorized.into())));
+
+    client.add_sender(
+        &admin,
+        &budget_service,
+        &vec![&env, symbol_short!("budget")],
+    );
+    let result = client.batch_notify(&budget_service, &payloads);
+    assert_eq!(result.successful_count, 1);
+    assert_eq!(
+        result.failures,
+        vec![
+            &env,
+            FailedNotification {
+                user: user.clone(),
+                error: NotificationError::CategoryNotAllowed as u32,
+            }
+        ]
+    );
+
+    let notification = client.get_notifications(&user, &0, &1).get(0).unwrap();
+    assert_eq!(notification.sender, budget_service);
+
+    let result = client.try_broadcast(
+        &budget_service,
+        &String::from_str(&env, "Sale!"),
+        &symbol_short!("promo"),
+    );
+    assert_eq!(
+        result,
+        Err(Ok(NotificationError::CategoryNotAllowed.into()))
+    );
+
+    client.remove_sender(&admin, &budget_service);
+    assert_eq!(client.get_sender_categories(&budget_service), None);
+}
+
+#[test]
+fn test_structured_bodies_are_validated() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchNotificationContract, ());
+    let client = BatchNotificationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let alert = BudgetAlert {
+        budget: symbol_short!("groceries"),
+        spent: 450,
+        limit: 500,
+    };
+    let payload = |body: NotificationBody| NotificationPayload {
+        user: user.clone(),
+        body,
+        category: symbol_short!("budget"),
+        priority: 2,
+    };
+    let payloads = vec![
+        &env,
+        payload(NotificationBody::BudgetAlert(alert.clone())),
+        payload(NotificationBody::BudgetAlert(BudgetAlert {
+            limit: 0,
+            ..alert.clone()
+        })),
+        payload(NotificationBody::Digest(Vec::new(&env))),
+    ];
+
+    let result = client.batch_notify(&admin, &payloads);
+    assert_eq!(result.successful_count, 1);
+    assert_eq!(result.failures.len(), 2);
+    for failure in result.failures.iter() {
+        assert_eq!(failure.error, NotificationError::InvalidBody as u32);
+    }
+
+    let notification = client.get_notifications(&user, &0, &1).get(0).unwrap();
+    assert_eq!(notification.body, NotificationBody::BudgetAlert(alert));
 }
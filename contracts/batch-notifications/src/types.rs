@@ -1,15 +1,239 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, Map, String, Symbol, Vec};
+
+/// Seconds a delivered notification stays in the inbox before it can be pruned (30 days).
+pub const NOTIFICATION_LIFETIME: u64 = 30 * 24 * 60 * 60;
+
+/// Ledgers an inbox stays live after its latest delivery: `NOTIFICATION_LIFETIME`
+/// at ~5 seconds per ledger.
+pub const INBOX_TTL_LEDGERS: u32 = (NOTIFICATION_LIFETIME / 5) as u32;
+
+/// Users reached per `broadcast`/`continue_broadcast` call, sized to stay
+/// within per-transaction ledger footprint limits.
+pub const BROADCAST_CHUNK_SIZE: u32 = 15;
+
+/// Maximum length in bytes of a text notification or template.
+pub const MAX_MESSAGE_LEN: u32 = 512;
+
+/// Maximum notifications kept in one user's inbox, so the inbox entry stays
+/// within ledger entry size limits.
+pub const MAX_INBOX_SIZE: u32 = 50;
+
+/// Maximum notifications one pending digest collects; later ones fail with
+/// `DigestFull` until the digest is flushed.
+pub const MAX_DIGEST_ITEMS: u32 = 20;
+
+/// Furthest ahead, in ledgers, a batch can be scheduled (~30 days).
+pub const MAX_SCHEDULE_LEDGERS: u32 = 518_400;
+
+/// Ledgers a scheduled batch is kept past its delivery ledger, giving keepers
+/// time to call `deliver_due` before the entry can be archived (~1 day).
+pub const SCHEDULE_TTL_BUFFER: u32 = 17_280;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct NotificationPayload {
     pub user: Address,
-    pub message: String,
+    pub body: NotificationBody,
+    /// Kind of notification, e.g. `budget` or `marketing`
+    pub category: Symbol,
+    /// Urgency; higher values are more urgent
+    pub priority: u32,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BatchResult {
     pub successful_count: u32,
-    pub failed_addresses: Vec<Address>,
+    pub failures: Vec<FailedNotification>,
+    /// Notifications skipped because the user opted out of the category
+    pub suppressed_count: u32,
+}
+
+/// Typed notification content, so consumers and indexers need not parse
+/// free text.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NotificationBody {
+    /// Free-form text, e.g. announcements and rendered templates
+    Text(String),
+    BudgetAlert(BudgetAlert),
+    PaymentReceived(PaymentReceived),
+    GoalReached(GoalReached),
+    /// Sender and body of each notification bundled by digest mode; not
+    /// accepted in payloads
+    Digest(Vec<(Address, NotificationBody)>),
+}
+
+/// Spending in a budget reached a threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BudgetAlert {
+    pub budget: Symbol,
+    pub spent: i128,
+    pub limit: i128,
+}
+
+/// The user received a payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentReceived {
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// A savings goal reached its target.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalReached {
+    pub goal_id: u64,
+    pub target: i128,
+}
+
+/// A payload that was not delivered, with the reason.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailedNotification {
+    pub user: Address,
+    /// `NotificationError` code
+    pub error: u32,
+}
+
+/// A delivered notification kept in a user's inbox.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notification {
+    pub id: u64,
+    /// Admin, operator, delegated sender or source contract that sent it
+    pub sender: Address,
+    pub body: NotificationBody,
+    pub category: Symbol,
+    pub priority: u32,
+    /// Ledger timestamp at delivery
+    pub timestamp: u64,
+    /// Ledger timestamp after which `prune_expired` may remove it
+    pub expires_at: u64,
+    pub read: bool,
+    /// Ledger timestamp at which the user acknowledged receipt
+    pub acknowledged_at: Option<u64>,
 }
+
+/// A platform-wide announcement being delivered in chunks.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Broadcast {
+    pub sender: Address,
+    pub message: String,
+    pub category: Symbol,
+    /// Index of the next registered user to notify
+    pub cursor: u32,
+    /// Registered users when the broadcast started
+    pub total: u32,
+}
+
+/// Reusable message text; each `{name}` placeholder is filled, in order, from
+/// the per-recipient parameters.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template {
+    pub text: String,
+    pub category: Symbol,
+    pub priority: u32,
+}
+
+/// Notifications queued for delivery at a later ledger.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledNotifications {
+    pub schedule_id: u64,
+    pub sender: Address,
+    pub payloads: Vec<NotificationPayload>,
+    /// Ledger sequence from which `deliver_due` dispatches the payloads
+    pub deliver_after_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Contract administrator
+    Admin,
+    /// Address allowed to dispatch notifications besides the admin
+    Operator(Address),
+    /// Last assigned notification ID
+    NotificationCounter,
+    /// Notifications delivered to a user, oldest first
+    Inbox(Address),
+    /// Per-category opt-in flags set by a user
+    Preferences(Address),
+    /// Number of registered users
+    UserCount,
+    /// Registered user by index
+    User(u32),
+    /// Marks an address as registered
+    Registered(Address),
+    /// Broadcast still being delivered
+    ActiveBroadcast,
+    /// Message template by ID
+    Template(Symbol),
+    /// Last assigned schedule ID
+    ScheduleCounter,
+    /// Queued notifications by schedule ID
+    Scheduled(u64),
+    /// (delivery ledger, schedule ID) of schedules not yet delivered, ascending
+    PendingSchedules,
+    /// Sibling contract allowed to call `notify_from_contract`
+    AllowedSource(Address),
+    /// Active dispatch limits
+    RateLimits,
+    /// Per-user delivery count for the current window (temporary)
+    UserQuota(Address),
+    /// Ledgers a user's digest collects notifications before delivery
+    DigestWindow,
+    /// Notifications waiting to be bundled into a user's next digest
+    PendingDigest(Address),
+    /// Users with a pending digest, in the order their digests opened
+    DigestQueue,
+    /// Categories a delegated sender may notify about
+    Sender(Address),
+}
+
+/// Notifications collected for one user during a digest window.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDigest {
+    /// Ledger sequence at which the first item was queued
+    pub opened_at: u32,
+    pub items: Vec<DigestItem>,
+}
+
+/// A queued digest notification and the sender that queued it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DigestItem {
+    pub sender: Address,
+    pub payload: NotificationPayload,
+}
+
+/// Admin-configured dispatch limits.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimits {
+    /// Notifications a single user may receive per window
+    pub max_per_user: u32,
+    /// Window length in ledgers
+    pub window_ledgers: u32,
+    /// Payloads accepted in a single dispatch call
+    pub max_batch_size: u32,
+}
+
+/// Notifications delivered to a user in the current rate-limit window.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserQuota {
+    /// Ledger sequence at which the window opened
+    pub window_start: u32,
+    pub sent: u32,
+}
+
+/// A user's category preferences; categories not listed are delivered.
+pub type Preferences = Map<Symbol, bool>;
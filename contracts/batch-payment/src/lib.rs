@@ -3,61 +3,2426 @@
 mod test;
 mod types;
 
-use crate::types::Payment;
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Vec};
+pub use crate::types::{
+    BatchInvoiceResult, BatchPaymentResult, BatchRequestResult, DataKey, Discount, EscrowStatus,
+    EscrowedPayment, FeeConfig, Invoice, InvoiceResult, InvoiceStatus, Merchant, Payment,
+    PaymentRecord, PaymentRequest, PaymentResult, Receipt, RequestResult, RequestStatus,
+    ScheduleStatus, ScheduledPayment, SpendingCap, Subscription, SubscriptionStatus,
+    TransactionRecord, TxType, WalletStatus, MAX_BATCH_SIZE, MAX_FEE_BPS, TOTAL_SPLIT_BPS,
+    WALLET_DAILY_LIMIT_EXCEEDED,
+};
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, panic_with_error, symbol_short, token,
+    Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
+};
+
+/// Error codes for the batch payment contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BatchPaymentError {
+    /// Batch contains no payments
+    EmptyBatch = 1,
+    /// Batch exceeds `MAX_BATCH_SIZE`
+    BatchTooLarge = 2,
+    /// Payment amount must be positive
+    InvalidAmount = 3,
+    /// Claim deadline must be in the future
+    InvalidDeadline = 4,
+    /// Escrowed payment does not exist
+    PaymentNotFound = 5,
+    /// Caller is not allowed to act on this payment
+    Unauthorized = 6,
+    /// Payment was already claimed or cancelled
+    PaymentNotPending = 7,
+    /// Claim deadline has not passed yet
+    DeadlineNotReached = 8,
+    /// Invoice does not exist
+    InvoiceNotFound = 9,
+    /// Invoice was already paid or cancelled
+    InvoiceNotOpen = 10,
+    /// Refund would exceed the amount left on the payment
+    RefundExceedsPayment = 11,
+    /// Split shares are zero or do not sum to 10,000 bps
+    InvalidSplit = 12,
+    /// Payment request does not exist
+    RequestNotFound = 13,
+    /// Payment request was already paid
+    RequestNotPending = 14,
+    /// Payment request expired before approval
+    RequestExpired = 15,
+    /// Payer balance does not cover the payment
+    InsufficientBalance = 16,
+    /// Subscription does not exist
+    SubscriptionNotFound = 17,
+    /// Subscription is not in a state that allows this action
+    InvalidSubscriptionStatus = 18,
+    /// Subscription interval must be positive
+    InvalidInterval = 19,
+    /// Fee configuration is out of range
+    InvalidFee = 20,
+    /// Scheduled payment does not exist
+    ScheduleNotFound = 21,
+    /// Scheduled payment was already executed or cancelled
+    ScheduleNotPending = 22,
+    /// Scheduled payment is not executable yet
+    ScheduleNotDue = 23,
+    /// A discount with this code already exists
+    DiscountExists = 24,
+    /// Discount basis points or usage limit are out of range
+    InvalidDiscount = 25,
+    /// Payment exceeds the invoice's outstanding balance
+    ExceedsInvoiceBalance = 26,
+    /// Payment would exceed the payer's spending cap
+    SpendingCapExceeded = 27,
+    /// A merchant with this ID is already registered
+    MerchantExists = 28,
+    /// Merchant ID is not registered
+    MerchantNotFound = 29,
+    /// Token contract did not report the payer's balance
+    InvalidToken = 30,
+    /// Recipient's wallet is frozen or closed
+    RecipientInactive = 31,
+    /// Batch would take the payer past their wallet's daily spend limit
+    WalletLimitExceeded = 32,
+}
+
+/// Recording entrypoint exposed by the batch-history contract.
+#[contractclient(name = "HistoryClient")]
+pub trait HistoryInterface {
+    fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32;
+}
+
+/// Status lookup and spend reporting exposed by the batch-wallet-creation contract.
+#[contractclient(name = "WalletClient")]
+pub trait WalletInterface {
+    fn get_wallet_status(env: Env, owner: Address) -> Option<WalletStatus>;
+    fn get_remaining_daily_limit(env: Env, owner: Address) -> i128;
+    fn record_spend(env: Env, caller_contract: Address, owner: Address, amount: i128);
+    fn bump_activity(env: Env, caller_contract: Address, owner: Address);
+}
 
 #[contract]
 pub struct BatchPaymentContract;
 
 #[contractimpl]
 impl BatchPaymentContract {
+    /// Initializes the contract with an admin.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Configures the platform fee taken from each `batch_transfer` payment.
+    ///
+    /// The fee is `amount * fee_bps / 10_000`, deducted from the amount the
+    /// recipient receives and routed to `collector`.
+    ///
+    /// # Errors
+    /// * `Unauthorized` - If the caller is not the admin
+    /// * `InvalidFee` - If `fee_bps` exceeds `MAX_FEE_BPS`
+    pub fn set_fee_config(env: Env, admin: Address, collector: Address, fee_bps: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if fee_bps > MAX_FEE_BPS {
+            panic_with_error!(&env, BatchPaymentError::InvalidFee);
+        }
+
+        let config = FeeConfig { collector, fee_bps };
+        env.storage().instance().set(&DataKey::FeeConfig, &config);
+    }
+
+    /// Disables the platform fee.
+    pub fn clear_fee_config(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::FeeConfig);
+    }
+
+    /// Returns the platform fee configuration, if set.
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&DataKey::FeeConfig)
+    }
+
+    /// Sets the batch-history contract that records every settled payment
+    /// in both the payer's and the recipient's history. This contract must be
+    /// on its source allowlist.
+    ///
+    /// Recording is best effort: a failing history call never reverts the
+    /// payments themselves.
+    pub fn set_history_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::HistoryContract, &contract);
+    }
+
+    /// Stops recording payments in the batch-history contract.
+    pub fn clear_history_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::HistoryContract);
+    }
+
+    /// Returns the configured batch-history contract, if any.
+    pub fn get_history_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::HistoryContract)
+    }
+
+    /// Sets the batch-wallet-creation contract consulted before each payment.
+    ///
+    /// Payments to a recipient whose wallet is frozen or closed fail with
+    /// `RecipientInactive`. Recipients without a registered wallet are paid as
+    /// usual, and so is every recipient while the wallet contract fails to
+    /// answer the status lookup.
+    ///
+    /// Once this contract is trusted by the wallet contract, every payment
+    /// funded by a payer is also reported as activity of the payer, and as a
+    /// spend when it is made in the token set with `set_wallet_limit_token`.
+    /// A payment that would take the payer past their wallet's daily limit
+    /// panics with `WalletLimitExceeded`, except for subscription charges,
+    /// which are skipped and retried like any other failed charge.
+    pub fn set_wallet_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WalletContract, &contract);
+    }
+
+    /// Stops consulting the batch-wallet-creation contract.
+    pub fn clear_wallet_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::WalletContract);
+    }
+
+    /// Returns the configured batch-wallet-creation contract, if any.
+    pub fn get_wallet_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::WalletContract)
+    }
+
+    /// Sets the token whose payments count against the payer's wallet daily
+    /// limit. The limit is a single amount, so payments in any other token are
+    /// only reported as activity.
+    pub fn set_wallet_limit_token(env: Env, admin: Address, token: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WalletLimitToken, &token);
+    }
+
+    /// Returns the token counted against wallet daily limits, if any.
+    pub fn get_wallet_limit_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::WalletLimitToken)
+    }
+
+    /// Returns the total fees collected in `token`.
+    pub fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccruedFees(token))
+            .unwrap_or(0)
+    }
+
+    /// Sets the caller's own cap on how much of `token` can be spent through
+    /// this contract per `period` seconds.
+    ///
+    /// Replacing a cap keeps the current period and what was already spent
+    /// in it. A tighter limit (lower cap, no shorter period) applies at once;
+    /// any other change only takes over when the current period ends.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If the cap is not positive
+    /// * `InvalidInterval` - If the period is zero
+    pub fn set_my_spending_cap(env: Env, payer: Address, token: Address, cap: i128, period: u64) {
+        payer.require_auth();
+
+        if cap <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if period == 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidInterval);
+        }
+
+        let spending_cap = match Self::current_spending_cap(&env, &payer, &token) {
+            Some(mut current) => {
+                if cap <= current.cap && period >= current.period {
+                    current.cap = cap;
+                    current.period = period;
+                    current.pending_cap = None;
+                } else {
+                    current.pending_cap = Some(cap);
+                    current.pending_period = period;
+                }
+                current.clear_pending = false;
+                current
+            }
+            None => SpendingCap {
+                cap,
+                period,
+                window_start: env.ledger().timestamp(),
+                spent: 0,
+                pending_cap: None,
+                pending_period: 0,
+                clear_pending: false,
+            },
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::SpendingCap(payer, token), &spending_cap);
+    }
+
+    /// Removes the caller's spending cap for `token` once its current period
+    /// ends.
+    pub fn clear_my_spending_cap(env: Env, payer: Address, token: Address) {
+        payer.require_auth();
+
+        if let Some(mut spending_cap) = Self::current_spending_cap(&env, &payer, &token) {
+            spending_cap.pending_cap = None;
+            spending_cap.clear_pending = true;
+            env.storage()
+                .persistent()
+                .set(&DataKey::SpendingCap(payer, token), &spending_cap);
+        }
+    }
+
+    /// Returns a payer's spending cap for `token` as it applies in the
+    /// current period, if set.
+    pub fn get_spending_cap(env: Env, payer: Address, token: Address) -> Option<SpendingCap> {
+        Self::current_spending_cap(&env, &payer, &token)
+    }
+
     /// Transfers tokens from the caller to multiple recipients.
     ///
+    /// Each payment is validated on its own; invalid or unaffordable payments
+    /// are skipped and reported as failures instead of reverting the batch.
+    ///
     /// # Arguments
     /// * `env` - The contract environment.
     /// * `from` - The address sending the tokens (must authorize the call).
     /// * `token` - The address of the token contract (e.g., USDC).
     /// * `payments` - A vector of `Payment` structs containing recipients and amounts.
-    pub fn batch_transfer(env: Env, from: Address, token: Address, payments: Vec<Payment>) {
+    ///
+    /// # Errors
+    /// * `EmptyBatch` - If no payments are provided
+    /// * `BatchTooLarge` - If more than `MAX_BATCH_SIZE` payments are provided
+    pub fn batch_transfer(
+        env: Env,
+        from: Address,
+        token: Address,
+        payments: Vec<Payment>,
+    ) -> BatchPaymentResult {
         // Require authorization from the sender
         from.require_auth();
 
-        let token_client = token::Client::new(&env, &token);
+        Self::pay_batch(env, from, token, payments)
+    }
+
+    /// Splits `total` between recipients by basis points and pays each share.
+    ///
+    /// Shares are rounded down; the rounding remainder goes to the last
+    /// recipient so the full `total` is always paid out.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If `total` is not positive
+    /// * `EmptyBatch` / `BatchTooLarge` - If the split list size is invalid
+    /// * `InvalidSplit` - If any share is zero or the shares do not sum to 10,000 bps
+    pub fn split_payment(
+        env: Env,
+        from: Address,
+        token: Address,
+        total: i128,
+        splits: Vec<(Address, u32)>,
+    ) -> BatchPaymentResult {
+        from.require_auth();
+
+        if total <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if splits.is_empty() {
+            panic_with_error!(&env, BatchPaymentError::EmptyBatch);
+        }
+
+        let mut total_bps: u32 = 0;
+        for (_, bps) in splits.iter() {
+            if bps == 0 {
+                panic_with_error!(&env, BatchPaymentError::InvalidSplit);
+            }
+            total_bps = total_bps.saturating_add(bps);
+        }
+        if total_bps != TOTAL_SPLIT_BPS {
+            panic_with_error!(&env, BatchPaymentError::InvalidSplit);
+        }
+
+        let mut payments: Vec<Payment> = Vec::new(&env);
+        let mut allocated: i128 = 0;
+        let last = splits.len() - 1;
+        for (index, (recipient, bps)) in splits.iter().enumerate() {
+            let amount = if index as u32 == last {
+                total - allocated
+            } else {
+                total
+                    .checked_mul(bps as i128)
+                    .unwrap_or_else(|| panic_with_error!(&env, BatchPaymentError::InvalidAmount))
+                    / TOTAL_SPLIT_BPS as i128
+            };
+            allocated += amount;
+            payments.push_back(Payment {
+                recipient,
+                amount,
+                reference: None,
+                token: None,
+            });
+        }
+
+        Self::pay_batch(env, from, token, payments)
+    }
+
+    /// Registers a merchant ID owned by the caller.
+    ///
+    /// # Errors
+    /// * `MerchantExists` - If the ID is already registered
+    pub fn register_merchant(
+        env: Env,
+        owner: Address,
+        merchant_id: Symbol,
+        settlement_address: Address,
+        metadata: String,
+    ) {
+        owner.require_auth();
+
+        let key = DataKey::Merchant(merchant_id.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, BatchPaymentError::MerchantExists);
+        }
+
+        let merchant = Merchant {
+            merchant_id: merchant_id.clone(),
+            owner,
+            settlement_address: settlement_address.clone(),
+            metadata,
+        };
+        env.storage().persistent().set(&key, &merchant);
+
+        // Topics: (merchant, register, merchant_id)
+        // Data: settlement_address
+        let topics = (
+            symbol_short!("merchant"),
+            symbol_short!("register"),
+            merchant_id,
+        );
+        env.events().publish(topics, settlement_address);
+    }
+
+    /// Rotates the address that receives a merchant's payments.
+    ///
+    /// # Errors
+    /// * `MerchantNotFound` - If the ID is not registered
+    /// * `Unauthorized` - If the caller does not own the merchant ID
+    pub fn update_settlement_address(
+        env: Env,
+        owner: Address,
+        merchant_id: Symbol,
+        settlement_address: Address,
+    ) {
+        owner.require_auth();
+
+        let mut merchant = Self::merchant(&env, &merchant_id);
+        if merchant.owner != owner {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+
+        merchant.settlement_address = settlement_address.clone();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Merchant(merchant_id.clone()), &merchant);
+
+        // Topics: (merchant, rotated, merchant_id)
+        // Data: settlement_address
+        let topics = (
+            symbol_short!("merchant"),
+            symbol_short!("rotated"),
+            merchant_id,
+        );
+        env.events().publish(topics, settlement_address);
+    }
+
+    /// Returns a registered merchant by ID.
+    pub fn get_merchant(env: Env, merchant_id: Symbol) -> Option<Merchant> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Merchant(merchant_id))
+    }
+
+    /// Pays registered merchants by ID, resolving each to its current
+    /// settlement address. Payments then follow `batch_transfer` semantics.
+    ///
+    /// # Errors
+    /// * `MerchantNotFound` - If any merchant ID is not registered
+    /// * `EmptyBatch` / `BatchTooLarge` - If the batch size is invalid
+    pub fn batch_pay_merchants(
+        env: Env,
+        from: Address,
+        token: Address,
+        merchant_payments: Vec<(Symbol, i128)>,
+    ) -> BatchPaymentResult {
+        from.require_auth();
+
+        let mut payments: Vec<Payment> = Vec::new(&env);
+        for (merchant_id, amount) in merchant_payments.iter() {
+            let merchant = Self::merchant(&env, &merchant_id);
+            payments.push_back(Payment {
+                recipient: merchant.settlement_address,
+                amount,
+                reference: None,
+                token: None,
+            });
+        }
+
+        Self::pay_batch(env, from, token, payments)
+    }
+
+    /// Sends part or all of a settled payment back to its sender.
+    ///
+    /// Only the original recipient can refund, and the total refunded can never
+    /// exceed the original amount.
+    ///
+    /// # Returns
+    /// * `i128` - The total refunded on this payment so far
+    ///
+    /// # Errors
+    /// * `PaymentNotFound` - If the payment record does not exist
+    /// * `Unauthorized` - If the caller is not the recipient
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `RefundExceedsPayment` - If the refund exceeds what is left to refund
+    pub fn refund(env: Env, recipient: Address, payment_id: u64, amount: i128) -> i128 {
+        recipient.require_auth();
+
+        let mut record: PaymentRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PaymentRecord(payment_id))
+            .unwrap_or_else(|| panic_with_error!(&env, BatchPaymentError::PaymentNotFound));
+        if record.recipient != recipient {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if amount > record.amount - record.refunded {
+            panic_with_error!(&env, BatchPaymentError::RefundExceedsPayment);
+        }
+
+        token::Client::new(&env, &record.token).transfer(&recipient, &record.sender, &amount);
+
+        record.refunded += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PaymentRecord(payment_id), &record);
+
+        // Emit payment_refunded event
+        // Topics: (refunded, batch_id, payment_id)
+        // Data: (recipient, sender, amount)
+        let topics = (symbol_short!("refunded"), record.batch_id, payment_id);
+        env.events()
+            .publish(topics, (recipient, record.sender.clone(), amount));
+
+        record.refunded
+    }
+
+    /// Returns a settled payment record by ID.
+    pub fn get_payment(env: Env, payment_id: u64) -> Option<PaymentRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRecord(payment_id))
+    }
+
+    /// Returns up to `limit` receipts for payments made by `payer`, starting
+    /// at index `start` (oldest first).
+    pub fn get_payer_receipts(env: Env, payer: Address, start: u32, limit: u32) -> Vec<Receipt> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PayerReceiptCount(payer.clone()))
+            .unwrap_or(0);
+        let mut receipts: Vec<Receipt> = Vec::new(&env);
+        for index in start..count.min(start.saturating_add(limit)) {
+            let payment_id: u64 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::PayerReceipt(payer.clone(), index))
+                .unwrap();
+            receipts.push_back(Self::receipt(&env, payment_id));
+        }
+        receipts
+    }
+
+    /// Returns up to `limit` receipts for payments received by `recipient`,
+    /// starting at index `start` (oldest first).
+    pub fn get_recipient_receipts(
+        env: Env,
+        recipient: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Receipt> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RecipientReceiptCount(recipient.clone()))
+            .unwrap_or(0);
+        let mut receipts: Vec<Receipt> = Vec::new(&env);
+        for index in start..count.min(start.saturating_add(limit)) {
+            let payment_id: u64 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::RecipientReceipt(recipient.clone(), index))
+                .unwrap();
+            receipts.push_back(Self::receipt(&env, payment_id));
+        }
+        receipts
+    }
+
+    /// Returns the number of receipts for payments made by `payer`.
+    pub fn get_payer_receipt_count(env: Env, payer: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PayerReceiptCount(payer))
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of receipts for payments received by `recipient`.
+    pub fn get_recipient_receipt_count(env: Env, recipient: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RecipientReceiptCount(recipient))
+            .unwrap_or(0)
+    }
+
+    /// Escrows payments in the contract until each recipient claims them.
+    ///
+    /// The batch total is moved from the sender up front. Recipients withdraw
+    /// with `claim`; payments still unclaimed after `claim_deadline` (a ledger
+    /// timestamp) can be taken back by the sender with `cancel_payment`.
+    ///
+    /// # Returns
+    /// * `Vec<u64>` - The escrowed payment IDs, in input order
+    ///
+    /// # Errors
+    /// * `EmptyBatch` / `BatchTooLarge` - If the batch size is invalid
+    /// * `InvalidAmount` - If any payment amount is not positive
+    /// * `InvalidDeadline` - If the deadline is not in the future
+    pub fn batch_escrow(
+        env: Env,
+        from: Address,
+        token: Address,
+        payments: Vec<Payment>,
+        claim_deadline: u64,
+    ) -> Vec<u64> {
+        from.require_auth();
+
+        if payments.is_empty() {
+            panic_with_error!(&env, BatchPaymentError::EmptyBatch);
+        }
+        if payments.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchPaymentError::BatchTooLarge);
+        }
+        if claim_deadline <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchPaymentError::InvalidDeadline);
+        }
+
+        let mut token_totals: Map<Address, i128> = Map::new(&env);
+        for payment in payments.iter() {
+            if payment.amount <= 0 {
+                panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+            }
+            let payment_token = payment.token.unwrap_or_else(|| token.clone());
+            let total = token_totals
+                .get(payment_token.clone())
+                .unwrap_or(0)
+                .checked_add(payment.amount)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchPaymentError::InvalidAmount));
+            token_totals.set(payment_token, total);
+        }
+
+        for (payment_token, total) in token_totals.iter() {
+            if !Self::within_spending_cap(&env, &from, &payment_token, total) {
+                panic_with_error!(&env, BatchPaymentError::SpendingCapExceeded);
+            }
+            token::Client::new(&env, &payment_token).transfer(
+                &from,
+                &env.current_contract_address(),
+                &total,
+            );
+            Self::record_spending(&env, &from, &payment_token, total);
+            Self::report_wallet_spend(&env, &from, &payment_token, total);
+        }
+
+        let batch_id = Self::next_batch_id(&env);
+        let mut payment_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PaymentCounter)
+            .unwrap_or(0);
+        let mut payment_ids: Vec<u64> = Vec::new(&env);
+
+        for payment in payments.iter() {
+            payment_id += 1;
+            let payment_token = payment.token.unwrap_or_else(|| token.clone());
+            let escrowed = EscrowedPayment {
+                payment_id,
+                batch_id,
+                sender: from.clone(),
+                recipient: payment.recipient.clone(),
+                token: payment_token.clone(),
+                amount: payment.amount,
+                claim_deadline,
+                status: EscrowStatus::Pending,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::EscrowedPayment(payment_id), &escrowed);
+            payment_ids.push_back(payment_id);
+
+            // Topics: (escrowed, payment_id, recipient)
+            // Data: (token, amount, claim_deadline, reference)
+            let topics = (symbol_short!("escrowed"), payment_id, payment.recipient);
+            env.events().publish(
+                topics,
+                (
+                    payment_token,
+                    payment.amount,
+                    claim_deadline,
+                    payment.reference,
+                ),
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PaymentCounter, &payment_id);
+
+        payment_ids
+    }
+
+    /// Withdraws an escrowed payment to its recipient.
+    ///
+    /// # Errors
+    /// * `PaymentNotFound` - If the payment does not exist
+    /// * `Unauthorized` - If the caller is not the recipient
+    /// * `PaymentNotPending` - If the payment was already claimed or cancelled
+    pub fn claim(env: Env, recipient: Address, payment_id: u64) -> i128 {
+        recipient.require_auth();
+
+        let mut payment = Self::escrowed_payment(&env, payment_id);
+        if payment.recipient != recipient {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if payment.status != EscrowStatus::Pending {
+            panic_with_error!(&env, BatchPaymentError::PaymentNotPending);
+        }
+
+        payment.status = EscrowStatus::Claimed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::EscrowedPayment(payment_id), &payment);
+
+        token::Client::new(&env, &payment.token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &payment.amount,
+        );
+        Self::store_receipt(
+            &env,
+            &Receipt {
+                payment_id,
+                payer: payment.sender.clone(),
+                recipient: recipient.clone(),
+                token: payment.token.clone(),
+                amount: payment.amount,
+                batch_id: payment.batch_id,
+                timestamp: env.ledger().timestamp(),
+                reference: None,
+            },
+        );
+
+        // Topics: (claimed, payment_id, recipient)
+        // Data: amount
+        let topics = (symbol_short!("claimed"), payment_id, recipient);
+        env.events().publish(topics, payment.amount);
+
+        payment.amount
+    }
+
+    /// Returns an unclaimed escrowed payment to its sender after the deadline.
+    ///
+    /// # Errors
+    /// * `PaymentNotFound` - If the payment does not exist
+    /// * `Unauthorized` - If the caller is not the sender
+    /// * `PaymentNotPending` - If the payment was already claimed or cancelled
+    /// * `DeadlineNotReached` - If the claim deadline has not passed
+    pub fn cancel_payment(env: Env, sender: Address, payment_id: u64) -> i128 {
+        sender.require_auth();
+
+        let mut payment = Self::escrowed_payment(&env, payment_id);
+        if payment.sender != sender {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if payment.status != EscrowStatus::Pending {
+            panic_with_error!(&env, BatchPaymentError::PaymentNotPending);
+        }
+        if env.ledger().timestamp() <= payment.claim_deadline {
+            panic_with_error!(&env, BatchPaymentError::DeadlineNotReached);
+        }
+
+        payment.status = EscrowStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::EscrowedPayment(payment_id), &payment);
+
+        token::Client::new(&env, &payment.token).transfer(
+            &env.current_contract_address(),
+            &sender,
+            &payment.amount,
+        );
+
+        // Topics: (pay_cancl, payment_id, sender)
+        // Data: amount
+        let topics = (symbol_short!("pay_cancl"), payment_id, sender);
+        env.events().publish(topics, payment.amount);
+
+        payment.amount
+    }
+
+    /// Returns an escrowed payment by ID.
+    pub fn get_escrowed_payment(env: Env, payment_id: u64) -> Option<EscrowedPayment> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EscrowedPayment(payment_id))
+    }
+
+    /// Issues an invoice from a merchant to a payer.
+    ///
+    /// # Returns
+    /// * `u64` - The new invoice ID
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `InvalidDeadline` - If `due_ledger` is not in the future
+    pub fn create_invoice(
+        env: Env,
+        merchant: Address,
+        payer: Address,
+        token: Address,
+        amount: i128,
+        due_ledger: u32,
+        memo: String,
+    ) -> u64 {
+        merchant.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if due_ledger <= env.ledger().sequence() {
+            panic_with_error!(&env, BatchPaymentError::InvalidDeadline);
+        }
+
+        let invoice_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::InvoiceCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::InvoiceCounter, &invoice_id);
+
+        let invoice = Invoice {
+            invoice_id,
+            merchant: merchant.clone(),
+            payer: payer.clone(),
+            token,
+            amount,
+            amount_paid: 0,
+            amount_discounted: 0,
+            due_ledger,
+            memo,
+            status: InvoiceStatus::Open,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Invoice(invoice_id), &invoice);
+
+        // Topics: (invoice, created, invoice_id)
+        // Data: (merchant, payer, amount, due_ledger)
+        let topics = (
+            symbol_short!("invoice"),
+            symbol_short!("created"),
+            invoice_id,
+        );
+        env.events()
+            .publish(topics, (merchant, payer, amount, due_ledger));
+
+        invoice_id
+    }
+
+    /// Pays off the outstanding balance of several invoices addressed to the
+    /// same payer.
+    ///
+    /// Each invoice is checked on its own; invoices that cannot be paid are
+    /// reported as failures without reverting the others. Overdue invoices can
+    /// still be paid. When `discount_code` is given, it is matched by its
+    /// SHA-256 hash against the registered discounts, and every invoice is
+    /// charged at the discounted balance and consumes one use of the code. The
+    /// waived amount is kept on the invoice as `amount_discounted`.
+    ///
+    /// # Errors
+    /// * `EmptyBatch` / `BatchTooLarge` - If the batch size is invalid
+    pub fn batch_pay_invoices(
+        env: Env,
+        payer: Address,
+        invoice_ids: Vec<u64>,
+        discount_code: Option<Bytes>,
+    ) -> BatchInvoiceResult {
+        payer.require_auth();
+
+        if invoice_ids.is_empty() {
+            panic_with_error!(&env, BatchPaymentError::EmptyBatch);
+        }
+        if invoice_ids.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchPaymentError::BatchTooLarge);
+        }
+
+        let code_hash: Option<BytesN<32>> =
+            discount_code.map(|code| env.crypto().sha256(&code).into());
+        let batch_id = Self::next_batch_id(&env);
+
+        let mut results: Vec<InvoiceResult> = Vec::new(&env);
+        let mut successful: u32 = 0;
+        let mut failed: u32 = 0;
+        let mut total_paid: i128 = 0;
+
+        for invoice_id in invoice_ids.iter() {
+            let (mut invoice, discount) =
+                match Self::payable_invoice(&env, &payer, invoice_id, &code_hash) {
+                    Ok(payable) => payable,
+                    Err(error) => {
+                        let code = error as u32;
+                        results.push_back(InvoiceResult::Failure(invoice_id, code));
+                        failed += 1;
+
+                        // Topics: (inv_fail, invoice_id)
+                        // Data: error_code
+                        let topics = (symbol_short!("inv_fail"), invoice_id);
+                        env.events().publish(topics, code);
+                        continue;
+                    }
+                };
+
+            let charged = invoice.amount - invoice.amount_paid - discount;
+            if charged > 0 {
+                token::Client::new(&env, &invoice.token).transfer(
+                    &payer,
+                    &invoice.merchant,
+                    &charged,
+                );
+            }
+            Self::record_spending(&env, &payer, &invoice.token, charged);
+            Self::report_wallet_spend(&env, &payer, &invoice.token, charged);
+            if charged > 0 {
+                Self::issue_receipt(
+                    &env,
+                    &payer,
+                    &invoice.merchant,
+                    &invoice.token,
+                    charged,
+                    batch_id,
+                    None,
+                );
+            }
+            invoice.amount_paid += charged;
+            invoice.amount_discounted = discount;
+            invoice.status = InvoiceStatus::Paid;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Invoice(invoice_id), &invoice);
+
+            if let Some(code_hash) = &code_hash {
+                let mut record = Self::discount(&env, code_hash);
+                record.uses += 1;
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Discount(code_hash.clone()), &record);
+
+                // Topics: (discount, used, code_hash)
+                // Data: (invoice_id, discount)
+                let topics = (
+                    symbol_short!("discount"),
+                    symbol_short!("used"),
+                    code_hash.clone(),
+                );
+                env.events().publish(topics, (invoice_id, discount));
+            }
+
+            results.push_back(InvoiceResult::Paid(invoice_id, charged));
+            successful += 1;
+            total_paid += charged;
+
+            // Topics: (invoice, paid, invoice_id)
+            // Data: (payer, amount)
+            let topics = (symbol_short!("invoice"), symbol_short!("paid"), invoice_id);
+            env.events().publish(topics, (payer.clone(), charged));
+        }
+
+        BatchInvoiceResult {
+            total_requests: invoice_ids.len(),
+            successful,
+            failed,
+            total_paid,
+            results,
+        }
+    }
+
+    /// Registers a discount code by its SHA-256 hash.
+    ///
+    /// Only the hash is stored, so the code itself stays with the customers
+    /// it is handed to, who pass it to `batch_pay_invoices`.
+    ///
+    /// A discount only applies to invoices issued by its owner, so a merchant
+    /// cannot be made to accept another party's promotion.
+    ///
+    /// # Errors
+    /// * `InvalidDiscount` - If `bps` is 0 or above 10,000, or `max_uses` is 0
+    /// * `InvalidDeadline` - If `expiry` is not in the future
+    /// * `DiscountExists` - If the code is already registered
+    pub fn create_discount(
+        env: Env,
+        owner: Address,
+        code_hash: BytesN<32>,
+        bps: u32,
+        max_uses: u32,
+        expiry: u64,
+    ) {
+        owner.require_auth();
+
+        if bps == 0 || bps > TOTAL_SPLIT_BPS || max_uses == 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidDiscount);
+        }
+        if expiry <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchPaymentError::InvalidDeadline);
+        }
+        let key = DataKey::Discount(code_hash.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, BatchPaymentError::DiscountExists);
+        }
+
+        let discount = Discount {
+            code_hash: code_hash.clone(),
+            owner: owner.clone(),
+            bps,
+            max_uses,
+            uses: 0,
+            expiry,
+        };
+        env.storage().persistent().set(&key, &discount);
+
+        // Topics: (discount, created)
+        // Data: (owner, bps, max_uses, expiry)
+        let topics = (symbol_short!("discount"), symbol_short!("created"));
+        env.events().publish(topics, (owner, bps, max_uses, expiry));
+    }
+
+    /// Returns a discount by code hash.
+    pub fn get_discount(env: Env, code_hash: BytesN<32>) -> Option<Discount> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Discount(code_hash))
+    }
+
+    /// Pays part of an invoice. The invoice is marked `Paid` once the full
+    /// amount has been covered.
+    ///
+    /// # Returns
+    /// * `i128` - The balance still due after this payment
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` - If the invoice does not exist
+    /// * `Unauthorized` - If the caller is not the payer
+    /// * `InvoiceNotOpen` - If the invoice was already paid or cancelled
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `ExceedsInvoiceBalance` - If the amount exceeds the balance due
+    /// * `SpendingCapExceeded` - If the payment exceeds the payer's spending cap
+    pub fn pay_invoice(env: Env, payer: Address, invoice_id: u64, amount: i128) -> i128 {
+        payer.require_auth();
+
+        let mut invoice = Self::invoice(&env, invoice_id);
+        if invoice.payer != payer {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if invoice.status != InvoiceStatus::Open {
+            panic_with_error!(&env, BatchPaymentError::InvoiceNotOpen);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        let balance_due = invoice.amount - invoice.amount_paid;
+        if amount > balance_due {
+            panic_with_error!(&env, BatchPaymentError::ExceedsInvoiceBalance);
+        }
+
+        if !Self::within_spending_cap(&env, &payer, &invoice.token, amount) {
+            panic_with_error!(&env, BatchPaymentError::SpendingCapExceeded);
+        }
+        token::Client::new(&env, &invoice.token).transfer(&payer, &invoice.merchant, &amount);
+        Self::record_spending(&env, &payer, &invoice.token, amount);
+        Self::report_wallet_spend(&env, &payer, &invoice.token, amount);
+        let batch_id = Self::next_batch_id(&env);
+        Self::issue_receipt(
+            &env,
+            &payer,
+            &invoice.merchant,
+            &invoice.token,
+            amount,
+            batch_id,
+            None,
+        );
+
+        invoice.amount_paid += amount;
+        if invoice.amount_paid == invoice.amount {
+            invoice.status = InvoiceStatus::Paid;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Invoice(invoice_id), &invoice);
+
+        // Topics: (invoice, partial, invoice_id)
+        // Data: (payer, amount, balance_due)
+        let topics = (
+            symbol_short!("invoice"),
+            symbol_short!("partial"),
+            invoice_id,
+        );
+        env.events()
+            .publish(topics, (payer, amount, balance_due - amount));
+
+        balance_due - amount
+    }
+
+    /// Returns the amount still due on an invoice. Paid invoices, including
+    /// ones settled at a discount, and cancelled invoices owe nothing.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` - If the invoice does not exist
+    pub fn get_invoice_balance(env: Env, invoice_id: u64) -> i128 {
+        let invoice = Self::invoice(&env, invoice_id);
+        match invoice.status {
+            InvoiceStatus::Paid | InvoiceStatus::Cancelled => 0,
+            InvoiceStatus::Open | InvoiceStatus::Overdue => invoice.amount - invoice.amount_paid,
+        }
+    }
+
+    /// Cancels an open invoice.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` - If the invoice does not exist
+    /// * `Unauthorized` - If the caller is not the merchant
+    /// * `InvoiceNotOpen` - If the invoice was already paid or cancelled
+    pub fn cancel_invoice(env: Env, merchant: Address, invoice_id: u64) {
+        merchant.require_auth();
+
+        let mut invoice = Self::invoice(&env, invoice_id);
+        if invoice.merchant != merchant {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if invoice.status != InvoiceStatus::Open {
+            panic_with_error!(&env, BatchPaymentError::InvoiceNotOpen);
+        }
+
+        invoice.status = InvoiceStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Invoice(invoice_id), &invoice);
+
+        let topics = (
+            symbol_short!("invoice"),
+            symbol_short!("cancelled"),
+            invoice_id,
+        );
+        env.events().publish(topics, merchant);
+    }
+
+    /// Returns an invoice by ID, reporting unpaid invoices past their due
+    /// ledger as `Overdue`.
+    pub fn get_invoice(env: Env, invoice_id: u64) -> Option<Invoice> {
+        let invoice: Option<Invoice> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Invoice(invoice_id));
+        invoice.map(|mut invoice| {
+            if invoice.status == InvoiceStatus::Open && env.ledger().sequence() > invoice.due_ledger
+            {
+                invoice.status = InvoiceStatus::Overdue;
+            }
+            invoice
+        })
+    }
+
+    /// Creates a pull-payment request that the payer must approve.
+    ///
+    /// # Returns
+    /// * `u64` - The new request ID
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `InvalidDeadline` - If `expiry` is not in the future
+    pub fn request_payment(
+        env: Env,
+        merchant: Address,
+        payer: Address,
+        token: Address,
+        amount: i128,
+        expiry: u64,
+    ) -> u64 {
+        merchant.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if expiry <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchPaymentError::InvalidDeadline);
+        }
+
+        let request_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RequestCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::RequestCounter, &request_id);
+
+        let request = PaymentRequest {
+            request_id,
+            merchant: merchant.clone(),
+            payer: payer.clone(),
+            token,
+            amount,
+            expiry,
+            status: RequestStatus::Pending,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::PaymentRequest(request_id), &request);
+
+        // Topics: (request, created, request_id)
+        // Data: (merchant, payer, amount, expiry)
+        let topics = (
+            symbol_short!("request"),
+            symbol_short!("created"),
+            request_id,
+        );
+        env.events()
+            .publish(topics, (merchant, payer, amount, expiry));
+
+        request_id
+    }
+
+    /// Approves a pending payment request and pays the merchant.
+    ///
+    /// # Errors
+    /// * `RequestNotFound` - If the request does not exist
+    /// * `Unauthorized` - If the caller is not the requested payer
+    /// * `RequestNotPending` - If the request was already paid
+    /// * `RequestExpired` - If the request expired
+    /// * `InsufficientBalance` - If the payer cannot cover the amount
+    /// * `SpendingCapExceeded` - If the payment exceeds the payer's spending cap
+    pub fn approve_and_pay(env: Env, payer: Address, request_id: u64) -> i128 {
+        payer.require_auth();
+
+        let batch_id = Self::next_batch_id(&env);
+        Self::pay_request(&env, &payer, request_id, batch_id)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+    }
+
+    /// Approves several payment requests at once.
+    ///
+    /// Requests that cannot be paid are reported as failures without
+    /// reverting the others.
+    ///
+    /// # Errors
+    /// * `EmptyBatch` / `BatchTooLarge` - If the batch size is invalid
+    pub fn batch_approve_and_pay(
+        env: Env,
+        payer: Address,
+        request_ids: Vec<u64>,
+    ) -> BatchRequestResult {
+        payer.require_auth();
+
+        if request_ids.is_empty() {
+            panic_with_error!(&env, BatchPaymentError::EmptyBatch);
+        }
+        if request_ids.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchPaymentError::BatchTooLarge);
+        }
+
+        let batch_id = Self::next_batch_id(&env);
+        let mut results: Vec<RequestResult> = Vec::new(&env);
+        let mut successful: u32 = 0;
+        let mut failed: u32 = 0;
+        let mut total_paid: i128 = 0;
+
+        for request_id in request_ids.iter() {
+            match Self::pay_request(&env, &payer, request_id, batch_id) {
+                Ok(amount) => {
+                    results.push_back(RequestResult::Paid(request_id, amount));
+                    successful += 1;
+                    total_paid += amount;
+                }
+                Err(error) => {
+                    results.push_back(RequestResult::Failure(request_id, error as u32));
+                    failed += 1;
+                }
+            }
+        }
+
+        BatchRequestResult {
+            total_requests: request_ids.len(),
+            successful,
+            failed,
+            total_paid,
+            results,
+        }
+    }
+
+    /// Returns a payment request by ID, reporting unapproved requests past
+    /// their expiry as `Expired`.
+    pub fn get_payment_request(env: Env, request_id: u64) -> Option<PaymentRequest> {
+        let request: Option<PaymentRequest> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(request_id));
+        request.map(|mut request| {
+            if request.status == RequestStatus::Pending && env.ledger().timestamp() > request.expiry
+            {
+                request.status = RequestStatus::Expired;
+            }
+            request
+        })
+    }
+
+    /// Creates a recurring subscription charged by `charge_due_subscriptions`.
+    ///
+    /// The first cycle is due immediately. Charges are pulled with
+    /// `transfer_from`, so the payer must approve this contract as a spender
+    /// on `token` for at least the amounts to be charged.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `InvalidInterval` - If the interval is zero
+    pub fn create_subscription(
+        env: Env,
+        payer: Address,
+        merchant: Address,
+        token: Address,
+        amount: i128,
+        interval: u64,
+        max_cycles: u32,
+    ) -> u64 {
+        payer.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if interval == 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidInterval);
+        }
+
+        let subscription_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SubscriptionCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::SubscriptionCounter, &subscription_id);
+
+        let subscription = Subscription {
+            subscription_id,
+            payer: payer.clone(),
+            merchant: merchant.clone(),
+            token,
+            amount,
+            interval,
+            max_cycles,
+            cycles_charged: 0,
+            next_charge: env.ledger().timestamp(),
+            status: SubscriptionStatus::Active,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Subscription(subscription_id), &subscription);
+
+        Self::add_open_subscription(&env, subscription_id);
+
+        // Topics: (sub, created, subscription_id)
+        // Data: (payer, merchant, amount, interval)
+        let topics = (
+            symbol_short!("sub"),
+            symbol_short!("created"),
+            subscription_id,
+        );
+        env.events()
+            .publish(topics, (payer, merchant, amount, interval));
+
+        subscription_id
+    }
+
+    /// Charges active subscriptions whose cycle is due. Callable by anyone.
+    ///
+    /// Each call looks at up to `limit` active subscriptions, resuming where
+    /// the previous call stopped and wrapping around, so keepers cover every
+    /// subscription over successive calls. A charge that fails (e.g. missing
+    /// allowance) stays due and is retried when its turn comes again. Each
+    /// charge covers one cycle; cycles that fell due in between are skipped,
+    /// not charged back.
+    ///
+    /// # Returns
+    /// * `u32` - The number of successful charges
+    pub fn charge_due_subscriptions(env: Env, limit: u32) -> u32 {
+        let mut open = Self::open_subscriptions(&env);
+        let now = env.ledger().timestamp();
+        let contract = env.current_contract_address();
+
+        let mut index: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SubscriptionCursor)
+            .unwrap_or(0);
+        let steps = limit.min(open.len());
+        let mut completed = false;
+        let mut charged: u32 = 0;
+        let mut batch_id: Option<u64> = None;
+
+        for _ in 0..steps {
+            if index >= open.len() {
+                index = 0;
+            }
+            let subscription_id = open.get_unchecked(index);
+            let mut subscription = Self::subscription(&env, subscription_id);
+            if subscription.next_charge > now {
+                index += 1;
+                continue;
+            }
+
+            let charged_ok = Self::within_spending_cap(
+                &env,
+                &subscription.payer,
+                &subscription.token,
+                subscription.amount,
+            ) && Self::within_wallet_limit(
+                &env,
+                &subscription.payer,
+                &subscription.token,
+                subscription.amount,
+            ) && matches!(
+                token::Client::new(&env, &subscription.token).try_transfer_from(
+                    &contract,
+                    &subscription.payer,
+                    &subscription.merchant,
+                    &subscription.amount,
+                ),
+                Ok(Ok(()))
+            );
+            if !charged_ok {
+                index += 1;
+
+                // Topics: (sub_fail, subscription_id)
+                // Data: amount
+                let topics = (symbol_short!("sub_fail"), subscription_id);
+                env.events().publish(topics, subscription.amount);
+                continue;
+            }
+
+            Self::record_spending(
+                &env,
+                &subscription.payer,
+                &subscription.token,
+                subscription.amount,
+            );
+            Self::report_wallet_spend(
+                &env,
+                &subscription.payer,
+                &subscription.token,
+                subscription.amount,
+            );
+            let charge_batch_id = *batch_id.get_or_insert_with(|| Self::next_batch_id(&env));
+            Self::issue_receipt(
+                &env,
+                &subscription.payer,
+                &subscription.merchant,
+                &subscription.token,
+                subscription.amount,
+                charge_batch_id,
+                None,
+            );
+            charged += 1;
+            subscription.cycles_charged += 1;
+            // Cycles missed while the charge kept failing are not charged back
+            subscription.next_charge = Self::next_charge_slot(&subscription, now.saturating_add(1));
+            if subscription.max_cycles > 0 && subscription.cycles_charged >= subscription.max_cycles
+            {
+                subscription.status = SubscriptionStatus::Completed;
+                open.remove(index);
+                completed = true;
+            } else {
+                index += 1;
+            }
+            env.storage()
+                .persistent()
+                .set(&DataKey::Subscription(subscription_id), &subscription);
+
+            // Topics: (sub, charged, subscription_id)
+            // Data: (amount, cycles_charged)
+            let topics = (
+                symbol_short!("sub"),
+                symbol_short!("charged"),
+                subscription_id,
+            );
+            env.events()
+                .publish(topics, (subscription.amount, subscription.cycles_charged));
+        }
+
+        if completed {
+            env.storage()
+                .persistent()
+                .set(&DataKey::OpenSubscriptions, &open);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SubscriptionCursor, &index);
+
+        charged
+    }
+
+    /// Pauses an active subscription. Only the payer can pause.
+    ///
+    /// # Errors
+    /// * `SubscriptionNotFound` - If the subscription does not exist
+    /// * `Unauthorized` - If the caller is not the payer
+    /// * `InvalidSubscriptionStatus` - If the subscription is not active
+    pub fn pause_subscription(env: Env, payer: Address, subscription_id: u64) {
+        payer.require_auth();
+        Self::set_subscription_status(
+            &env,
+            &payer,
+            subscription_id,
+            SubscriptionStatus::Active,
+            SubscriptionStatus::Paused,
+        );
+        Self::remove_open_subscription(&env, subscription_id);
+    }
+
+    /// Resumes a paused subscription. Only the payer can resume.
+    ///
+    /// Cycles that fell due while paused are skipped; the next charge is the
+    /// first cycle at or after the current time.
+    ///
+    /// # Errors
+    /// * `SubscriptionNotFound` - If the subscription does not exist
+    /// * `Unauthorized` - If the caller is not the payer
+    /// * `InvalidSubscriptionStatus` - If the subscription is not paused
+    pub fn resume_subscription(env: Env, payer: Address, subscription_id: u64) {
+        payer.require_auth();
+        Self::set_subscription_status(
+            &env,
+            &payer,
+            subscription_id,
+            SubscriptionStatus::Paused,
+            SubscriptionStatus::Active,
+        );
+        Self::add_open_subscription(&env, subscription_id);
+    }
+
+    /// Cancels a subscription. Either the payer or the merchant can cancel.
+    ///
+    /// # Errors
+    /// * `SubscriptionNotFound` - If the subscription does not exist
+    /// * `Unauthorized` - If the caller is neither payer nor merchant
+    /// * `InvalidSubscriptionStatus` - If the subscription already ended
+    pub fn cancel_subscription(env: Env, caller: Address, subscription_id: u64) {
+        caller.require_auth();
+
+        let mut subscription = Self::subscription(&env, subscription_id);
+        if caller != subscription.payer && caller != subscription.merchant {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if subscription.status != SubscriptionStatus::Active
+            && subscription.status != SubscriptionStatus::Paused
+        {
+            panic_with_error!(&env, BatchPaymentError::InvalidSubscriptionStatus);
+        }
+
+        subscription.status = SubscriptionStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Subscription(subscription_id), &subscription);
+
+        Self::remove_open_subscription(&env, subscription_id);
+
+        let topics = (
+            symbol_short!("sub"),
+            symbol_short!("cancelled"),
+            subscription_id,
+        );
+        env.events().publish(topics, caller);
+    }
+
+    /// Returns a subscription by ID.
+    pub fn get_subscription(env: Env, subscription_id: u64) -> Option<Subscription> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscription_id))
+    }
+
+    /// Funds a payment now and queues it for release at `execute_after`.
+    ///
+    /// # Returns
+    /// * `u64` - The scheduled payment ID
+    ///
+    /// # Errors
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `InvalidDeadline` - If `execute_after` is not in the future
+    pub fn schedule_payment(
+        env: Env,
+        from: Address,
+        token: Address,
+        payment: Payment,
+        execute_after: u64,
+    ) -> u64 {
+        from.require_auth();
+
+        if payment.amount <= 0 {
+            panic_with_error!(&env, BatchPaymentError::InvalidAmount);
+        }
+        if execute_after <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchPaymentError::InvalidDeadline);
+        }
+
+        let payment_token = payment.token.unwrap_or(token);
+        if !Self::within_spending_cap(&env, &from, &payment_token, payment.amount) {
+            panic_with_error!(&env, BatchPaymentError::SpendingCapExceeded);
+        }
+        token::Client::new(&env, &payment_token).transfer(
+            &from,
+            &env.current_contract_address(),
+            &payment.amount,
+        );
+        Self::record_spending(&env, &from, &payment_token, payment.amount);
+        Self::report_wallet_spend(&env, &from, &payment_token, payment.amount);
+
+        let schedule_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ScheduleCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::ScheduleCounter, &schedule_id);
+
+        let scheduled = ScheduledPayment {
+            schedule_id,
+            sender: from,
+            recipient: payment.recipient.clone(),
+            token: payment_token.clone(),
+            amount: payment.amount,
+            reference: payment.reference,
+            execute_after,
+            status: ScheduleStatus::Pending,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::ScheduledPayment(schedule_id), &scheduled);
+
+        // Topics: (scheduled, schedule_id, recipient)
+        // Data: (token, amount, execute_after)
+        let topics = (symbol_short!("scheduled"), schedule_id, payment.recipient);
+        env.events()
+            .publish(topics, (payment_token, payment.amount, execute_after));
+
+        schedule_id
+    }
+
+    /// Releases a due scheduled payment to its recipient. Callable by anyone,
+    /// so a keeper can execute queued payments.
+    ///
+    /// # Errors
+    /// * `ScheduleNotFound` - If the scheduled payment does not exist
+    /// * `ScheduleNotPending` - If it was already executed or cancelled
+    /// * `ScheduleNotDue` - If `execute_after` has not been reached
+    pub fn execute_scheduled_payment(env: Env, schedule_id: u64) -> i128 {
+        let mut scheduled = Self::scheduled_payment(&env, schedule_id);
+        if scheduled.status != ScheduleStatus::Pending {
+            panic_with_error!(&env, BatchPaymentError::ScheduleNotPending);
+        }
+        if env.ledger().timestamp() < scheduled.execute_after {
+            panic_with_error!(&env, BatchPaymentError::ScheduleNotDue);
+        }
+
+        scheduled.status = ScheduleStatus::Executed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::ScheduledPayment(schedule_id), &scheduled);
+
+        token::Client::new(&env, &scheduled.token).transfer(
+            &env.current_contract_address(),
+            &scheduled.recipient,
+            &scheduled.amount,
+        );
+        let batch_id = Self::next_batch_id(&env);
+        Self::issue_receipt(
+            &env,
+            &scheduled.sender,
+            &scheduled.recipient,
+            &scheduled.token,
+            scheduled.amount,
+            batch_id,
+            scheduled.reference.clone(),
+        );
+
+        // Topics: (sched_exe, schedule_id, recipient)
+        // Data: (amount, reference)
+        let topics = (symbol_short!("sched_exe"), schedule_id, scheduled.recipient);
+        env.events()
+            .publish(topics, (scheduled.amount, scheduled.reference));
+
+        scheduled.amount
+    }
+
+    /// Cancels a pending scheduled payment and refunds the sender.
+    ///
+    /// # Errors
+    /// * `ScheduleNotFound` - If the scheduled payment does not exist
+    /// * `Unauthorized` - If the caller is not the sender
+    /// * `ScheduleNotPending` - If it was already executed or cancelled
+    pub fn cancel_scheduled_payment(env: Env, sender: Address, schedule_id: u64) -> i128 {
+        sender.require_auth();
+
+        let mut scheduled = Self::scheduled_payment(&env, schedule_id);
+        if scheduled.sender != sender {
+            panic_with_error!(&env, BatchPaymentError::Unauthorized);
+        }
+        if scheduled.status != ScheduleStatus::Pending {
+            panic_with_error!(&env, BatchPaymentError::ScheduleNotPending);
+        }
+
+        scheduled.status = ScheduleStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::ScheduledPayment(schedule_id), &scheduled);
+
+        token::Client::new(&env, &scheduled.token).transfer(
+            &env.current_contract_address(),
+            &sender,
+            &scheduled.amount,
+        );
+
+        // Topics: (sched_cnl, schedule_id, sender)
+        // Data: amount
+        let topics = (symbol_short!("sched_cnl"), schedule_id, sender);
+        env.events().publish(topics, scheduled.amount);
+
+        scheduled.amount
+    }
+
+    /// Returns a scheduled payment by ID.
+    pub fn get_scheduled_payment(env: Env, schedule_id: u64) -> Option<ScheduledPayment> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ScheduledPayment(schedule_id))
+    }
+
+    // Internal helper that settles one payment request for an authorized payer
+    fn pay_request(
+        env: &Env,
+        payer: &Address,
+        request_id: u64,
+        batch_id: u64,
+    ) -> Result<i128, BatchPaymentError> {
+        let mut request: PaymentRequest = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(request_id))
+            .ok_or(BatchPaymentError::RequestNotFound)?;
+        if request.payer != *payer {
+            return Err(BatchPaymentError::Unauthorized);
+        }
+        if request.status != RequestStatus::Pending {
+            return Err(BatchPaymentError::RequestNotPending);
+        }
+        if env.ledger().timestamp() > request.expiry {
+            return Err(BatchPaymentError::RequestExpired);
+        }
+
+        let token_client = token::Client::new(env, &request.token);
+        if token_client.balance(payer) < request.amount {
+            return Err(BatchPaymentError::InsufficientBalance);
+        }
+        if !Self::within_spending_cap(env, payer, &request.token, request.amount) {
+            return Err(BatchPaymentError::SpendingCapExceeded);
+        }
+        token_client.transfer(payer, &request.merchant, &request.amount);
+        Self::record_spending(env, payer, &request.token, request.amount);
+        Self::report_wallet_spend(env, payer, &request.token, request.amount);
+        Self::issue_receipt(
+            env,
+            payer,
+            &request.merchant,
+            &request.token,
+            request.amount,
+            batch_id,
+            None,
+        );
+
+        request.status = RequestStatus::Paid;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PaymentRequest(request_id), &request);
+
+        // Topics: (request, paid, request_id)
+        // Data: (payer, amount)
+        let topics = (symbol_short!("request"), symbol_short!("paid"), request_id);
+        env.events()
+            .publish(topics, (payer.clone(), request.amount));
+
+        Ok(request.amount)
+    }
+
+    // Internal helper that pays an authorized batch, skipping invalid payments
+    fn pay_batch(
+        env: Env,
+        from: Address,
+        token: Address,
+        payments: Vec<Payment>,
+    ) -> BatchPaymentResult {
+        // Validate batch size
+        if payments.is_empty() {
+            panic_with_error!(&env, BatchPaymentError::EmptyBatch);
+        }
+        if payments.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchPaymentError::BatchTooLarge);
+        }
+
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        let history_contract: Option<Address> =
+            env.storage().instance().get(&DataKey::HistoryContract);
+        let mut history: Vec<(Address, TransactionRecord)> = Vec::new(&env);
+
+        // Balances are looked up once per token and tracked as payments settle
+        let mut balances: Map<Address, i128> = Map::new(&env);
+        let mut token_totals: Map<Address, i128> = Map::new(&env);
 
         let mut total_amount: i128 = 0;
         let mut count: u32 = 0;
+        let mut failed: u32 = 0;
+        let mut results: Vec<PaymentResult> = Vec::new(&env);
 
-        // Generate a pseudo-unique batch ID based on ledger and timestamp (just for event tracking)
-        let batch_id = env.ledger().sequence() as u64; // Simple ID for now
+        let batch_id = Self::next_batch_id(&env);
+        let mut payment_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PaymentCounter)
+            .unwrap_or(0);
 
         for payment in payments.iter() {
+            let payment_token = payment.token.clone().unwrap_or_else(|| token.clone());
+            let token_client = token::Client::new(&env, &payment_token);
+
             // Validation
-            if payment.amount <= 0 {
-                panic!("Payment amount must be positive");
+            let available_balance = match balances.get(payment_token.clone()) {
+                Some(balance) => Some(balance),
+                None => match token_client.try_balance(&from) {
+                    Ok(Ok(balance)) => {
+                        balances.set(payment_token.clone(), balance);
+                        Some(balance)
+                    }
+                    _ => None,
+                },
+            };
+            // Platform fee routed to the collector, if it can be computed
+            let fee = match &fee_config {
+                Some(config) => payment
+                    .amount
+                    .checked_mul(config.fee_bps as i128)
+                    .map(|value| value / TOTAL_SPLIT_BPS as i128),
+                None => Some(0),
+            };
+            let error = match available_balance {
+                _ if payment.amount <= 0 || fee.is_none() => Some(BatchPaymentError::InvalidAmount),
+                None => Some(BatchPaymentError::InvalidToken),
+                Some(balance) if payment.amount > balance => {
+                    Some(BatchPaymentError::InsufficientBalance)
+                }
+                Some(_)
+                    if !Self::within_spending_cap(&env, &from, &payment_token, payment.amount) =>
+                {
+                    Some(BatchPaymentError::SpendingCapExceeded)
+                }
+                Some(_) if Self::recipient_wallet_inactive(&env, &payment.recipient) => {
+                    Some(BatchPaymentError::RecipientInactive)
+                }
+                Some(_) => None,
+            };
+
+            if let Some(error) = error {
+                let code = error as u32;
+                results.push_back(PaymentResult::Failure(
+                    payment.recipient.clone(),
+                    payment.amount,
+                    code,
+                ));
+                failed += 1;
+
+                // Emit per-payment failure event
+                // Topics: (pay_fail, batch_id, recipient)
+                // Data: (amount, error_code, reference)
+                let topics = (
+                    symbol_short!("pay_fail"),
+                    batch_id,
+                    payment.recipient.clone(),
+                );
+                env.events()
+                    .publish(topics, (payment.amount, code, payment.reference.clone()));
+                continue;
             }
 
-            // Execute transfer
-            token_client.transfer(&from, &payment.recipient, &payment.amount);
+            // Execute transfer, routing the platform fee to the collector
+            let fee = fee.unwrap_or(0);
+            if fee > 0 {
+                let collector = fee_config.as_ref().unwrap().collector.clone();
+                token_client.transfer(&from, &collector, &fee);
+
+                let key = DataKey::AccruedFees(payment_token.clone());
+                let accrued: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+                env.storage().persistent().set(&key, &(accrued + fee));
+
+                // Topics: (fee, batch_id, collector)
+                // Data: (token, fee)
+                let topics = (symbol_short!("fee"), batch_id, collector);
+                env.events().publish(topics, (payment_token.clone(), fee));
+            }
+            let net_amount = payment.amount - fee;
+            if net_amount > 0 {
+                token_client.transfer(&from, &payment.recipient, &net_amount);
+            }
+            Self::record_spending(&env, &from, &payment_token, payment.amount);
+            let balance = balances.get(payment_token.clone()).unwrap_or(0);
+            balances.set(payment_token.clone(), balance - payment.amount);
+            let paid = token_totals.get(payment_token.clone()).unwrap_or(0);
+            token_totals.set(payment_token.clone(), paid + payment.amount);
 
             total_amount += payment.amount;
             count += 1;
+            results.push_back(PaymentResult::Success(
+                payment.recipient.clone(),
+                payment.amount,
+            ));
+
+            // Record the payment so the recipient can refund it later
+            payment_id += 1;
+            let record = PaymentRecord {
+                payment_id,
+                batch_id,
+                sender: from.clone(),
+                recipient: payment.recipient.clone(),
+                token: payment_token.clone(),
+                amount: net_amount,
+                reference: payment.reference.clone(),
+                refunded: 0,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::PaymentRecord(payment_id), &record);
+
+            let receipt = Receipt {
+                payment_id,
+                payer: from.clone(),
+                recipient: payment.recipient.clone(),
+                token: payment_token.clone(),
+                amount: net_amount,
+                batch_id,
+                timestamp: env.ledger().timestamp(),
+                reference: payment.reference.clone(),
+            };
+            Self::store_receipt(&env, &receipt);
+
+            if history_contract.is_some() {
+                history.push_back((
+                    from.clone(),
+                    TransactionRecord {
+                        amount: -payment.amount,
+                        timestamp: receipt.timestamp,
+                        description: String::from_str(&env, "Payment sent"),
+                        category: symbol_short!("payment"),
+                        counterparty: Some(payment.recipient.clone()),
+                        tx_type: TxType::Payment,
+                        source_contract: Some(env.current_contract_address()),
+                        batch_id: Some(batch_id),
+                    },
+                ));
+                history.push_back((
+                    payment.recipient.clone(),
+                    TransactionRecord {
+                        amount: net_amount,
+                        timestamp: receipt.timestamp,
+                        description: String::from_str(&env, "Payment received"),
+                        category: symbol_short!("payment"),
+                        counterparty: Some(from.clone()),
+                        tx_type: TxType::Payment,
+                        source_contract: Some(env.current_contract_address()),
+                        batch_id: Some(batch_id),
+                    },
+                ));
+            }
 
             // Emit per-payment event
             // Topics: (payment, batch_id, recipient)
-            // Data: (token, amount)
+            // Data: (token, amount, payment_id, reference)
             let topics = (
                 symbol_short!("payment"),
                 batch_id,
                 payment.recipient.clone(),
             );
-            env.events()
-                .publish(topics, (token.clone(), payment.amount));
+            env.events().publish(
+                topics,
+                (payment_token, payment.amount, payment_id, payment.reference),
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PaymentCounter, &payment_id);
+
+        for (paid_token, paid) in token_totals.iter() {
+            Self::report_wallet_spend(&env, &from, &paid_token, paid);
+        }
+
+        if let Some(contract) = history_contract {
+            if !history.is_empty() {
+                let _ = HistoryClient::new(&env, &contract)
+                    .try_batch_record_from_contract(&env.current_contract_address(), &history);
+            }
         }
 
         // Emit batch completion event
         // Topics: (batch, complete, batch_id)
-        // Data: (total_payments, total_amount)
+        // Data: (total_payments, total_amount, token_totals)
         let topics = (symbol_short!("batch"), symbol_short!("complete"), batch_id);
-        env.events().publish(topics, (count, total_amount));
+        env.events()
+            .publish(topics, (count, total_amount, token_totals.clone()));
+
+        BatchPaymentResult {
+            batch_id,
+            total_requests: payments.len(),
+            successful: count,
+            failed,
+            total_amount,
+            token_totals,
+            results,
+        }
+    }
+
+    // Internal helper to check the caller is the admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+
+        if *caller != admin {
+            panic_with_error!(env, BatchPaymentError::Unauthorized);
+        }
+    }
+
+    // Internal helper returning the payer's cap rolled over to the current
+    // period, with any queued change applied from the first period boundary
+    fn current_spending_cap(env: &Env, payer: &Address, token: &Address) -> Option<SpendingCap> {
+        let spending_cap: Option<SpendingCap> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SpendingCap(payer.clone(), token.clone()));
+        let mut spending_cap = spending_cap?;
+        let now = env.ledger().timestamp();
+        if now >= spending_cap.window_start + spending_cap.period {
+            if spending_cap.clear_pending {
+                return None;
+            }
+            spending_cap.window_start += spending_cap.period;
+            if let Some(cap) = spending_cap.pending_cap.take() {
+                spending_cap.cap = cap;
+                spending_cap.period = spending_cap.pending_period;
+            }
+            if now >= spending_cap.window_start + spending_cap.period {
+                let elapsed = (now - spending_cap.window_start) / spending_cap.period;
+                spending_cap.window_start += elapsed * spending_cap.period;
+            }
+            spending_cap.spent = 0;
+        }
+        Some(spending_cap)
+    }
+
+    // Internal helper asking the wallet contract, when configured, whether the
+    // recipient's wallet is frozen or closed. A failed lookup counts as active
+    // so a broken wallet contract cannot revert the whole payout.
+    fn recipient_wallet_inactive(env: &Env, recipient: &Address) -> bool {
+        let contract: Option<Address> = env.storage().instance().get(&DataKey::WalletContract);
+        match contract {
+            Some(contract) => matches!(
+                WalletClient::new(env, &contract).try_get_wallet_status(recipient),
+                Ok(Ok(Some(WalletStatus::Frozen))) | Ok(Ok(Some(WalletStatus::Closed)))
+            ),
+            None => false,
+        }
+    }
+
+    // Internal helper reporting a payer-funded payment to the wallet contract,
+    // when configured. Only payments in the limit token count as a spend, and
+    // only a spend past the payer's daily limit reverts; payers without a
+    // wallet are skipped, and so is every payer while the wallet contract fails.
+    fn report_wallet_spend(env: &Env, payer: &Address, token: &Address, amount: i128) {
+        let contract: Option<Address> = env.storage().instance().get(&DataKey::WalletContract);
+        let Some(contract) = contract else {
+            return;
+        };
+        if amount <= 0 {
+            return;
+        }
+        let client = WalletClient::new(env, &contract);
+        let caller = env.current_contract_address();
+        if Self::is_wallet_limit_token(env, token) {
+            if let Err(Ok(error)) = client.try_record_spend(&caller, payer, &amount) {
+                if error == soroban_sdk::Error::from_contract_error(WALLET_DAILY_LIMIT_EXCEEDED) {
+                    panic_with_error!(env, BatchPaymentError::WalletLimitExceeded);
+                }
+            }
+        }
+        let _ = client.try_bump_activity(&caller, payer);
+    }
+
+    // Internal helper to check a payment fits within the payer's wallet daily
+    // limit, for paths that skip a payment instead of reverting
+    fn within_wallet_limit(env: &Env, payer: &Address, token: &Address, amount: i128) -> bool {
+        let contract: Option<Address> = env.storage().instance().get(&DataKey::WalletContract);
+        match contract {
+            Some(contract) if Self::is_wallet_limit_token(env, token) => {
+                match WalletClient::new(env, &contract).try_get_remaining_daily_limit(payer) {
+                    Ok(Ok(remaining)) => amount <= remaining,
+                    _ => true,
+                }
+            }
+            _ => true,
+        }
+    }
+
+    // Internal helper to check whether a token counts against wallet limits
+    fn is_wallet_limit_token(env: &Env, token: &Address) -> bool {
+        let limit_token: Option<Address> = env.storage().instance().get(&DataKey::WalletLimitToken);
+        limit_token.as_ref() == Some(token)
+    }
+
+    // Internal helper to check a payment fits within the payer's spending cap
+    fn within_spending_cap(env: &Env, payer: &Address, token: &Address, amount: i128) -> bool {
+        Self::current_spending_cap(env, payer, token)
+            .map(|spending_cap| spending_cap.spent + amount <= spending_cap.cap)
+            .unwrap_or(true)
+    }
+
+    // Internal helper to count a settled payment against the payer's spending cap
+    fn record_spending(env: &Env, payer: &Address, token: &Address, amount: i128) {
+        if let Some(mut spending_cap) = Self::current_spending_cap(env, payer, token) {
+            spending_cap.spent += amount;
+            env.storage().persistent().set(
+                &DataKey::SpendingCap(payer.clone(), token.clone()),
+                &spending_cap,
+            );
+        }
+    }
+
+    // Internal helper to assign the next monotonic batch ID
+    fn next_batch_id(env: &Env) -> u64 {
+        let batch_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastBatchId)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::LastBatchId, &batch_id);
+        batch_id
+    }
+
+    // Internal helper to load a subscription or panic
+    fn subscription(env: &Env, subscription_id: u64) -> Subscription {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscription_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::SubscriptionNotFound))
+    }
+
+    // Internal helper loading the IDs of active subscriptions
+    fn open_subscriptions(env: &Env) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OpenSubscriptions)
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Internal helper queueing an active subscription for the keeper
+    fn add_open_subscription(env: &Env, subscription_id: u64) {
+        let mut open = Self::open_subscriptions(env);
+        open.push_back(subscription_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::OpenSubscriptions, &open);
+    }
+
+    // Internal helper dropping a paused or ended subscription from the keeper's queue
+    fn remove_open_subscription(env: &Env, subscription_id: u64) {
+        let mut open = Self::open_subscriptions(env);
+        if let Some(index) = open.first_index_of(subscription_id) {
+            open.remove(index);
+            env.storage()
+                .persistent()
+                .set(&DataKey::OpenSubscriptions, &open);
+        }
+    }
+
+    // Internal helper returning the first charge slot of a subscription at or
+    // after `from`, skipping the cycles that fell due before it
+    fn next_charge_slot(subscription: &Subscription, from: u64) -> u64 {
+        if subscription.next_charge >= from {
+            return subscription.next_charge;
+        }
+        let missed = (from - subscription.next_charge).div_ceil(subscription.interval);
+        subscription
+            .next_charge
+            .saturating_add(missed.saturating_mul(subscription.interval))
+    }
+
+    // Internal helper for payer-driven pause/resume transitions
+    fn set_subscription_status(
+        env: &Env,
+        payer: &Address,
+        subscription_id: u64,
+        from: SubscriptionStatus,
+        to: SubscriptionStatus,
+    ) {
+        let mut subscription = Self::subscription(env, subscription_id);
+        if subscription.payer != *payer {
+            panic_with_error!(env, BatchPaymentError::Unauthorized);
+        }
+        if subscription.status != from {
+            panic_with_error!(env, BatchPaymentError::InvalidSubscriptionStatus);
+        }
+
+        subscription.status = to.clone();
+        if to == SubscriptionStatus::Active {
+            subscription.next_charge =
+                Self::next_charge_slot(&subscription, env.ledger().timestamp());
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Subscription(subscription_id), &subscription);
+
+        // Topics: (sub, status, subscription_id)
+        // Data: new status
+        let topics = (
+            symbol_short!("sub"),
+            symbol_short!("status"),
+            subscription_id,
+        );
+        env.events().publish(topics, to);
+    }
+
+    // Internal helper to number a settled payment and store its receipt
+    fn issue_receipt(
+        env: &Env,
+        payer: &Address,
+        recipient: &Address,
+        token: &Address,
+        amount: i128,
+        batch_id: u64,
+        reference: Option<BytesN<32>>,
+    ) {
+        let payment_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PaymentCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::PaymentCounter, &payment_id);
+
+        let receipt = Receipt {
+            payment_id,
+            payer: payer.clone(),
+            recipient: recipient.clone(),
+            token: token.clone(),
+            amount,
+            batch_id,
+            timestamp: env.ledger().timestamp(),
+            reference,
+        };
+        Self::store_receipt(env, &receipt);
+    }
+
+    // Internal helper to persist a receipt and index it for both parties
+    fn store_receipt(env: &Env, receipt: &Receipt) {
+        let storage = env.storage().persistent();
+        storage.set(&DataKey::Receipt(receipt.payment_id), receipt);
+
+        let payer_count: u32 = storage
+            .get(&DataKey::PayerReceiptCount(receipt.payer.clone()))
+            .unwrap_or(0);
+        storage.set(
+            &DataKey::PayerReceipt(receipt.payer.clone(), payer_count),
+            &receipt.payment_id,
+        );
+        storage.set(
+            &DataKey::PayerReceiptCount(receipt.payer.clone()),
+            &(payer_count + 1),
+        );
+
+        let recipient_count: u32 = storage
+            .get(&DataKey::RecipientReceiptCount(receipt.recipient.clone()))
+            .unwrap_or(0);
+        storage.set(
+            &DataKey::RecipientReceipt(receipt.recipient.clone(), recipient_count),
+            &receipt.payment_id,
+        );
+        storage.set(
+            &DataKey::RecipientReceiptCount(receipt.recipient.clone()),
+            &(recipient_count + 1),
+        );
+    }
+
+    // Internal helper to load a receipt that is known to exist
+    fn receipt(env: &Env, payment_id: u64) -> Receipt {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Receipt(payment_id))
+            .unwrap()
+    }
+
+    // Internal helper to load a registered merchant or panic
+    fn merchant(env: &Env, merchant_id: &Symbol) -> Merchant {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Merchant(merchant_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::MerchantNotFound))
+    }
+
+    // Internal helper to load an invoice or panic
+    fn invoice(env: &Env, invoice_id: u64) -> Invoice {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Invoice(invoice_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::InvoiceNotFound))
+    }
+
+    // Internal helper that checks an invoice can be paid by `payer`, returning
+    // it with the discount to apply or an `InvoiceResult` failure code
+    fn payable_invoice(
+        env: &Env,
+        payer: &Address,
+        invoice_id: u64,
+        discount_code: &Option<BytesN<32>>,
+    ) -> Result<(Invoice, i128), BatchPaymentError> {
+        let invoice: Invoice = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Invoice(invoice_id))
+            .ok_or(BatchPaymentError::InvoiceNotFound)?;
+        if invoice.payer != *payer {
+            return Err(BatchPaymentError::Unauthorized);
+        }
+        if invoice.status != InvoiceStatus::Open {
+            return Err(BatchPaymentError::InvoiceNotOpen);
+        }
+
+        let discount = match discount_code {
+            Some(code_hash) => {
+                let record: Discount = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::Discount(code_hash.clone()))
+                    .ok_or(BatchPaymentError::InvalidDiscount)?;
+                if record.owner != invoice.merchant
+                    || record.uses >= record.max_uses
+                    || env.ledger().timestamp() > record.expiry
+                {
+                    return Err(BatchPaymentError::InvalidDiscount);
+                }
+                (invoice.amount - invoice.amount_paid) * record.bps as i128
+                    / TOTAL_SPLIT_BPS as i128
+            }
+            None => 0,
+        };
+
+        let balance = token::Client::new(env, &invoice.token).balance(payer);
+        let charge = invoice.amount - invoice.amount_paid - discount;
+        if balance < charge {
+            return Err(BatchPaymentError::InsufficientBalance);
+        }
+        if !Self::within_spending_cap(env, payer, &invoice.token, charge) {
+            return Err(BatchPaymentError::SpendingCapExceeded);
+        }
+
+        Ok((invoice, discount))
+    }
+
+    // Internal helper to load a discount or panic
+    fn discount(env: &Env, code_hash: &BytesN<32>) -> Discount {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Discount(code_hash.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::InvalidDiscount))
+    }
+
+    // Internal helper to load a scheduled payment or panic
+    fn scheduled_payment(env: &Env, schedule_id: u64) -> ScheduledPayment {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ScheduledPayment(schedule_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::ScheduleNotFound))
+    }
+
+    // Internal helper to load an escrowed payment or panic
+    fn escrowed_payment(env: &Env, payment_id: u64) -> EscrowedPayment {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EscrowedPayment(payment_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchPaymentError::PaymentNotFound))
     }
 }
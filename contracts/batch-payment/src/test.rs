@@ -3,8 +3,8 @@ extern crate std;
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    Address, Env, Vec,
+    testutils::{Address as _, Events, Ledger},
+    Address, Bytes, BytesN, Env, String, Symbol, Vec,
 };
 
 #[test]
@@ -35,10 +35,14 @@ fn test_batch_transfer() {
     payments.push_back(Payment {
         recipient: user1.clone(),
         amount: 100,
+        reference: None,
+        token: None,
     });
     payments.push_back(Payment {
         recipient: user2.clone(),
         amount: 200,
+        reference: None,
+        token: None,
     });
 
     // Execute batch transfer
@@ -64,7 +68,6 @@ fn test_batch_transfer() {
 }
 
 #[test]
-#[should_panic(expected = "Payment amount must be positive")]
 fn test_batch_transfer_zero_amount() {
     let env = Env::default();
     env.mock_all_auths();
@@ -81,9 +84,1210 @@ fn test_batch_transfer_zero_amount() {
 
     let mut payments = Vec::new(&env);
     payments.push_back(Payment {
-        recipient: user1,
+        recipient: user1.clone(),
         amount: 0,
+        reference: None,
+        token: None,
     });
 
-    client.batch_transfer(&sender, &token_contract.address(), &payments);
+    let result = client.batch_transfer(&sender, &token_contract.address(), &payments);
+    assert_eq!(result.successful, 0);
+    assert_eq!(result.failed, 1);
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        PaymentResult::Failure(user1, 0, BatchPaymentError::InvalidAmount as u32)
+    );
+}
+
+#[test]
+fn test_batch_transfer_skips_failed_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(&env, &contract_id);
+
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_client = token::Client::new(&env, &token_contract.address());
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_contract.address());
+
+    let sender = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    token_admin_client.mint(&sender, &500);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: user1.clone(),
+        amount: -5,
+        reference: None,
+        token: None,
+    });
+    payments.push_back(Payment {
+        recipient: user2.clone(),
+        amount: 400,
+        reference: None,
+        token: None,
+    });
+    payments.push_back(Payment {
+        recipient: user3.clone(),
+        amount: 200,
+        reference: None,
+        token: None,
+    });
+
+    let result = client.batch_transfer(&sender, &token_contract.address(), &payments);
+    assert_eq!(result.total_requests, 3);
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 2);
+    assert_eq!(result.total_amount, 400);
+    assert_eq!(
+        result.results.get(2).unwrap(),
+        PaymentResult::Failure(
+            user3.clone(),
+            200,
+            BatchPaymentError::InsufficientBalance as u32
+        )
+    );
+    assert_eq!(token_client.balance(&user2), 400);
+    assert_eq!(token_client.balance(&user3), 0);
+    assert_eq!(token_client.balance(&sender), 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_batch_transfer_empty_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(&env, &contract_id);
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+
+    let payments: Vec<Payment> = Vec::new(&env);
+    client.batch_transfer(
+        &Address::generate(&env),
+        &token_contract.address(),
+        &payments,
+    );
+}
+
+#[test]
+fn test_batch_transfer_too_large() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(&env, &contract_id);
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+
+    let mut payments: Vec<Payment> = Vec::new(&env);
+    for _ in 0..(MAX_BATCH_SIZE + 1) {
+        payments.push_back(Payment {
+            recipient: Address::generate(&env),
+            amount: 1,
+            reference: None,
+            token: None,
+        });
+    }
+
+    let result = client.try_batch_transfer(
+        &Address::generate(&env),
+        &token_contract.address(),
+        &payments,
+    );
+    assert_eq!(result, Err(Ok(BatchPaymentError::BatchTooLarge.into())));
+}
+
+/// Registers the contract and a token, and funds a sender with 1000 units.
+fn setup_funded_sender(
+    env: &Env,
+) -> (
+    BatchPaymentContractClient<'static>,
+    Address,
+    token::Client<'static>,
+    Address,
+) {
+    let contract_id = env.register(BatchPaymentContract, ());
+    let client = BatchPaymentContractClient::new(env, &contract_id);
+
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let token_address = token_contract.address();
+    let token_client = token::Client::new(env, &token_address);
+    let sender = Address::generate(env);
+    token::StellarAssetClient::new(env, &token_address).mint(&sender, &1000);
+
+    (client, token_address, token_client, sender)
+}
+
+#[test]
+fn test_escrowed_payment_claim_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let worker1 = Address::generate(&env);
+    let worker2 = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: worker1.clone(),
+        amount: 100,
+        reference: None,
+        token: None,
+    });
+    payments.push_back(Payment {
+        recipient: worker2.clone(),
+        amount: 200,
+        reference: None,
+        token: None,
+    });
+
+    let deadline = env.ledger().timestamp() + 1_000;
+    let ids = client.batch_escrow(&sender, &token, &payments, &deadline);
+    assert_eq!(ids.len(), 2);
+    assert_eq!(token_client.balance(&sender), 700);
+    assert_eq!(token_client.balance(&client.address), 300);
+
+    // Worker 1 comes online and claims
+    assert_eq!(client.claim(&worker1, &ids.get(0).unwrap()), 100);
+    assert_eq!(token_client.balance(&worker1), 100);
+
+    // Worker 2 never claims; the sender takes it back after the deadline
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    assert_eq!(client.cancel_payment(&sender, &ids.get(1).unwrap()), 200);
+    assert_eq!(token_client.balance(&sender), 900);
+
+    let cancelled = client.get_escrowed_payment(&ids.get(1).unwrap()).unwrap();
+    assert_eq!(cancelled.status, EscrowStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_payment_before_deadline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _token_client, sender) = setup_funded_sender(&env);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: Address::generate(&env),
+        amount: 100,
+        reference: None,
+        token: None,
+    });
+    let deadline = env.ledger().timestamp() + 1_000;
+    let ids = client.batch_escrow(&sender, &token, &payments, &deadline);
+
+    let result = client.try_cancel_payment(&sender, &ids.get(0).unwrap());
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::DeadlineNotReached.into()))
+    );
+}
+
+#[test]
+fn test_invoice_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let due = env.ledger().sequence() + 100;
+    let memo = String::from_str(&env, "INV-2024-001");
+    let first = client.create_invoice(&merchant, &payer, &token, &300, &due, &memo);
+    let second = client.create_invoice(&merchant, &payer, &token, &200, &due, &memo);
+    let cancelled = client.create_invoice(&merchant, &payer, &token, &50, &due, &memo);
+    client.cancel_invoice(&merchant, &cancelled);
+
+    // Unpaid invoices become overdue after the due ledger
+    env.ledger().with_mut(|li| li.sequence_number = due + 1);
+    assert_eq!(
+        client.get_invoice(&first).unwrap().status,
+        InvoiceStatus::Overdue
+    );
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(first);
+    ids.push_back(second);
+    ids.push_back(cancelled);
+    ids.push_back(99);
+    let result = client.batch_pay_invoices(&payer, &ids, &None);
+
+    assert_eq!(result.successful, 2);
+    assert_eq!(result.failed, 2);
+    assert_eq!(result.total_paid, 500);
+    assert_eq!(
+        result.results.get(2).unwrap(),
+        InvoiceResult::Failure(cancelled, BatchPaymentError::InvoiceNotOpen as u32)
+    );
+    assert_eq!(
+        result.results.get(3).unwrap(),
+        InvoiceResult::Failure(99, BatchPaymentError::InvoiceNotFound as u32)
+    );
+    assert_eq!(token_client.balance(&merchant), 500);
+    assert_eq!(
+        client.get_invoice(&first).unwrap().status,
+        InvoiceStatus::Paid
+    );
+    assert_eq!(
+        client.get_invoice(&cancelled).unwrap().status,
+        InvoiceStatus::Cancelled
+    );
+    assert_eq!(client.get_invoice_balance(&cancelled), 0);
+}
+
+#[test]
+fn test_refund_partial_payment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 400,
+        reference: Some(BytesN::from_array(&env, &[7; 32])),
+        token: None,
+    });
+    let result = client.batch_transfer(&sender, &token, &payments);
+
+    let record = client.get_payment(&1).unwrap();
+    assert_eq!(record.batch_id, result.batch_id);
+    assert_eq!(record.recipient, recipient);
+    assert_eq!(record.reference, Some(BytesN::from_array(&env, &[7; 32])));
+
+    assert_eq!(client.refund(&recipient, &1, &150), 150);
+    assert_eq!(client.refund(&recipient, &1, &250), 400);
+    assert_eq!(token_client.balance(&sender), 1000);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // Nothing left to refund
+    let result = client.try_refund(&recipient, &1, &1);
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::RefundExceedsPayment.into()))
+    );
+
+    // Only the recipient can refund
+    let result = client.try_refund(&sender, &1, &1);
+    assert_eq!(result, Err(Ok(BatchPaymentError::Unauthorized.into())));
+}
+
+#[test]
+fn test_split_payment_assigns_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let seller = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let affiliate = Address::generate(&env);
+    let mut splits = Vec::new(&env);
+    splits.push_back((seller.clone(), 3_333));
+    splits.push_back((platform.clone(), 3_333));
+    splits.push_back((affiliate.clone(), 3_334));
+
+    let result = client.split_payment(&sender, &token, &100, &splits);
+    assert_eq!(result.successful, 3);
+    assert_eq!(result.total_amount, 100);
+    assert_eq!(token_client.balance(&seller), 33);
+    assert_eq!(token_client.balance(&platform), 33);
+    assert_eq!(token_client.balance(&affiliate), 34);
+
+    // Shares must add up to 100%
+    splits.set(2, (affiliate, 3_000));
+    let result = client.try_split_payment(&sender, &token, &100, &splits);
+    assert_eq!(result, Err(Ok(BatchPaymentError::InvalidSplit.into())));
+}
+
+#[test]
+fn test_batch_transfer_multiple_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, usdc, usdc_client, sender) = setup_funded_sender(&env);
+
+    let eurc_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let eurc = eurc_contract.address();
+    let eurc_client = token::Client::new(&env, &eurc);
+    token::StellarAssetClient::new(&env, &eurc).mint(&sender, &500);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 300,
+        reference: None,
+        token: None,
+    });
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 400,
+        reference: None,
+        token: Some(eurc.clone()),
+    });
+    // Not a token contract
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 50,
+        reference: None,
+        token: Some(Address::generate(&env)),
+    });
+
+    let result = client.batch_transfer(&sender, &usdc, &payments);
+    assert_eq!(result.successful, 2);
+    assert_eq!(
+        result.results.get(2).unwrap(),
+        PaymentResult::Failure(
+            recipient.clone(),
+            50,
+            BatchPaymentError::InvalidToken as u32
+        )
+    );
+    assert_eq!(result.token_totals.get(usdc.clone()), Some(300));
+    assert_eq!(result.token_totals.get(eurc.clone()), Some(400));
+    assert_eq!(usdc_client.balance(&recipient), 300);
+    assert_eq!(eurc_client.balance(&recipient), 400);
+    assert_eq!(client.get_payment(&2).unwrap().token, eurc);
+}
+
+#[test]
+fn test_payment_requests_approved_in_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let expiry = env.ledger().timestamp() + 3_600;
+    let first = client.request_payment(&merchant, &payer, &token, &250, &expiry);
+    let second = client.request_payment(&merchant, &payer, &token, &900, &expiry);
+
+    assert_eq!(client.approve_and_pay(&payer, &first), 250);
+    let result = client.try_approve_and_pay(&payer, &first);
+    assert_eq!(result, Err(Ok(BatchPaymentError::RequestNotPending.into())));
+
+    let third = client.request_payment(&merchant, &payer, &token, &100, &expiry);
+    let mut ids = Vec::new(&env);
+    ids.push_back(second);
+    ids.push_back(third);
+    let result = client.batch_approve_and_pay(&payer, &ids);
+    assert_eq!(result.successful, 1);
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        RequestResult::Failure(second, BatchPaymentError::InsufficientBalance as u32)
+    );
+    assert_eq!(token_client.balance(&merchant), 350);
+
+    // Unapproved requests expire
+    env.ledger().with_mut(|li| li.timestamp = expiry + 1);
+    assert_eq!(
+        client.get_payment_request(&second).unwrap().status,
+        RequestStatus::Expired
+    );
+    let result = client.try_approve_and_pay(&payer, &second);
+    assert_eq!(result, Err(Ok(BatchPaymentError::RequestExpired.into())));
+}
+
+#[test]
+fn test_subscription_charging_pause_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(&payer, &client.address, &1000, &expiration);
+
+    let subscription_id = client.create_subscription(&payer, &merchant, &token, &100, &60, &3);
+    let unfunded =
+        client.create_subscription(&Address::generate(&env), &merchant, &token, &100, &60, &0);
+
+    // First cycle is due at creation; the unfunded one fails and stays due
+    assert_eq!(client.charge_due_subscriptions(&10), 1);
+    assert_eq!(client.charge_due_subscriptions(&10), 0);
+    assert_eq!(token_client.balance(&merchant), 100);
+
+    // Paused subscriptions are skipped
+    client.pause_subscription(&payer, &subscription_id);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert_eq!(client.charge_due_subscriptions(&10), 0);
+    client.resume_subscription(&payer, &subscription_id);
+
+    // `limit` bounds how many subscriptions a call looks at, and calls take
+    // turns through the queue
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert_eq!(client.charge_due_subscriptions(&1), 1);
+    assert_eq!(client.charge_due_subscriptions(&1), 0);
+    assert_eq!(client.charge_due_subscriptions(&1), 0);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert_eq!(client.charge_due_subscriptions(&2), 1);
+    assert_eq!(token_client.balance(&merchant), 300);
+    assert_eq!(
+        client.get_subscription(&subscription_id).unwrap().status,
+        SubscriptionStatus::Completed
+    );
+
+    client.cancel_subscription(&merchant, &unfunded);
+    let result = client.try_cancel_subscription(&merchant, &unfunded);
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::InvalidSubscriptionStatus.into()))
+    );
+}
+
+#[test]
+fn test_subscription_skips_cycles_missed_while_paused_or_failing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(&payer, &client.address, &1000, &expiration);
+
+    let subscription_id = client.create_subscription(&payer, &merchant, &token, &100, &60, &0);
+    assert_eq!(client.charge_due_subscriptions(&10), 1);
+
+    // Ten intervals pass while paused; resuming charges a single cycle
+    client.pause_subscription(&payer, &subscription_id);
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    client.resume_subscription(&payer, &subscription_id);
+    let now = env.ledger().timestamp();
+    assert_eq!(
+        client
+            .get_subscription(&subscription_id)
+            .unwrap()
+            .next_charge,
+        now
+    );
+    assert_eq!(client.charge_due_subscriptions(&10), 1);
+    assert_eq!(client.charge_due_subscriptions(&10), 0);
+    assert_eq!(token_client.balance(&merchant), 200);
+
+    // Cycles missed while the allowance was gone are not charged back either
+    token_client.approve(&payer, &client.address, &0, &expiration);
+    env.ledger().with_mut(|li| li.timestamp += 180);
+    assert_eq!(client.charge_due_subscriptions(&10), 0);
+    token_client.approve(&payer, &client.address, &1000, &expiration);
+    assert_eq!(client.charge_due_subscriptions(&10), 1);
+    assert_eq!(client.charge_due_subscriptions(&10), 0);
+    assert_eq!(token_client.balance(&merchant), 300);
+    assert_eq!(
+        client
+            .get_subscription(&subscription_id)
+            .unwrap()
+            .next_charge,
+        env.ledger().timestamp() + 60
+    );
+}
+
+#[test]
+fn test_batch_ids_unique_within_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _, sender) = setup_funded_sender(&env);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: Address::generate(&env),
+        amount: 10,
+        reference: None,
+        token: None,
+    });
+
+    let first = client.batch_transfer(&sender, &token, &payments);
+    let second = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(first.batch_id, 1);
+    assert_eq!(second.batch_id, 2);
+}
+
+#[test]
+fn test_platform_fee_routed_to_collector() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_fee_config(&admin, &collector, &250);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 400,
+        reference: None,
+        token: None,
+    });
+    let result = client.batch_transfer(&sender, &token, &payments);
+
+    assert_eq!(result.total_amount, 400);
+    assert_eq!(token_client.balance(&recipient), 390);
+    assert_eq!(token_client.balance(&collector), 10);
+    assert_eq!(client.get_accrued_fees(&token), 10);
+    assert_eq!(client.get_payment(&1).unwrap().amount, 390);
+
+    // A fee that would overflow fails the payment instead of the batch
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: i128::MAX,
+        reference: None,
+        token: None,
+    });
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        PaymentResult::Failure(
+            recipient.clone(),
+            i128::MAX,
+            BatchPaymentError::InvalidAmount as u32
+        )
+    );
+
+    let result = client.try_set_fee_config(&admin, &collector, &(MAX_FEE_BPS + 1));
+    assert_eq!(result, Err(Ok(BatchPaymentError::InvalidFee.into())));
+    let result = client.try_set_fee_config(&collector, &collector, &100);
+    assert_eq!(result, Err(Ok(BatchPaymentError::Unauthorized.into())));
+}
+
+#[test]
+fn test_scheduled_payment_execute_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let recipient = Address::generate(&env);
+    let payment = Payment {
+        recipient: recipient.clone(),
+        amount: 300,
+        reference: None,
+        token: None,
+    };
+    let execute_after = env.ledger().timestamp() + 86_400;
+    let rent = client.schedule_payment(&sender, &token, &payment, &execute_after);
+    let cancelled = client.schedule_payment(&sender, &token, &payment, &execute_after);
+    assert_eq!(token_client.balance(&sender), 400);
+
+    let result = client.try_execute_scheduled_payment(&rent);
+    assert_eq!(result, Err(Ok(BatchPaymentError::ScheduleNotDue.into())));
+
+    assert_eq!(client.cancel_scheduled_payment(&sender, &cancelled), 300);
+
+    env.ledger().with_mut(|li| li.timestamp = execute_after);
+    assert_eq!(client.execute_scheduled_payment(&rent), 300);
+    assert_eq!(token_client.balance(&recipient), 300);
+    assert_eq!(token_client.balance(&sender), 700);
+    assert_eq!(
+        client.get_scheduled_payment(&rent).unwrap().status,
+        ScheduleStatus::Executed
+    );
+
+    let result = client.try_execute_scheduled_payment(&cancelled);
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::ScheduleNotPending.into()))
+    );
+}
+
+#[test]
+fn test_discount_applied_to_invoices() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let other_merchant = Address::generate(&env);
+    let due = env.ledger().sequence() + 100;
+    let memo = String::from_str(&env, "order");
+    let first = client.create_invoice(&merchant, &payer, &token, &200, &due, &memo);
+    let second = client.create_invoice(&merchant, &payer, &token, &200, &due, &memo);
+    let third = client.create_invoice(&merchant, &payer, &token, &200, &due, &memo);
+    let foreign = client.create_invoice(&other_merchant, &payer, &token, &200, &due, &memo);
+
+    // 25% off, usable twice, only on this merchant's invoices
+    let code = Bytes::from_slice(&env, b"SPRING25");
+    let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+    let expiry = env.ledger().timestamp() + 3_600;
+    client.create_discount(&merchant, &code_hash, &2_500, &2, &expiry);
+
+    // The published hash is not accepted in place of the code
+    let mut ids = Vec::new(&env);
+    ids.push_back(first);
+    let hash_as_code = Bytes::from_array(&env, &code_hash.to_array());
+    let result = client.batch_pay_invoices(&payer, &ids, &Some(hash_as_code));
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        InvoiceResult::Failure(first, BatchPaymentError::InvalidDiscount as u32)
+    );
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(foreign);
+    ids.push_back(first);
+    ids.push_back(second);
+    ids.push_back(third);
+    let result = client.batch_pay_invoices(&payer, &ids, &Some(code.clone()));
+
+    assert_eq!(result.successful, 2);
+    assert_eq!(result.total_paid, 300);
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        InvoiceResult::Failure(foreign, BatchPaymentError::InvalidDiscount as u32)
+    );
+    assert_eq!(
+        result.results.get(1).unwrap(),
+        InvoiceResult::Paid(first, 150)
+    );
+    // Usage limit reached
+    assert_eq!(
+        result.results.get(3).unwrap(),
+        InvoiceResult::Failure(third, BatchPaymentError::InvalidDiscount as u32)
+    );
+    assert_eq!(token_client.balance(&merchant), 300);
+
+    // Discounted invoices are settled in full, with the waived part recorded
+    assert_eq!(client.get_invoice_balance(&first), 0);
+    let invoice = client.get_invoice(&first).unwrap();
+    assert_eq!(invoice.amount_paid, 150);
+    assert_eq!(invoice.amount_discounted, 50);
+    assert_eq!(client.get_invoice_balance(&third), 200);
+    assert_eq!(client.get_discount(&code_hash).unwrap().uses, 2);
+
+    let result = client.try_create_discount(&merchant, &code_hash, &1_000, &1, &expiry);
+    assert_eq!(result, Err(Ok(BatchPaymentError::DiscountExists.into())));
+
+    // The admin's codes do not apply to a merchant's invoices either
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let admin_code = Bytes::from_slice(&env, b"ADMIN10");
+    let admin_hash: BytesN<32> = env.crypto().sha256(&admin_code).into();
+    client.create_discount(&admin, &admin_hash, &1_000, &5, &expiry);
+    let result =
+        client.batch_pay_invoices(&payer, &Vec::from_array(&env, [third]), &Some(admin_code));
+    assert_eq!(
+        result.results.get(0).unwrap(),
+        InvoiceResult::Failure(third, BatchPaymentError::InvalidDiscount as u32)
+    );
+}
+
+#[test]
+fn test_partial_invoice_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let due = env.ledger().sequence() + 100;
+    let memo = String::from_str(&env, "installments");
+    let invoice_id = client.create_invoice(&merchant, &payer, &token, &600, &due, &memo);
+
+    assert_eq!(client.pay_invoice(&payer, &invoice_id, &200), 400);
+    assert_eq!(client.get_invoice_balance(&invoice_id), 400);
+    assert_eq!(
+        client.get_invoice(&invoice_id).unwrap().status,
+        InvoiceStatus::Open
+    );
+
+    let result = client.try_pay_invoice(&payer, &invoice_id, &401);
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::ExceedsInvoiceBalance.into()))
+    );
+
+    // Batch payment settles the remaining balance
+    let mut ids = Vec::new(&env);
+    ids.push_back(invoice_id);
+    let result = client.batch_pay_invoices(&payer, &ids, &None);
+    assert_eq!(result.total_paid, 400);
+
+    let invoice = client.get_invoice(&invoice_id).unwrap();
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 600);
+    assert_eq!(client.get_invoice_balance(&invoice_id), 0);
+    assert_eq!(token_client.balance(&merchant), 600);
+}
+
+#[test]
+fn test_spending_cap_limits_payer_per_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    client.set_my_spending_cap(&sender, &token, &300, &86_400);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    for amount in [200, 150, 100] {
+        payments.push_back(Payment {
+            recipient: recipient.clone(),
+            amount,
+            reference: None,
+            token: None,
+        });
+    }
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 2);
+    assert_eq!(
+        result.results.get(1).unwrap(),
+        PaymentResult::Failure(
+            recipient.clone(),
+            150,
+            BatchPaymentError::SpendingCapExceeded as u32
+        )
+    );
+    assert_eq!(client.get_spending_cap(&sender, &token).unwrap().spent, 300);
+
+    let payment = Payment {
+        recipient: recipient.clone(),
+        amount: 1,
+        reference: None,
+        token: None,
+    };
+    let execute_after = env.ledger().timestamp() + 60;
+    let result = client.try_schedule_payment(&sender, &token, &payment, &execute_after);
+    assert_eq!(
+        result,
+        Err(Ok(BatchPaymentError::SpendingCapExceeded.into()))
+    );
+
+    // The cap resets in the next period
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 2);
+    assert_eq!(token_client.balance(&recipient), 600);
+}
+
+#[test]
+fn test_replacing_spending_cap_keeps_current_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _token_client, sender) = setup_funded_sender(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&sender, &5_000);
+
+    client.set_my_spending_cap(&sender, &token, &300, &86_400);
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 200,
+        reference: None,
+        token: None,
+    });
+    client.batch_transfer(&sender, &token, &payments);
+
+    // Re-setting the cap does not reset what was spent in this period
+    client.set_my_spending_cap(&sender, &token, &300, &86_400);
+    assert_eq!(client.get_spending_cap(&sender, &token).unwrap().spent, 200);
+
+    // A lower cap applies immediately
+    client.set_my_spending_cap(&sender, &token, &250, &86_400);
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 0);
+
+    // A raise only takes over in the next period
+    client.set_my_spending_cap(&sender, &token, &1_000, &86_400);
+    let spending_cap = client.get_spending_cap(&sender, &token).unwrap();
+    assert_eq!(spending_cap.cap, 250);
+    assert_eq!(spending_cap.pending_cap, Some(1_000));
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    let mut large = Vec::new(&env);
+    large.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 900,
+        reference: None,
+        token: None,
+    });
+    let result = client.batch_transfer(&sender, &token, &large);
+    assert_eq!(result.successful, 1);
+
+    // Clearing also waits for the current period to end
+    client.clear_my_spending_cap(&sender, &token);
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 0);
+    assert!(client.get_spending_cap(&sender, &token).is_some());
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    let result = client.batch_transfer(&sender, &token, &large);
+    assert_eq!(result.successful, 1);
+    assert!(client.get_spending_cap(&sender, &token).is_none());
+}
+
+#[test]
+fn test_pay_merchants_by_id_after_rotation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let owner = Address::generate(&env);
+    let old_wallet = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+    let merchant_id = Symbol::new(&env, "coffee_shop");
+    let metadata = String::from_str(&env, "Coffee Shop Ltd");
+    client.register_merchant(&owner, &merchant_id, &old_wallet, &metadata);
+
+    let result = client.try_register_merchant(&sender, &merchant_id, &sender, &metadata);
+    assert_eq!(result, Err(Ok(BatchPaymentError::MerchantExists.into())));
+
+    client.update_settlement_address(&owner, &merchant_id, &new_wallet);
+
+    let mut payments = Vec::new(&env);
+    payments.push_back((merchant_id.clone(), 250));
+    client.batch_pay_merchants(&sender, &token, &payments);
+    assert_eq!(token_client.balance(&new_wallet), 250);
+    assert_eq!(token_client.balance(&old_wallet), 0);
+
+    let mut unknown = Vec::new(&env);
+    unknown.push_back((Symbol::new(&env, "unknown"), 10));
+    let result = client.try_batch_pay_merchants(&sender, &token, &unknown);
+    assert_eq!(result, Err(Ok(BatchPaymentError::MerchantNotFound.into())));
+}
+
+#[test]
+fn test_receipts_paginated_by_payer_and_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _, sender) = setup_funded_sender(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    for (recipient, amount) in [(&alice, 10), (&bob, 20), (&alice, 30)] {
+        payments.push_back(Payment {
+            recipient: recipient.clone(),
+            amount,
+            reference: None,
+            token: None,
+        });
+    }
+    let result = client.batch_transfer(&sender, &token, &payments);
+
+    assert_eq!(client.get_payer_receipt_count(&sender), 3);
+    let page = client.get_payer_receipts(&sender, &1, &5);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().recipient, bob);
+    assert_eq!(page.get(1).unwrap().amount, 30);
+    assert_eq!(page.get(1).unwrap().batch_id, result.batch_id);
+
+    assert_eq!(client.get_recipient_receipt_count(&alice), 2);
+    let page = client.get_recipient_receipts(&alice, &0, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().payer, sender);
+    assert_eq!(page.get(0).unwrap().amount, 10);
+    assert_eq!(client.get_recipient_receipts(&bob, &1, &5).len(), 0);
+}
+
+#[test]
+fn test_invoice_and_subscription_payments_issue_receipts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, payer) = setup_funded_sender(&env);
+
+    let merchant = Address::generate(&env);
+    let due = env.ledger().sequence() + 100;
+    let memo = String::from_str(&env, "INV-2024-001");
+    let first = client.create_invoice(&merchant, &payer, &token, &300, &due, &memo);
+    let second = client.create_invoice(&merchant, &payer, &token, &200, &due, &memo);
+    client.pay_invoice(&payer, &first, &100);
+    client.batch_pay_invoices(&payer, &Vec::from_array(&env, [first, second]), &None);
+
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(&payer, &client.address, &1000, &expiration);
+    client.create_subscription(&payer, &merchant, &token, &50, &60, &0);
+    assert_eq!(client.charge_due_subscriptions(&10), 1);
+
+    // Receipts are numbered in settlement order; the batched invoices share
+    // a batch ID
+    let receipts = client.get_payer_receipts(&payer, &0, &10);
+    assert_eq!(receipts.len(), 4);
+    for (index, amount) in [100, 200, 200, 50].into_iter().enumerate() {
+        assert_eq!(receipts.get(index as u32).unwrap().amount, amount);
+    }
+    assert_eq!(
+        receipts.get(1).unwrap().batch_id,
+        receipts.get(2).unwrap().batch_id
+    );
+    assert_eq!(receipts.get(3).unwrap().payment_id, 4);
+    assert_eq!(client.get_recipient_receipt_count(&merchant), 4);
+}
+
+/// Minimal stand-in for the batch-history contract.
+#[contract]
+struct MockHistory;
+
+#[contractimpl]
+impl MockHistory {
+    pub fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32 {
+        source_contract.require_auth();
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("records"), &records);
+        records.len()
+    }
+
+    pub fn recorded(env: Env) -> Vec<(Address, TransactionRecord)> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("records"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_settled_payments_are_recorded_in_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _token_client, sender) = setup_funded_sender(&env);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let history_id = env.register(MockHistory, ());
+    let history = MockHistoryClient::new(&env, &history_id);
+    client.set_history_contract(&admin, &history_id);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 300,
+        reference: None,
+        token: None,
+    });
+    client.batch_transfer(&sender, &token, &payments);
+
+    let records = history.recorded();
+    assert_eq!(records.len(), 2);
+    let (payer, sent) = records.get(0).unwrap();
+    assert_eq!(payer, sender);
+    assert_eq!(sent.amount, -300);
+    let (payee, received) = records.get(1).unwrap();
+    assert_eq!(payee, recipient);
+    assert_eq!(received.amount, 300);
+}
+
+/// Minimal stand-in for the batch-wallet-creation contract.
+#[contract]
+struct MockWallet;
+
+#[contractimpl]
+impl MockWallet {
+    pub fn set_status(env: Env, owner: Address, status: WalletStatus) {
+        env.storage().persistent().set(&owner, &status);
+    }
+
+    pub fn get_wallet_status(env: Env, owner: Address) -> Option<WalletStatus> {
+        env.storage().persistent().get(&owner)
+    }
+
+    pub fn set_daily_limit(env: Env, owner: Address, limit: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("limit"), owner), &limit);
+    }
+
+    pub fn record_spend(env: Env, _caller_contract: Address, owner: Address, amount: i128) {
+        let limit: i128 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("limit"), owner.clone()))
+            .unwrap_or(i128::MAX);
+        let spent = Self::get_spent(env.clone(), owner.clone()) + amount;
+        if spent > limit {
+            panic_with_error!(
+                &env,
+                soroban_sdk::Error::from_contract_error(WALLET_DAILY_LIMIT_EXCEEDED)
+            );
+        }
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("spent"), owner), &spent);
+    }
+
+    pub fn get_remaining_daily_limit(env: Env, owner: Address) -> i128 {
+        let limit: i128 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("limit"), owner.clone()))
+            .unwrap_or(i128::MAX);
+        limit - Self::get_spent(env, owner)
+    }
+
+    pub fn get_spent(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("spent"), owner))
+            .unwrap_or(0)
+    }
+
+    pub fn bump_activity(env: Env, _caller_contract: Address, owner: Address) {
+        let count = Self::get_activity(env.clone(), owner.clone()) + 1;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("active"), owner), &count);
+    }
+
+    pub fn get_activity(env: Env, owner: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("active"), owner))
+            .unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_frozen_recipient_wallets_are_not_paid() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let wallet_id = env.register(MockWallet, ());
+    let wallets = MockWalletClient::new(&env, &wallet_id);
+    client.set_wallet_contract(&admin, &wallet_id);
+    assert_eq!(client.get_wallet_contract(), Some(wallet_id));
+
+    let active = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    wallets.set_status(&active, &WalletStatus::Active);
+    wallets.set_status(&frozen, &WalletStatus::Frozen);
+
+    let mut payments = Vec::new(&env);
+    for recipient in [active.clone(), frozen.clone()] {
+        payments.push_back(Payment {
+            recipient,
+            amount: 100,
+            reference: None,
+            token: None,
+        });
+    }
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 1);
+    assert_eq!(
+        result.results.get(1).unwrap(),
+        PaymentResult::Failure(
+            frozen.clone(),
+            100,
+            BatchPaymentError::RecipientInactive as u32
+        )
+    );
+    assert_eq!(token_client.balance(&active), 100);
+    assert_eq!(token_client.balance(&frozen), 0);
+
+    client.clear_wallet_contract(&admin);
+    assert_eq!(client.get_wallet_contract(), None);
+}
+
+#[test]
+fn test_failing_wallet_contract_does_not_block_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    // A contract without `get_wallet_status` stands in for a broken one
+    client.set_wallet_contract(&admin, &token);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 100,
+        reference: None,
+        token: None,
+    });
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 100);
+}
+
+#[test]
+fn test_settled_payments_are_reported_to_the_wallet_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, token_client, sender) = setup_funded_sender(&env);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let wallet_id = env.register(MockWallet, ());
+    let wallets = MockWalletClient::new(&env, &wallet_id);
+    client.set_wallet_contract(&admin, &wallet_id);
+    client.set_wallet_limit_token(&admin, &token);
+    wallets.set_daily_limit(&sender, &250);
+
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    for amount in [100, 50] {
+        payments.push_back(Payment {
+            recipient: recipient.clone(),
+            amount,
+            reference: None,
+            token: None,
+        });
+    }
+    client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(wallets.get_spent(&sender), 150);
+    assert_eq!(wallets.get_activity(&sender), 1);
+
+    // A batch past the payer's daily limit reverts entirely
+    assert_eq!(
+        client.try_batch_transfer(&sender, &token, &payments).err(),
+        Some(Ok(BatchPaymentError::WalletLimitExceeded.into()))
+    );
+    assert_eq!(token_client.balance(&recipient), 150);
+    assert_eq!(wallets.get_spent(&sender), 150);
+    assert_eq!(wallets.get_activity(&sender), 1);
+}
+
+#[test]
+fn test_only_the_limit_token_counts_against_the_wallet_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _token_client, sender) = setup_funded_sender(&env);
+    let other_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &other_token).mint(&sender, &1000);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let wallet_id = env.register(MockWallet, ());
+    let wallets = MockWalletClient::new(&env, &wallet_id);
+    client.set_wallet_contract(&admin, &wallet_id);
+    wallets.set_daily_limit(&sender, &250);
+
+    // Without a limit token only activity is reported
+    let recipient = Address::generate(&env);
+    let mut payments = Vec::new(&env);
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 100,
+        reference: None,
+        token: None,
+    });
+    payments.push_back(Payment {
+        recipient: recipient.clone(),
+        amount: 400,
+        reference: None,
+        token: Some(other_token.clone()),
+    });
+    client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(wallets.get_spent(&sender), 0);
+
+    // Only the limit token's share of a mixed batch is counted
+    client.set_wallet_limit_token(&admin, &token);
+    assert_eq!(client.get_wallet_limit_token(), Some(token.clone()));
+    let result = client.batch_transfer(&sender, &token, &payments);
+    assert_eq!(result.successful, 2);
+    assert_eq!(wallets.get_spent(&sender), 100);
+    assert_eq!(wallets.get_activity(&sender), 4);
 }
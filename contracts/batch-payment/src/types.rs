@@ -1,8 +1,426 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Symbol, Vec};
+
+/// Maximum number of payments in a single batch.
+pub const MAX_BATCH_SIZE: u32 = 100;
+/// Basis points that split shares must add up to (100%).
+pub const TOTAL_SPLIT_BPS: u32 = 10_000;
+/// Highest platform fee that can be configured, in basis points.
+pub const MAX_FEE_BPS: u32 = 10_000;
+/// Code of the batch-wallet-creation contract's `DailyLimitExceeded` error.
+pub const WALLET_DAILY_LIMIT_EXCEEDED: u32 = 17;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Payment {
     pub recipient: Address,
     pub amount: i128,
+    /// Optional caller-supplied reference (e.g. an order hash) echoed in events
+    pub reference: Option<BytesN<32>>,
+    /// Token to pay in; defaults to the batch token when `None`
+    pub token: Option<Address>,
+}
+
+/// Outcome of a single payment.
+///
+/// The failure code is a `BatchPaymentError` value: `InvalidAmount`,
+/// `InsufficientBalance`, `InvalidToken`, `SpendingCapExceeded` or
+/// `RecipientInactive`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PaymentResult {
+    Success(Address, i128),
+    Failure(Address, i128, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchPaymentResult {
+    pub batch_id: u64,
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub total_amount: i128,
+    /// Amount paid per token
+    pub token_totals: Map<Address, i128>,
+    pub results: Vec<PaymentResult>,
+}
+
+/// Lifecycle of an escrowed payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    /// Held by the contract, waiting for the recipient
+    Pending,
+    /// Withdrawn by the recipient
+    Claimed,
+    /// Returned to the sender after the deadline
+    Cancelled,
+}
+
+/// A payment held by the contract until its recipient claims it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowedPayment {
+    pub payment_id: u64,
+    pub batch_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Ledger timestamp after which the sender may cancel an unclaimed payment
+    pub claim_deadline: u64,
+    pub status: EscrowStatus,
+}
+
+/// A settled `batch_transfer` payment, kept so it can be refunded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRecord {
+    pub payment_id: u64,
+    pub batch_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    /// Amount the recipient received, net of platform fees
+    pub amount: i128,
+    pub reference: Option<BytesN<32>>,
+    /// Amount pushed back to the sender so far
+    pub refunded: i128,
+}
+
+/// Mirror of the batch-wallet-creation contract's `WalletStatus`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum WalletStatus {
+    Active,
+    Frozen,
+    Closed,
+}
+
+/// Mirror of the batch-history contract's `TxType`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TxType {
+    Transfer,
+    Payment,
+    Conversion,
+    GoalContribution,
+    BudgetSpend,
+    Other,
+}
+
+/// Entry appended to a user's history in the batch-history contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionRecord {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub description: String,
+    pub category: Symbol,
+    pub counterparty: Option<Address>,
+    pub tx_type: TxType,
+    pub source_contract: Option<Address>,
+    pub batch_id: Option<u64>,
+}
+
+/// Proof of a settled payment, indexed by payer and recipient.
+///
+/// Batch transfers, claimed escrow, invoices, payment requests, subscription
+/// charges and executed scheduled payments all issue one. Payment IDs share
+/// the counter of `PaymentRecord`; single settlements get a batch ID of their
+/// own.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub payment_id: u64,
+    pub payer: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub batch_id: u64,
+    pub timestamp: u64,
+    pub reference: Option<BytesN<32>>,
+}
+
+/// Lifecycle of an invoice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceStatus {
+    /// Awaiting payment
+    Open,
+    /// Settled by the payer
+    Paid,
+    /// Still unpaid after its due ledger
+    Overdue,
+    /// Withdrawn by the merchant
+    Cancelled,
+}
+
+/// A merchant-issued request for payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub invoice_id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub token: Address,
+    /// Total amount due
+    pub amount: i128,
+    /// Amount paid so far through partial or full payments
+    pub amount_paid: i128,
+    /// Amount waived by a discount code when the invoice was settled
+    pub amount_discounted: i128,
+    /// Ledger sequence after which an unpaid invoice is overdue
+    pub due_ledger: u32,
+    pub memo: String,
+    pub status: InvoiceStatus,
+}
+
+/// Outcome of paying a single invoice.
+///
+/// The failure code is the `BatchPaymentError` value that paying the
+/// invoice on its own would have raised, with `Unauthorized` for a caller who
+/// is not the payer and `InvalidDiscount` for a discount code not usable on
+/// the invoice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceResult {
+    Paid(u64, i128),
+    Failure(u64, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchInvoiceResult {
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub total_paid: i128,
+    pub results: Vec<InvoiceResult>,
+}
+
+/// Lifecycle of a merchant-initiated pull-payment request.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RequestStatus {
+    /// Waiting for the payer's approval
+    Pending,
+    /// Approved and paid by the payer
+    Paid,
+    /// Not approved before its expiry
+    Expired,
+}
+
+/// A payment the merchant asks the payer to approve.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRequest {
+    pub request_id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Ledger timestamp after which the request can no longer be approved
+    pub expiry: u64,
+    pub status: RequestStatus,
+}
+
+/// Outcome of approving a single payment request.
+///
+/// The failure code is the `BatchPaymentError` value that approving the
+/// request on its own would have raised.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RequestResult {
+    Paid(u64, i128),
+    Failure(u64, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchRequestResult {
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub total_paid: i128,
+    pub results: Vec<RequestResult>,
+}
+
+/// Lifecycle of a recurring subscription.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionStatus {
+    /// Charged whenever a cycle falls due
+    Active,
+    /// Skipped by the keeper until resumed
+    Paused,
+    /// Stopped by the payer or merchant
+    Cancelled,
+    /// All `max_cycles` were charged
+    Completed,
+}
+
+/// A recurring charge pulled from the payer's allowance to this contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub subscription_id: u64,
+    pub payer: Address,
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Seconds between charges
+    pub interval: u64,
+    /// Number of charges before the subscription completes (0 = unlimited)
+    pub max_cycles: u32,
+    pub cycles_charged: u32,
+    /// Ledger timestamp at which the next charge is due
+    pub next_charge: u64,
+    pub status: SubscriptionStatus,
+}
+
+/// Lifecycle of a future-dated payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScheduleStatus {
+    /// Funded and waiting for its execution time
+    Pending,
+    /// Paid out to the recipient
+    Executed,
+    /// Returned to the sender
+    Cancelled,
+}
+
+/// A payment funded now and released to its recipient at a later time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledPayment {
+    pub schedule_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub reference: Option<BytesN<32>>,
+    /// Ledger timestamp from which the payment can be executed
+    pub execute_after: u64,
+    pub status: ScheduleStatus,
+}
+
+/// A merchant's promotion that reduces the amount charged for their invoices.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Discount {
+    /// SHA-256 hash of the discount code handed out to customers
+    pub code_hash: BytesN<32>,
+    pub owner: Address,
+    /// Reduction in basis points of the invoice amount
+    pub bps: u32,
+    pub max_uses: u32,
+    pub uses: u32,
+    /// Ledger timestamp after which the code can no longer be used
+    pub expiry: u64,
+}
+
+/// A payer's self-imposed limit on how much of one token can leave their
+/// account through this contract per period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingCap {
+    /// Maximum amount spendable per period
+    pub cap: i128,
+    /// Period length in seconds
+    pub period: u64,
+    /// Ledger timestamp at which the current period started
+    pub window_start: u64,
+    /// Amount spent in the current period
+    pub spent: i128,
+    /// Looser cap that takes over once the current period ends
+    pub pending_cap: Option<i128>,
+    /// Period length that takes over together with `pending_cap`
+    pub pending_period: u64,
+    /// Whether the cap is lifted once the current period ends
+    pub clear_pending: bool,
+}
+
+/// A merchant reachable by a stable ID whose payout address can be rotated.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Merchant {
+    pub merchant_id: Symbol,
+    /// Address allowed to manage the registration
+    pub owner: Address,
+    /// Address that receives payments made to this merchant
+    pub settlement_address: Address,
+    pub metadata: String,
+}
+
+/// Platform fee taken from each `batch_transfer` payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Address receiving collected fees
+    pub collector: Address,
+    /// Fee in basis points, deducted from the amount the recipient receives
+    pub fee_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Contract administrator
+    Admin,
+    /// Platform fee configuration
+    FeeConfig,
+    /// Total fees collected per token
+    AccruedFees(Address),
+    /// Last assigned batch ID
+    LastBatchId,
+    /// Last assigned escrowed payment ID
+    PaymentCounter,
+    /// Escrowed payment by ID
+    EscrowedPayment(u64),
+    /// Last assigned invoice ID
+    InvoiceCounter,
+    /// Invoice by ID
+    Invoice(u64),
+    /// Settled payment record by ID
+    PaymentRecord(u64),
+    /// Last assigned payment request ID
+    RequestCounter,
+    /// Payment request by ID
+    PaymentRequest(u64),
+    /// Last assigned subscription ID
+    SubscriptionCounter,
+    /// Subscription by ID
+    Subscription(u64),
+    /// IDs of active subscriptions the keeper still has to look at
+    OpenSubscriptions,
+    /// Position in `OpenSubscriptions` the next keeper call starts from
+    SubscriptionCursor,
+    /// Last assigned scheduled payment ID
+    ScheduleCounter,
+    /// Scheduled payment by ID
+    ScheduledPayment(u64),
+    /// Discount by code hash
+    Discount(BytesN<32>),
+    /// Spending cap by (payer, token)
+    SpendingCap(Address, Address),
+    /// Registered merchant by ID
+    Merchant(Symbol),
+    /// Receipt by payment ID
+    Receipt(u64),
+    /// Number of receipts where the address paid
+    PayerReceiptCount(Address),
+    /// Payment ID of a payer's n-th receipt
+    PayerReceipt(Address, u32),
+    /// Number of receipts where the address was paid
+    RecipientReceiptCount(Address),
+    /// Payment ID of a recipient's n-th receipt
+    RecipientReceipt(Address, u32),
+    /// Batch-history contract that records settled payments
+    HistoryContract,
+    /// Batch-wallet-creation contract consulted for recipient wallet status
+    WalletContract,
+    /// Token whose spends count against the payer's wallet daily limit
+    WalletLimitToken,
 }
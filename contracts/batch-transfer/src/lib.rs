@@ -4,165 +4,2351 @@
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, panic_with_error, symbol_short, token, Address, BytesN,
+    Env, Map, String, Vec,
+};
+
+pub use crate::types::{
+    ApprovalConfig, BatchOptions, BatchRecord, BatchStatus, BatchTransferResult,
+    BatchValidationReport, ClaimableBatch, DataKey, DedupeMode, Employee, FeeConfig, FeeMode,
+    NotificationBody, NotificationPayload, NotificationResult, PaymentReceived, PendingBatch,
+    PeriodLimit, PeriodUsage, Stream, StreamRequest, TokenStats, TransactionRecord, TransferCaps,
+    TransferError, TransferEvents, TransferRequest, TransferResult, TxType, WalletStatus,
+    BATCH_RESULT_TTL_EXTEND, BATCH_RESULT_TTL_THRESHOLD, DAILY_VOLUME_TTL_LEDGERS,
+    IDEMPOTENCY_TTL_LEDGERS, INSTANCE_TTL_EXTEND, INSTANCE_TTL_THRESHOLD, MAX_BATCH_SIZE,
+    MAX_FEE_BPS, SECONDS_PER_DAY, TOTAL_SPLIT_BPS, WALLET_DAILY_LIMIT_EXCEEDED,
+};
+use crate::validation::{
+    dedupe_transfers, validate_address, validate_amount, validate_daily_cap,
+    validate_max_transfer_amount, validate_recipient_allowed, validate_recipient_not_blocked,
+    validate_transfer_cap,
+};
+
+/// Error codes for the batch transfer contract.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum BatchTransferError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Caller is not authorized
+    Unauthorized = 2,
+    /// Invalid batch data
+    InvalidBatch = 3,
+    /// Batch is empty
+    EmptyBatch = 4,
+    /// Batch exceeds maximum size
+    BatchTooLarge = 5,
+    /// Invalid token contract
+    InvalidToken = 6,
+    /// Pending batch not found
+    BatchNotFound = 7,
+    /// Caller is not a configured approver
+    NotApprover = 8,
+    /// Approver has already approved this batch
+    AlreadyApproved = 9,
+    /// Invalid configuration values
+    InvalidConfig = 10,
+    /// Sender balance cannot cover the batch total
+    InsufficientBalance = 11,
+    /// Batch contains the same recipient more than once
+    DuplicateRecipient = 12,
+    /// Amount must be positive
+    InvalidAmount = 13,
+    /// Stream not found
+    StreamNotFound = 14,
+    /// Stream must not start in the past and must end after its start time
+    InvalidSchedule = 15,
+    /// Employee is not in the payroll registry
+    EmployeeNotFound = 16,
+    /// Batch would exceed the per-period volume cap
+    PeriodLimitExceeded = 17,
+    /// Claimable batch not found
+    ClaimNotFound = 18,
+    /// Claim deadline has passed
+    ClaimExpired = 19,
+    /// Claim deadline has not passed yet
+    ClaimNotExpired = 20,
+    /// Split shares must be positive and sum to 10,000 basis points
+    InvalidSplit = 21,
+    /// Contract is paused by the admin
+    ContractPaused = 22,
+    /// Batch failures have already been retried
+    AlreadyRetried = 23,
+    /// Amount exceeds the approval threshold on a path without approvals
+    ApprovalRequired = 24,
+    /// Batch would take the submitter past their wallet's daily spend limit
+    WalletLimitExceeded = 25,
+    /// Recipient is blocked, not allowlisted or has an inactive wallet
+    RecipientRejected = 26,
+}
+
+impl From<BatchTransferError> for soroban_sdk::Error {
+    fn from(e: BatchTransferError) -> Self {
+        soroban_sdk::Error::from_contract_error(e as u32)
+    }
+}
+
+/// Contribution entrypoint exposed by the savings-goals contract.
+#[contractclient(name = "SavingsGoalsClient")]
+pub trait SavingsGoalsInterface {
+    fn contribute(
+        env: Env,
+        contributor: Address,
+        goal_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> i128;
+}
+
+// Identifies a batch being executed and the metadata recorded with it
+struct BatchHeader {
+    batch_id: u64,
+    submitter: Address,
+    token: Address,
+    payload_hash: Option<BytesN<32>>,
+    from_pool: bool,
+    dedupe_mode: DedupeMode,
+    require_full_balance: bool,
+}
+
+/// Sibling-contract entrypoint exposed by the batch-notifications contract.
+#[contractclient(name = "NotificationsClient")]
+pub trait NotificationsInterface {
+    fn notify_from_contract(
+        env: Env,
+        source_contract: Address,
+        payloads: Vec<NotificationPayload>,
+    ) -> NotificationResult;
+}
+
+/// Recording entrypoint exposed by the batch-history contract.
+#[contractclient(name = "HistoryClient")]
+pub trait HistoryInterface {
+    fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32;
+}
+
+/// Status lookup and spend reporting exposed by the batch-wallet-creation contract.
+#[contractclient(name = "WalletClient")]
+pub trait WalletInterface {
+    fn get_wallet_status(env: Env, owner: Address) -> Option<WalletStatus>;
+    fn record_spend(env: Env, caller_contract: Address, owner: Address, amount: i128);
+    fn bump_activity(env: Env, caller_contract: Address, owner: Address);
+}
+
+#[contract]
+pub struct BatchTransferContract;
+
+#[contractimpl]
+impl BatchTransferContract {
+    /// Initializes the contract with an admin address.
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::TotalBatches, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalTransfersProcessed, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalVolumeTransferred, &0i128);
+        Self::extend_instance_ttl(&env);
+    }
+
+    /// Executes batch transfers of XLM to multiple recipients.
+    ///
+    /// If an approval configuration is set and the batch total exceeds its
+    /// threshold, the funds are locked in the contract and the batch is stored
+    /// as pending until enough approvers call `approve_batch`.
+    ///
+    /// `options.dedupe_mode` controls how repeated recipients are handled:
+    /// rejected up front, merged into a single transfer, or paid separately.
+    ///
+    /// If `caller` already used `options.idempotency_key` within
+    /// `IDEMPOTENCY_TTL_LEDGERS`, nothing is executed and the original batch
+    /// ID is returned with `BatchStatus::Duplicate`. Keys are scoped per
+    /// submitter, so another submitter's key never blocks this batch.
+    ///
+    /// With `options.require_full_balance`, the batch fails with
+    /// `InsufficientBalance` unless every valid transfer can be covered.
+    ///
+    /// `options.payload_hash` is stored in the batch record and emitted with
+    /// `batch_completed` so audits can tie execution to the off-chain file.
+    pub fn batch_transfer(
+        env: Env,
+        caller: Address,
+        token: Address,
+        transfers: Vec<TransferRequest>,
+        options: BatchOptions,
+    ) -> BatchTransferResult {
+        // Verify authorization
+        caller.require_auth();
+        Self::require_admin_or_operator(&env, &caller);
+        Self::require_not_paused(&env);
+
+        Self::submit_batch(&env, &caller, &token, &transfers, &options, false)
+    }
+
+    /// Returns the admin address.
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized")
+    }
+
+    /// Updates the admin address.
+    pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
+        current_admin.require_auth();
+        Self::require_admin(&env, &current_admin);
+        Self::require_not_paused(&env);
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    /// Returns the total number of batches processed.
+    pub fn get_total_batches(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalBatches)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total number of transfers processed (successful + failed).
+    pub fn get_total_transfers_processed(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalTransfersProcessed)
+            .unwrap_or(0)
+    }
+
+    /// Returns the total volume transferred (in stroops).
+    pub fn get_total_volume_transferred(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalVolumeTransferred)
+            .unwrap_or(0)
+    }
+
+    /// Executes batch transfers paid out of the contract-held treasury pool.
+    ///
+    /// Behaves like `batch_transfer`, but funds come from the pool balance
+    /// deposited with `deposit_pool` instead of the caller's wallet.
+    pub fn batch_transfer_from_pool(
+        env: Env,
+        caller: Address,
+        token: Address,
+        transfers: Vec<TransferRequest>,
+        options: BatchOptions,
+    ) -> BatchTransferResult {
+        // Verify authorization
+        caller.require_auth();
+        Self::require_admin_or_operator(&env, &caller);
+        Self::require_not_paused(&env);
+
+        Self::submit_batch(&env, &caller, &token, &transfers, &options, true)
+    }
+
+    /// Splits `total_amount` across recipients by basis-point share.
+    ///
+    /// Shares must be positive and sum to exactly 10,000 bps. Each share is
+    /// rounded down and any rounding remainder goes to the last recipient,
+    /// so exactly `total_amount` is distributed. The resulting transfers are
+    /// then submitted like `batch_transfer`.
+    pub fn batch_split_transfer(
+        env: Env,
+        caller: Address,
+        token: Address,
+        total_amount: i128,
+        splits: Vec<(Address, u32)>,
+    ) -> BatchTransferResult {
+        caller.require_auth();
+        Self::require_admin_or_operator(&env, &caller);
+        Self::require_not_paused(&env);
+
+        if validate_amount(total_amount).is_err() {
+            panic_with_error!(&env, BatchTransferError::InvalidAmount);
+        }
+        if splits.is_empty() {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+
+        let mut total_bps: u32 = 0;
+        for (_, bps) in splits.iter() {
+            if bps == 0 {
+                panic_with_error!(&env, BatchTransferError::InvalidSplit);
+            }
+            total_bps = total_bps.saturating_add(bps);
+        }
+        if total_bps != TOTAL_SPLIT_BPS {
+            panic_with_error!(&env, BatchTransferError::InvalidSplit);
+        }
+
+        let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+        let mut allocated: i128 = 0;
+        let last = splits.len() - 1;
+        for (index, (recipient, bps)) in splits.iter().enumerate() {
+            let amount = if index as u32 == last {
+                total_amount - allocated
+            } else {
+                total_amount
+                    .checked_mul(bps as i128)
+                    .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount))
+                    / TOTAL_SPLIT_BPS as i128
+            };
+            allocated += amount;
+            transfers.push_back(TransferRequest { recipient, amount });
+        }
+
+        Self::submit_batch(
+            &env,
+            &caller,
+            &token,
+            &transfers,
+            &BatchOptions::default(),
+            false,
+        )
+    }
+
+    /// Pays tokens straight into savings goals held by other contracts.
+    ///
+    /// Each entry is `(goal_contract, goal_id, amount)`; the admin's tokens are
+    /// moved through the goal contract's `contribute` entrypoint so the goal
+    /// balance and the payout settle together. `token` must be the goal
+    /// contract's contribution token. A failing contribution is recorded
+    /// against its goal contract without reverting the rest. Batches above
+    /// the multi-signer approval threshold are refused with `ApprovalRequired`.
+    pub fn batch_transfer_to_goals(
+        env: Env,
+        admin: Address,
+        token: Address,
+        contributions: Vec<(Address, u64, i128)>,
+    ) -> BatchTransferResult {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let request_count = contributions.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+
+        let mut total_amount: i128 = 0;
+        for (_, _, amount) in contributions.iter() {
+            if validate_amount(amount).is_err() {
+                panic_with_error!(&env, BatchTransferError::InvalidAmount);
+            }
+            total_amount = total_amount
+                .checked_add(amount)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+        }
+        Self::require_within_approval_threshold(&env, total_amount);
+        Self::check_period_limit(&env, total_amount);
+
+        let batch_id = Self::next_batch_id(&env);
+        TransferEvents::batch_started(&env, batch_id, request_count, &admin);
+
+        let mut results: Vec<TransferResult> = Vec::new(&env);
+        let mut successful_count: u32 = 0;
+        let mut failed_count: u32 = 0;
+        let mut total_transferred: i128 = 0;
+
+        for (goal_contract, goal_id, amount) in contributions.iter() {
+            let goals = SavingsGoalsClient::new(&env, &goal_contract);
+            match goals.try_contribute(&admin, &goal_id, &token, &amount) {
+                Ok(Ok(_)) => {
+                    results.push_back(TransferResult::Success(goal_contract.clone(), amount));
+                    successful_count += 1;
+                    total_transferred += amount;
+                    TransferEvents::transfer_success(&env, batch_id, &goal_contract, amount);
+                }
+                _ => {
+                    results.push_back(TransferResult::Failure(
+                        goal_contract.clone(),
+                        amount,
+                        TransferError::GoalContributionFailed,
+                    ));
+                    failed_count += 1;
+                    TransferEvents::transfer_failure(
+                        &env,
+                        batch_id,
+                        &goal_contract,
+                        amount,
+                        TransferError::GoalContributionFailed,
+                    );
+                }
+            }
+        }
+
+        Self::record_stats(&env, &token, request_count, total_transferred, 0);
+        Self::record_period_volume(&env, total_transferred);
+
+        TransferEvents::batch_completed(
+            &env,
+            batch_id,
+            successful_count,
+            failed_count,
+            total_transferred,
+            &None,
+        );
+
+        BatchTransferResult {
+            batch_id,
+            status: BatchStatus::Executed,
+            total_requests: request_count,
+            successful: successful_count,
+            failed: failed_count,
+            total_transferred,
+            total_fees: 0,
+            results,
+        }
+    }
+
+    /// Escrows a batch of transfers that each recipient claims with
+    /// `claim_transfer` before `claim_deadline` (a ledger timestamp).
+    ///
+    /// The batch total is moved into the contract up front, net of protocol
+    /// fees, which go to the fee collector right away; anything still
+    /// unclaimed after the deadline is returned by `reclaim_expired`.
+    /// Escrows above the multi-signer approval threshold are refused with
+    /// `ApprovalRequired`.
+    pub fn batch_transfer_claimable(
+        env: Env,
+        caller: Address,
+        token: Address,
+        transfers: Vec<TransferRequest>,
+        claim_deadline: u64,
+    ) -> u64 {
+        caller.require_auth();
+        Self::require_admin_or_operator(&env, &caller);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let request_count = transfers.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+        if claim_deadline <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchTransferError::InvalidSchedule);
+        }
+
+        // Every escrowed item must be payable; repeated recipients are merged
+        let caps: Option<TransferCaps> = env.storage().instance().get(&DataKey::TransferCaps);
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        let mut claims: Map<Address, i128> = Map::new(&env);
+        let mut total_amount: i128 = 0;
+        let mut total_cost: i128 = 0;
+        let mut total_fees: i128 = 0;
+        for request in transfers.iter() {
+            if Self::validate_request(&env, &request, &caps, &fee_config).is_err() {
+                panic_with_error!(&env, BatchTransferError::InvalidBatch);
+            }
+            let fee = fee_config
+                .as_ref()
+                .map(|config| config.fee_for(request.amount))
+                .unwrap_or(0);
+            let sender_fee = Self::sender_fee(&fee_config, request.amount);
+            let net_amount = request.amount - (fee - sender_fee);
+
+            let claim = claims
+                .get(request.recipient.clone())
+                .unwrap_or(0)
+                .checked_add(net_amount)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+            claims.set(request.recipient, claim);
+            total_amount = total_amount
+                .checked_add(request.amount)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+            total_cost = total_cost
+                .checked_add(request.amount + sender_fee)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+            total_fees = total_fees
+                .checked_add(fee)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+        }
+
+        Self::require_within_approval_threshold(&env, total_cost);
+        Self::check_period_limit(&env, total_cost);
+        let token_client = token::Client::new(&env, &token);
+        if token_client.balance(&caller) < total_cost {
+            panic_with_error!(&env, BatchTransferError::InsufficientBalance);
+        }
+
+        let batch_id = Self::next_batch_id(&env);
+        token_client.transfer(
+            &caller,
+            &env.current_contract_address(),
+            &(total_cost - total_fees),
+        );
+        if let Some(config) = &fee_config {
+            Self::collect_fees(&env, batch_id, &caller, &token, config, total_fees);
+        }
+        Self::record_period_volume(&env, total_cost);
+        Self::record_stats(&env, &token, request_count, total_amount, total_fees);
+        Self::store_batch_record(
+            &env,
+            &BatchRecord {
+                batch_id,
+                submitter: caller.clone(),
+                source: caller.clone(),
+                token: token.clone(),
+                total_requests: request_count,
+                successful: request_count,
+                failed: 0,
+                total_transferred: total_amount,
+                total_fees,
+                executed_at: env.ledger().timestamp(),
+                failed_items: Vec::new(&env),
+                payload_hash: None,
+                from_pool: false,
+                dedupe_mode: DedupeMode::Merge,
+                require_full_balance: true,
+                retry_batch_id: None,
+            },
+        );
+
+        let batch = ClaimableBatch {
+            batch_id,
+            sender: caller.clone(),
+            token,
+            claim_deadline,
+            claims,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::ClaimableBatch(batch_id), &batch);
+
+        TransferEvents::claim_created(&env, batch_id, &caller, total_amount, claim_deadline);
+
+        batch_id
+    }
+
+    /// Withdraws the recipient's escrowed amount from a claimable batch.
+    pub fn claim_transfer(env: Env, recipient: Address, batch_id: u64) -> i128 {
+        recipient.require_auth();
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let mut batch = Self::claimable_batch(&env, batch_id);
+        if env.ledger().timestamp() > batch.claim_deadline {
+            panic_with_error!(&env, BatchTransferError::ClaimExpired);
+        }
+        let amount = batch
+            .claims
+            .get(recipient.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::ClaimNotFound));
+
+        batch.claims.remove(recipient.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::ClaimableBatch(batch_id), &batch);
+
+        token::Client::new(&env, &batch.token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+
+        TransferEvents::transfer_claimed(&env, batch_id, &recipient, amount);
+
+        amount
+    }
+
+    /// Returns every unclaimed amount of an expired batch to its sender.
+    ///
+    /// Callable by anyone once the claim deadline has passed; emits an expiry
+    /// event per unclaimed recipient and returns the total swept back.
+    pub fn reclaim_expired(env: Env, batch_id: u64) -> i128 {
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let batch = Self::claimable_batch(&env, batch_id);
+        if env.ledger().timestamp() <= batch.claim_deadline {
+            panic_with_error!(&env, BatchTransferError::ClaimNotExpired);
+        }
+
+        let mut total_unclaimed: i128 = 0;
+        for (recipient, amount) in batch.claims.iter() {
+            total_unclaimed += amount;
+            TransferEvents::claim_expired(&env, batch_id, &recipient, amount);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ClaimableBatch(batch_id));
+
+        if total_unclaimed > 0 {
+            token::Client::new(&env, &batch.token).transfer(
+                &env.current_contract_address(),
+                &batch.sender,
+                &total_unclaimed,
+            );
+        }
+
+        total_unclaimed
+    }
+
+    /// Returns a claimable batch by ID, if it has not been fully reclaimed.
+    pub fn get_claimable_batch(env: Env, batch_id: u64) -> Option<ClaimableBatch> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ClaimableBatch(batch_id))
+    }
+
+    /// Deposits tokens from the admin into the treasury pool.
+    pub fn deposit_pool(env: Env, admin: Address, token: Address, amount: i128) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchTransferError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &token).transfer(&admin, &env.current_contract_address(), &amount);
+        let balance = Self::pool_balance(&env, &token) + amount;
+        Self::set_pool_balance(&env, &token, balance);
+
+        TransferEvents::pool_deposit(&env, &token, &admin, amount);
+    }
+
+    /// Withdraws tokens from the treasury pool to `to`.
+    pub fn withdraw_pool(env: Env, admin: Address, token: Address, amount: i128, to: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchTransferError::InvalidAmount);
+        }
+        let balance = Self::pool_balance(&env, &token);
+        if balance < amount {
+            panic_with_error!(&env, BatchTransferError::InsufficientBalance);
+        }
+
+        Self::check_period_limit(&env, amount);
+        Self::record_period_volume(&env, amount);
+
+        Self::set_pool_balance(&env, &token, balance - amount);
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+
+        TransferEvents::pool_withdraw(&env, &token, &to, amount);
+    }
+
+    /// Returns the treasury pool balance for a token.
+    pub fn get_pool_balance(env: Env, token: Address) -> i128 {
+        Self::pool_balance(&env, &token)
+    }
+
+    /// Adds an employee to the payroll registry, or updates their salary and token.
+    pub fn add_employee(env: Env, admin: Address, employee: Address, salary: i128, token: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if validate_amount(salary).is_err() {
+            panic_with_error!(&env, BatchTransferError::InvalidAmount);
+        }
+
+        let mut employees = Self::get_employees(env.clone());
+        if !employees.contains(&employee) {
+            if employees.len() >= MAX_BATCH_SIZE {
+                panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+            }
+            employees.push_back(employee.clone());
+            env.storage()
+                .persistent()
+                .set(&DataKey::Employees, &employees);
+        }
+
+        let record = Employee {
+            address: employee.clone(),
+            salary,
+            token,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Employee(employee.clone()), &record);
+
+        TransferEvents::employee_added(&env, &employee, salary);
+    }
+
+    /// Removes an employee from the payroll registry.
+    pub fn remove_employee(env: Env, admin: Address, employee: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        let mut employees = Self::get_employees(env.clone());
+        let index = employees
+            .first_index_of(&employee)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::EmployeeNotFound));
+        employees.remove(index);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Employees, &employees);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Employee(employee.clone()));
+
+        TransferEvents::employee_removed(&env, &employee);
+    }
+
+    /// Returns an employee's payroll record.
+    pub fn get_employee(env: Env, employee: Address) -> Option<Employee> {
+        env.storage().persistent().get(&DataKey::Employee(employee))
+    }
+
+    /// Returns all registered employee addresses.
+    pub fn get_employees(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Employees)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Pays every registered employee their stored salary.
+    ///
+    /// Employees are grouped by payout token and each group is submitted as
+    /// its own batch from the admin's wallet, subject to the same approval,
+    /// cap and fee rules as `batch_transfer`.
+    pub fn run_payroll(env: Env, admin: Address) -> Vec<BatchTransferResult> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        let employees = Self::get_employees(env.clone());
+        if employees.is_empty() {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+
+        // Group salaries by token, preserving registry order
+        let mut tokens: Vec<Address> = Vec::new(&env);
+        let mut payloads: Map<Address, Vec<TransferRequest>> = Map::new(&env);
+        for employee in employees.iter() {
+            let record: Employee = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Employee(employee))
+                .unwrap();
+            let mut transfers = payloads.get(record.token.clone()).unwrap_or(Vec::new(&env));
+            if transfers.is_empty() {
+                tokens.push_back(record.token.clone());
+            }
+            transfers.push_back(TransferRequest {
+                recipient: record.address,
+                amount: record.salary,
+            });
+            payloads.set(record.token, transfers);
+        }
+
+        let mut results: Vec<BatchTransferResult> = Vec::new(&env);
+        for token in tokens.iter() {
+            let transfers = payloads.get(token.clone()).unwrap();
+            results.push_back(Self::submit_batch(
+                &env,
+                &admin,
+                &token,
+                &transfers,
+                &BatchOptions::default(),
+                false,
+            ));
+        }
+
+        TransferEvents::payroll_completed(&env, employees.len(), results.len());
+
+        results
+    }
+
+    /// Creates a single linearly vesting stream funded by the admin.
+    pub fn create_stream(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        token: Address,
+        total_amount: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        let mut streams: Vec<StreamRequest> = Vec::new(&env);
+        streams.push_back(StreamRequest {
+            recipient,
+            total_amount,
+            start_time,
+            end_time,
+        });
+        Self::create_streams(env, admin, token, streams)
+            .get(0)
+            .unwrap()
+    }
+
+    /// Creates a batch of linearly vesting streams funded by the admin.
+    ///
+    /// The combined total is moved into the contract up front; each recipient
+    /// then withdraws the vested portion with `claim_stream`. Every stream is
+    /// screened like a regular transfer: a blocked, non-allowlisted or inactive
+    /// recipient rejects the batch with `RecipientRejected`, and an amount over
+    /// the configured caps with `InvalidAmount`. Streams may not start in the
+    /// past. Funding above the multi-signer approval threshold is refused with
+    /// `ApprovalRequired`.
+    pub fn create_streams(
+        env: Env,
+        admin: Address,
+        token: Address,
+        streams: Vec<StreamRequest>,
+    ) -> Vec<u64> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let request_count = streams.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+
+        let caps: Option<TransferCaps> = env.storage().instance().get(&DataKey::TransferCaps);
+        let now = env.ledger().timestamp();
+        let mut total_funding: i128 = 0;
+        for request in streams.iter() {
+            let transfer = TransferRequest {
+                recipient: request.recipient.clone(),
+                amount: request.total_amount,
+            };
+            if let Err(error) = Self::validate_request(&env, &transfer, &caps, &None) {
+                let batch_error = match error {
+                    TransferError::InvalidAddress
+                    | TransferError::RecipientBlocked
+                    | TransferError::RecipientNotAllowed
+                    | TransferError::RecipientWalletInactive => {
+                        BatchTransferError::RecipientRejected
+                    }
+                    _ => BatchTransferError::InvalidAmount,
+                };
+                panic_with_error!(&env, batch_error);
+            }
+            if request.start_time < now || request.end_time <= request.start_time {
+                panic_with_error!(&env, BatchTransferError::InvalidSchedule);
+            }
+            total_funding = total_funding
+                .checked_add(request.total_amount)
+                .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::InvalidAmount));
+        }
+
+        Self::require_within_approval_threshold(&env, total_funding);
+        Self::check_period_limit(&env, total_funding);
+        Self::record_period_volume(&env, total_funding);
+        token::Client::new(&env, &token).transfer(
+            &admin,
+            &env.current_contract_address(),
+            &total_funding,
+        );
+
+        let mut stream_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::StreamCounter)
+            .unwrap_or(0);
+        let mut stream_ids: Vec<u64> = Vec::new(&env);
+
+        for request in streams.iter() {
+            stream_id += 1;
+            let stream = Stream {
+                stream_id,
+                sender: admin.clone(),
+                recipient: request.recipient.clone(),
+                token: token.clone(),
+                total_amount: request.total_amount,
+                claimed_amount: 0,
+                start_time: request.start_time,
+                end_time: request.end_time,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::Stream(stream_id), &stream);
+            stream_ids.push_back(stream_id);
+
+            TransferEvents::stream_created(
+                &env,
+                stream_id,
+                &request.recipient,
+                request.total_amount,
+                request.start_time,
+                request.end_time,
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::StreamCounter, &stream_id);
+
+        stream_ids
+    }
+
+    /// Withdraws the vested, unclaimed portion of a stream to its recipient.
+    ///
+    /// Returns the amount transferred, which is zero if nothing new has vested.
+    pub fn claim_stream(env: Env, recipient: Address, stream_id: u64) -> i128 {
+        recipient.require_auth();
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let mut stream: Stream = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Stream(stream_id))
+            .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::StreamNotFound));
+        if stream.recipient != recipient {
+            panic_with_error!(&env, BatchTransferError::Unauthorized);
+        }
+
+        let claimable = stream.claimable_at(env.ledger().timestamp());
+        if claimable == 0 {
+            return 0;
+        }
+
+        stream.claimed_amount += claimable;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Stream(stream_id), &stream);
+
+        token::Client::new(&env, &stream.token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &claimable,
+        );
+
+        TransferEvents::stream_claimed(&env, stream_id, &recipient, claimable);
+
+        claimable
+    }
+
+    /// Returns a stream by ID.
+    pub fn get_stream(env: Env, stream_id: u64) -> Option<Stream> {
+        env.storage().persistent().get(&DataKey::Stream(stream_id))
+    }
+
+    /// Returns the amount currently claimable from a stream.
+    pub fn get_claimable(env: Env, stream_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get::<_, Stream>(&DataKey::Stream(stream_id))
+            .map(|stream| stream.claimable_at(env.ledger().timestamp()))
+            .unwrap_or(0)
+    }
+
+    /// Runs full validation of a batch without transferring anything.
+    ///
+    /// Items are checked in order against the caller's current balance, caps
+    /// and fees exactly as `batch_transfer` would, so the report's per-item
+    /// failure reasons match those of a real submission.
+    pub fn validate_batch(
+        env: Env,
+        caller: Address,
+        token: Address,
+        transfers: Vec<TransferRequest>,
+    ) -> BatchValidationReport {
+        let request_count = transfers.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+
+        let caps: Option<TransferCaps> = env.storage().instance().get(&DataKey::TransferCaps);
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+
+        let available_balance = token::Client::new(&env, &token).balance(&caller);
+        let mut remaining_balance = available_balance;
+        let mut total_needed: i128 = 0;
+        let mut valid_count: u32 = 0;
+        let mut items: Vec<TransferResult> = Vec::new(&env);
+        // Amounts already assigned to each recipient earlier in this batch
+        let mut batch_received: Map<Address, i128> = Map::new(&env);
+
+        for request in transfers.iter() {
+            let mut outcome = Self::validate_request(&env, &request, &caps, &fee_config);
+            let cost = request
+                .amount
+                .checked_add(Self::sender_fee(&fee_config, request.amount))
+                .unwrap_or(i128::MAX);
+
+            if outcome.is_ok() {
+                if let Some(caps) = &caps {
+                    let received_today: i128 = env
+                        .storage()
+                        .temporary()
+                        .get(&DataKey::RecipientDailyVolume(
+                            request.recipient.clone(),
+                            day,
+                        ))
+                        .unwrap_or(0)
+                        + batch_received.get(request.recipient.clone()).unwrap_or(0);
+                    if validate_daily_cap(received_today, request.amount, caps).is_err() {
+                        outcome = Err(TransferError::ExceedsDailyCap);
+                    }
+                }
+            }
+            // Only items that would be paid given enough balance count as needed
+            if outcome.is_ok() {
+                total_needed = total_needed.checked_add(cost).unwrap_or(i128::MAX);
+            }
+            if outcome.is_ok() && remaining_balance < cost {
+                outcome = Err(TransferError::InsufficientBalance);
+            }
+
+            match outcome {
+                Ok(()) => {
+                    remaining_balance -= cost;
+                    let received = batch_received.get(request.recipient.clone()).unwrap_or(0);
+                    batch_received.set(request.recipient.clone(), received + request.amount);
+                    valid_count += 1;
+                    items.push_back(TransferResult::Success(request.recipient, request.amount));
+                }
+                Err(error) => {
+                    items.push_back(TransferResult::Failure(
+                        request.recipient,
+                        request.amount,
+                        error,
+                    ));
+                }
+            }
+        }
+
+        let approval_config: Option<ApprovalConfig> =
+            env.storage().instance().get(&DataKey::ApprovalConfig);
+        let valid_total = Self::valid_total(&env, &transfers);
+        let requires_approval = approval_config
+            .map(|config| valid_total > config.threshold)
+            .unwrap_or(false);
+
+        BatchValidationReport {
+            total_requests: request_count,
+            valid: valid_count,
+            invalid: request_count - valid_count,
+            total_needed,
+            available_balance,
+            sufficient_balance: available_balance >= total_needed,
+            requires_approval,
+            exceeds_period_limit: Self::exceeds_period_limit(&env, valid_total),
+            items,
+        }
+    }
+
+    /// Configures multi-signer approval for large batches.
+    ///
+    /// Batches whose valid total exceeds `threshold` require
+    /// `required_approvals` distinct approvals from `approvers`.
+    pub fn set_approval_config(
+        env: Env,
+        admin: Address,
+        threshold: i128,
+        approvers: Vec<Address>,
+        required_approvals: u32,
+    ) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if threshold < 0 || required_approvals == 0 || required_approvals > approvers.len() {
+            panic_with_error!(&env, BatchTransferError::InvalidConfig);
+        }
+
+        let config = ApprovalConfig {
+            threshold,
+            approvers,
+            required_approvals,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::ApprovalConfig, &config);
+    }
+
+    /// Returns the multi-signer approval configuration, if set.
+    pub fn get_approval_config(env: Env) -> Option<ApprovalConfig> {
+        env.storage().instance().get(&DataKey::ApprovalConfig)
+    }
+
+    /// Records an approval for a pending batch.
+    ///
+    /// Once the required number of approvals is reached the batch is executed
+    /// from the funds locked at submission and removed from pending storage.
+    pub fn approve_batch(env: Env, approver: Address, batch_id: u64) -> BatchTransferResult {
+        approver.require_auth();
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let config: ApprovalConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::ApprovalConfig)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::NotApprover));
+        if !config.approvers.contains(&approver) {
+            panic_with_error!(&env, BatchTransferError::NotApprover);
+        }
+
+        let mut pending = Self::pending_batch(&env, batch_id);
+
+        if pending.approvals.contains(&approver) {
+            panic_with_error!(&env, BatchTransferError::AlreadyApproved);
+        }
+        pending.approvals.push_back(approver.clone());
+
+        TransferEvents::batch_approved(&env, batch_id, &approver, pending.approvals.len());
+
+        if pending.approvals.len() < config.required_approvals {
+            env.storage()
+                .persistent()
+                .set(&DataKey::PendingBatch(batch_id), &pending);
+            return Self::pending_result(&env, batch_id, pending.transfers.len());
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingBatch(batch_id));
+
+        let contract_address = env.current_contract_address();
+        let header = BatchHeader {
+            batch_id,
+            submitter: pending.submitter.clone(),
+            token: pending.token.clone(),
+            payload_hash: pending.payload_hash.clone(),
+            from_pool: pending.from_pool,
+            dedupe_mode: pending.dedupe_mode.clone(),
+            require_full_balance: pending.require_full_balance,
+        };
+        let result = Self::execute_batch(
+            &env,
+            &header,
+            &contract_address,
+            &pending.transfers,
+            pending.total_amount,
+        );
+
+        // Return anything that was locked but not paid out to its source
+        let leftover = pending.total_amount - Self::amount_spent(&env, &result);
+        Self::release_locked(&env, &mut pending, leftover);
+
+        result
+    }
+
+    /// Replaces the transfers of a batch still awaiting approval.
+    ///
+    /// Callable by the submitter or the admin. Repeated recipients are handled
+    /// with the batch's original dedupe mode. The locked amount is topped up
+    /// or partially refunded to match the new total, with the period volume
+    /// adjusted to match, and any approvals already collected are cleared
+    /// since they covered the old contents.
+    pub fn amend_scheduled(
+        env: Env,
+        caller: Address,
+        batch_id: u64,
+        new_transfers: Vec<TransferRequest>,
+    ) {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let mut pending = Self::pending_batch(&env, batch_id);
+        Self::require_submitter_or_admin(&env, &caller, &pending.submitter);
+
+        let request_count = new_transfers.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        }
+        let new_transfers = dedupe_transfers(&env, &new_transfers, &pending.dedupe_mode)
+            .unwrap_or_else(|_| panic_with_error!(&env, BatchTransferError::DuplicateRecipient));
+
+        let new_total = Self::valid_total(&env, &new_transfers);
+        if new_total > pending.total_amount {
+            // Lock the difference from the original funding source
+            let top_up = new_total - pending.total_amount;
+            Self::check_period_limit(&env, top_up);
+            if pending.from_pool {
+                let balance = Self::pool_balance(&env, &pending.token);
+                if balance < top_up {
+                    panic_with_error!(&env, BatchTransferError::InsufficientBalance);
+                }
+                Self::set_pool_balance(&env, &pending.token, balance - top_up);
+            } else {
+                let token_client = token::Client::new(&env, &pending.token);
+                if token_client.balance(&pending.submitter) < top_up {
+                    panic_with_error!(&env, BatchTransferError::InsufficientBalance);
+                }
+                token_client.transfer(&pending.submitter, &env.current_contract_address(), &top_up);
+            }
+            Self::record_pending_volume(&env, &mut pending, top_up);
+        } else {
+            let refund = pending.total_amount - new_total;
+            Self::release_locked(&env, &mut pending, refund);
+        }
+
+        pending.transfers = new_transfers;
+        pending.total_amount = new_total;
+        pending.approvals = Vec::new(&env);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingBatch(batch_id), &pending);
+
+        TransferEvents::batch_amended(&env, batch_id, &caller, new_total);
+    }
+
+    /// Cancels a batch still awaiting approval, refunds its locked funds and
+    /// gives back the period volume they used.
+    ///
+    /// Callable by the submitter or the admin. Returns the amount refunded.
+    pub fn cancel_scheduled(env: Env, caller: Address, batch_id: u64) -> i128 {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let mut pending = Self::pending_batch(&env, batch_id);
+        Self::require_submitter_or_admin(&env, &caller, &pending.submitter);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingBatch(batch_id));
+        let refund = pending.total_amount;
+        Self::release_locked(&env, &mut pending, refund);
+
+        TransferEvents::batch_cancelled(&env, batch_id, &caller, pending.total_amount);
+
+        pending.total_amount
+    }
+
+    /// Configures per-transfer and per-recipient daily amount caps.
+    ///
+    /// A value of zero disables the corresponding cap. Transfers violating a
+    /// cap fail individually without affecting the rest of the batch.
+    pub fn set_transfer_caps(
+        env: Env,
+        admin: Address,
+        max_per_transfer: i128,
+        max_per_recipient_daily: i128,
+    ) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if max_per_transfer < 0 || max_per_recipient_daily < 0 {
+            panic_with_error!(&env, BatchTransferError::InvalidConfig);
+        }
+
+        let caps = TransferCaps {
+            max_per_transfer,
+            max_per_recipient_daily,
+        };
+        env.storage().instance().set(&DataKey::TransferCaps, &caps);
+    }
+
+    /// Returns the configured transfer caps, if set.
+    pub fn get_transfer_caps(env: Env) -> Option<TransferCaps> {
+        env.storage().instance().get(&DataKey::TransferCaps)
+    }
+
+    /// Sets a hard ceiling on any single transfer amount.
+    ///
+    /// Transfers above it fail with `TransferError::ExceedsMaxAmount`,
+    /// catching typo'd amounts independently of the transfer caps. Zero
+    /// disables the check.
+    pub fn set_max_transfer_amount(env: Env, admin: Address, amount: i128) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if amount < 0 {
+            panic_with_error!(&env, BatchTransferError::InvalidConfig);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxTransferAmount, &amount);
+    }
+
+    /// Returns the maximum transfer amount, or zero if unset.
+    pub fn get_max_transfer_amount(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxTransferAmount)
+            .unwrap_or(0)
+    }
+
+    /// Returns the amount a recipient has received today.
+    pub fn get_recipient_daily_volume(env: Env, recipient: Address) -> i128 {
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+        env.storage()
+            .temporary()
+            .get(&DataKey::RecipientDailyVolume(recipient, day))
+            .unwrap_or(0)
+    }
+
+    /// Configures the protocol fee charged on each successful transfer.
+    ///
+    /// The fee is `flat_fee + amount * fee_bps / 10_000`, either deducted from
+    /// the amount the recipient receives or charged to the sender on top of it,
+    /// and is routed to `collector` once per batch.
+    pub fn set_fee_config(
+        env: Env,
+        admin: Address,
+        collector: Address,
+        flat_fee: i128,
+        fee_bps: u32,
+        mode: FeeMode,
+    ) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if flat_fee < 0 || fee_bps > MAX_FEE_BPS {
+            panic_with_error!(&env, BatchTransferError::InvalidConfig);
+        }
+
+        let config = FeeConfig {
+            collector,
+            flat_fee,
+            fee_bps,
+            mode,
+        };
+        env.storage().instance().set(&DataKey::FeeConfig, &config);
+    }
+
+    /// Disables the protocol fee.
+    pub fn clear_fee_config(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage().instance().remove(&DataKey::FeeConfig);
+    }
+
+    /// Returns the protocol fee configuration, if set.
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&DataKey::FeeConfig)
+    }
+
+    /// Returns the total fees routed to fee collectors.
+    pub fn get_total_fees_collected(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalFeesCollected)
+            .unwrap_or(0)
+    }
+
+    /// Sets the batch-notifications contract that receives a "you've been
+    /// paid" message for every successful transfer. The notifications contract
+    /// must allowlist this contract as a source for `notify_from_contract`.
+    ///
+    /// Delivery is best effort: a failing notifications call never reverts
+    /// the payouts themselves.
+    pub fn set_notification_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::NotificationContract, &contract);
+    }
+
+    /// Stops sending payout notifications.
+    pub fn clear_notification_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::NotificationContract);
+    }
+
+    /// Returns the configured batch-notifications contract, if any.
+    pub fn get_notification_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::NotificationContract)
+    }
+
+    /// Sets the batch-history contract that records every successful
+    /// transfer in both the sender's and the recipient's history. This
+    /// contract must be on its source allowlist.
+    ///
+    /// Recording is best effort: a failing history call never reverts the
+    /// payouts themselves.
+    pub fn set_history_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::HistoryContract, &contract);
+    }
+
+    /// Stops recording transfers in the batch-history contract.
+    pub fn clear_history_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage().instance().remove(&DataKey::HistoryContract);
+    }
+
+    /// Returns the configured batch-history contract, if any.
+    pub fn get_history_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::HistoryContract)
+    }
+
+    /// Sets the batch-wallet-creation contract consulted before each payout.
+    ///
+    /// Transfers to a recipient whose wallet is frozen or closed fail with
+    /// `TransferError::RecipientWalletInactive`. Recipients without a
+    /// registered wallet are paid as usual, and so is every recipient while
+    /// the wallet contract fails to answer the status lookup.
+    ///
+    /// Once this contract is trusted by the wallet contract, each settled
+    /// batch is also reported as a spend and activity of the submitter. A
+    /// batch that would take the submitter past their wallet's daily limit
+    /// panics with `WalletLimitExceeded`.
+    pub fn set_wallet_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WalletContract, &contract);
+    }
+
+    /// Stops consulting the batch-wallet-creation contract.
+    pub fn clear_wallet_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage().instance().remove(&DataKey::WalletContract);
+    }
+
+    /// Returns the configured batch-wallet-creation contract, if any.
+    pub fn get_wallet_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::WalletContract)
+    }
+
+    /// Caps the total volume that can leave the contract's control per
+    /// `period_ledgers` ledgers, across all batch and pool payouts.
+    pub fn set_period_limit(env: Env, admin: Address, max_volume: i128, period_ledgers: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        if max_volume <= 0 || period_ledgers == 0 {
+            panic_with_error!(&env, BatchTransferError::InvalidConfig);
+        }
+
+        let limit = PeriodLimit {
+            max_volume,
+            period_ledgers,
+        };
+        env.storage().instance().set(&DataKey::PeriodLimit, &limit);
+    }
+
+    /// Returns the per-period volume cap, if set.
+    pub fn get_period_limit(env: Env) -> Option<PeriodLimit> {
+        env.storage().instance().get(&DataKey::PeriodLimit)
+    }
+
+    /// Returns the volume used in the current period.
+    pub fn get_period_usage(env: Env) -> Option<PeriodUsage> {
+        let limit: Option<PeriodLimit> = env.storage().instance().get(&DataKey::PeriodLimit);
+        limit.map(|limit| Self::current_period_usage(&env, &limit))
+    }
+
+    /// Returns the stored record of an executed batch.
+    pub fn get_batch_result(env: Env, batch_id: u64) -> Option<BatchRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BatchResult(batch_id))
+    }
+
+    /// Re-attempts only the failed transfers of an executed batch.
+    ///
+    /// The failures are submitted as a new batch on behalf of the original
+    /// submitter, with the original dedupe, balance and payload-hash options,
+    /// from the same funding source (the treasury pool, or otherwise the
+    /// submitter's wallet), e.g. after the balance has been topped up. The
+    /// original record is linked to the new batch through `retry_batch_id`,
+    /// and each batch can be retried once. Retries paid from a wallet also
+    /// need the submitter's authorization.
+    pub fn retry_failed(env: Env, admin: Address, batch_id: u64) -> BatchTransferResult {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        let record_key = DataKey::BatchResult(batch_id);
+        let mut record: BatchRecord = env
+            .storage()
+            .persistent()
+            .get(&record_key)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchTransferError::BatchNotFound));
+        if record.retry_batch_id.is_some() {
+            panic_with_error!(&env, BatchTransferError::AlreadyRetried);
+        }
+        if !record.from_pool {
+            record.submitter.require_auth();
+        }
+
+        let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+        for item in record.failed_items.iter() {
+            if let TransferResult::Failure(recipient, amount, _) = item {
+                transfers.push_back(TransferRequest { recipient, amount });
+            }
+        }
+
+        let options = BatchOptions {
+            dedupe_mode: record.dedupe_mode.clone(),
+            idempotency_key: None,
+            require_full_balance: record.require_full_balance,
+            payload_hash: record.payload_hash.clone(),
+        };
+        let result = Self::submit_batch(
+            &env,
+            &record.submitter,
+            &record.token,
+            &transfers,
+            &options,
+            record.from_pool,
+        );
+
+        record.retry_batch_id = Some(result.batch_id);
+        env.storage().persistent().set(&record_key, &record);
+
+        TransferEvents::batch_retried(&env, batch_id, result.batch_id, transfers.len());
+
+        result
+    }
+
+    /// Returns a pending batch by ID.
+    pub fn get_pending_batch(env: Env, batch_id: u64) -> Option<PendingBatch> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingBatch(batch_id))
+    }
+
+    /// Authorizes an operator to submit batches.
+    ///
+    /// Operators can call `batch_transfer` and `batch_transfer_from_pool` but
+    /// cannot change the admin, limits or any other configuration.
+    pub fn add_operator(env: Env, admin: Address, operator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Operator(operator.clone()), &true);
+
+        TransferEvents::operator_added(&env, &operator);
+    }
+
+    /// Revokes an operator's authorization.
+    pub fn remove_operator(env: Env, admin: Address, operator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Operator(operator.clone()));
+
+        TransferEvents::operator_removed(&env, &operator);
+    }
+
+    /// Returns whether an address is an authorized operator.
+    pub fn is_operator(env: Env, address: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Operator(address))
+            .unwrap_or(false)
+    }
+
+    /// Adds a recipient to the allowlist.
+    pub fn add_recipient(env: Env, admin: Address, recipient: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::AllowedRecipient(recipient.clone()), &true);
+
+        TransferEvents::recipient_allowed(&env, &recipient);
+    }
+
+    /// Removes a recipient from the allowlist.
+    pub fn remove_recipient(env: Env, admin: Address, recipient: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedRecipient(recipient.clone()));
+
+        TransferEvents::recipient_disallowed(&env, &recipient);
+    }
+
+    /// Turns allowlist enforcement on or off.
+    ///
+    /// While enforced, transfers to recipients not on the allowlist fail with
+    /// `TransferError::RecipientNotAllowed`.
+    pub fn set_allowlist_enforced(env: Env, admin: Address, enforced: bool) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowlistEnforced, &enforced);
+    }
+
+    /// Returns whether allowlist enforcement is on.
+    pub fn is_allowlist_enforced(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowlistEnforced)
+            .unwrap_or(false)
+    }
+
+    /// Returns whether a recipient is on the allowlist.
+    pub fn is_recipient_allowed(env: Env, recipient: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::AllowedRecipient(recipient))
+    }
+
+    /// Sets the compliance officer who manages the recipient denylist.
+    pub fn set_compliance_officer(env: Env, admin: Address, officer: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ComplianceOfficer, &officer);
+    }
+
+    /// Returns the compliance officer, if set.
+    pub fn get_compliance_officer(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ComplianceOfficer)
+    }
+
+    /// Adds a recipient to the denylist. Only the compliance officer may call this.
+    pub fn block_recipient(env: Env, officer: Address, recipient: Address) {
+        officer.require_auth();
+        Self::require_compliance_officer(&env, &officer);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::BlockedRecipient(recipient.clone()), &true);
+
+        TransferEvents::recipient_blocked(&env, &officer, &recipient);
+    }
+
+    /// Removes a recipient from the denylist. Only the compliance officer may call this.
+    pub fn unblock_recipient(env: Env, officer: Address, recipient: Address) {
+        officer.require_auth();
+        Self::require_compliance_officer(&env, &officer);
+        Self::require_not_paused(&env);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::BlockedRecipient(recipient.clone()));
+
+        TransferEvents::recipient_unblocked(&env, &officer, &recipient);
+    }
+
+    /// Returns whether a recipient is on the denylist.
+    pub fn is_recipient_blocked(env: Env, recipient: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::BlockedRecipient(recipient))
+    }
+
+    /// Freezes the contract until `unpause` is called.
+    ///
+    /// Every mutating entrypoint fails with `ContractPaused` while paused;
+    /// only `unpause` and read-only calls keep working.
+    pub fn pause(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::require_not_paused(&env);
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+
+        TransferEvents::paused(&env, &admin);
+    }
+
+    /// Resumes activity after a `pause`.
+    pub fn unpause(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::Paused);
+
+        TransferEvents::unpaused(&env, &admin);
+    }
+
+    /// Returns whether the contract is currently paused.
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Paused)
+    }
+
+    /// Returns lifetime statistics for batches paid in `token`.
+    pub fn get_token_stats(env: Env, token: Address) -> TokenStats {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TokenStats(token))
+            .unwrap_or_default()
+    }
+
+    // Internal helper shared by the batch entrypoints: validates the batch
+    // shape, applies dedupe, then either holds it for approval or executes it
+    // from the caller's wallet or the treasury pool.
+    fn submit_batch(
+        env: &Env,
+        caller: &Address,
+        token: &Address,
+        transfers: &Vec<TransferRequest>,
+        options: &BatchOptions,
+        from_pool: bool,
+    ) -> BatchTransferResult {
+        Self::extend_instance_ttl(env);
+
+        // Replayed submissions return the original batch without paying again
+        if let Some(key) = &options.idempotency_key {
+            let original: Option<u64> = env
+                .storage()
+                .temporary()
+                .get(&DataKey::IdempotencyKey(caller.clone(), key.clone()));
+            if let Some(batch_id) = original {
+                TransferEvents::batch_replayed(env, batch_id, caller);
+                return BatchTransferResult {
+                    batch_id,
+                    status: BatchStatus::Duplicate,
+                    total_requests: transfers.len(),
+                    successful: 0,
+                    failed: 0,
+                    total_transferred: 0,
+                    total_fees: 0,
+                    results: Vec::new(env),
+                };
+            }
+        }
+
+        // Validate batch size
+        let request_count = transfers.len();
+        if request_count == 0 {
+            panic_with_error!(env, BatchTransferError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(env, BatchTransferError::BatchTooLarge);
+        }
+
+        // Handle repeated recipients before anything is locked or paid
+        let transfers = dedupe_transfers(env, transfers, &options.dedupe_mode)
+            .unwrap_or_else(|_| panic_with_error!(env, BatchTransferError::DuplicateRecipient));
+        let request_count = transfers.len();
+
+        let batch_id = Self::next_batch_id(env);
+        if let Some(key) = &options.idempotency_key {
+            let storage_key = DataKey::IdempotencyKey(caller.clone(), key.clone());
+            env.storage().temporary().set(&storage_key, &batch_id);
+            env.storage().temporary().extend_ttl(
+                &storage_key,
+                IDEMPOTENCY_TTL_LEDGERS,
+                IDEMPOTENCY_TTL_LEDGERS,
+            );
+        }
+        let token_client = token::Client::new(env, token);
+        let contract_address = env.current_contract_address();
+
+        // Enforce the per-period volume cap against everything this batch could spend
+        let total_amount = Self::valid_total(env, &transfers);
+        Self::check_period_limit(env, total_amount);
+
+        // Optionally refuse batches the source cannot cover in full
+        if options.require_full_balance {
+            let available_balance = if from_pool {
+                Self::pool_balance(env, token)
+            } else {
+                token_client.balance(caller)
+            };
+            if available_balance < total_amount {
+                panic_with_error!(env, BatchTransferError::InsufficientBalance);
+            }
+        }
+
+        // Large batches are held for multi-signer approval
+        let approval_config: Option<ApprovalConfig> =
+            env.storage().instance().get(&DataKey::ApprovalConfig);
+        if let Some(config) = approval_config {
+            if total_amount > config.threshold {
+                if from_pool {
+                    let balance = Self::pool_balance(env, token);
+                    if balance < total_amount {
+                        panic_with_error!(env, BatchTransferError::InsufficientBalance);
+                    }
+                    Self::set_pool_balance(env, token, balance - total_amount);
+                } else {
+                    if token_client.balance(caller) < total_amount {
+                        panic_with_error!(env, BatchTransferError::InsufficientBalance);
+                    }
+                    token_client.transfer(caller, &contract_address, &total_amount);
+                }
+
+                let mut pending = PendingBatch {
+                    batch_id,
+                    submitter: caller.clone(),
+                    token: token.clone(),
+                    transfers,
+                    total_amount,
+                    approvals: Vec::new(env),
+                    created_at: env.ledger().sequence() as u64,
+                    from_pool,
+                    payload_hash: options.payload_hash.clone(),
+                    dedupe_mode: options.dedupe_mode.clone(),
+                    require_full_balance: options.require_full_balance,
+                    period_start: 0,
+                    period_volume: 0,
+                };
+                Self::record_pending_volume(env, &mut pending, total_amount);
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::PendingBatch(batch_id), &pending);
+
+                TransferEvents::batch_pending(env, batch_id, caller, total_amount);
+
+                return Self::pending_result(env, batch_id, request_count);
+            }
+        }
+
+        let header = BatchHeader {
+            batch_id,
+            submitter: caller.clone(),
+            token: token.clone(),
+            payload_hash: options.payload_hash.clone(),
+            from_pool,
+            dedupe_mode: options.dedupe_mode.clone(),
+            require_full_balance: options.require_full_balance,
+        };
+        let result = if from_pool {
+            let available_balance = Self::pool_balance(env, token);
+            let result = Self::execute_batch(
+                env,
+                &header,
+                &contract_address,
+                &transfers,
+                available_balance,
+            );
+            let remaining = available_balance - Self::amount_spent(env, &result);
+            Self::set_pool_balance(env, token, remaining);
+            result
+        } else {
+            // Get initial balance
+            let available_balance = token_client.balance(caller);
+            Self::execute_batch(env, &header, caller, &transfers, available_balance)
+        };
+
+        Self::record_period_volume(env, Self::amount_spent(env, &result));
+
+        result
+    }
+
+    // Internal helper returning the period usage, rolled over if the current
+    // window has elapsed.
+    fn current_period_usage(env: &Env, limit: &PeriodLimit) -> PeriodUsage {
+        let now = env.ledger().sequence();
+        let usage: Option<PeriodUsage> = env.storage().instance().get(&DataKey::PeriodUsage);
+        match usage {
+            Some(usage) if now < usage.period_start.saturating_add(limit.period_ledgers) => usage,
+            _ => PeriodUsage {
+                period_start: now,
+                volume: 0,
+            },
+        }
+    }
 
-pub use crate::types::{
-    BatchTransferResult, DataKey, TransferEvents, TransferRequest, TransferResult, MAX_BATCH_SIZE,
-};
-use crate::validation::{validate_address, validate_amount};
+    // Internal helper returning whether `amount` would push the current
+    // period over the configured volume cap
+    fn exceeds_period_limit(env: &Env, amount: i128) -> bool {
+        let limit: Option<PeriodLimit> = env.storage().instance().get(&DataKey::PeriodLimit);
+        limit.is_some_and(|limit| {
+            let usage = Self::current_period_usage(env, &limit);
+            usage.volume.saturating_add(amount) > limit.max_volume
+        })
+    }
 
-/// Error codes for the batch transfer contract.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(u32)]
-pub enum BatchTransferError {
-    /// Contract not initialized
-    NotInitialized = 1,
-    /// Caller is not authorized
-    Unauthorized = 2,
-    /// Invalid batch data
-    InvalidBatch = 3,
-    /// Batch is empty
-    EmptyBatch = 4,
-    /// Batch exceeds maximum size
-    BatchTooLarge = 5,
-    /// Invalid token contract
-    InvalidToken = 6,
-}
+    // Internal helper that panics if `amount` would push the current period
+    // over the configured volume cap.
+    fn check_period_limit(env: &Env, amount: i128) {
+        if Self::exceeds_period_limit(env, amount) {
+            panic_with_error!(env, BatchTransferError::PeriodLimitExceeded);
+        }
+    }
 
-impl From<BatchTransferError> for soroban_sdk::Error {
-    fn from(e: BatchTransferError) -> Self {
-        soroban_sdk::Error::from_contract_error(e as u32)
+    // Internal helper to add `amount` to the current period's volume
+    fn record_period_volume(env: &Env, amount: i128) {
+        let limit: Option<PeriodLimit> = env.storage().instance().get(&DataKey::PeriodLimit);
+        if let Some(limit) = limit {
+            let mut usage = Self::current_period_usage(env, &limit);
+            usage.volume = usage.volume.saturating_add(amount);
+            env.storage().instance().set(&DataKey::PeriodUsage, &usage);
+        }
     }
-}
 
-#[contract]
-pub struct BatchTransferContract;
+    // Internal helper to add `amount` locked for a pending batch to the
+    // current period's volume, tracking how much the batch counted toward it
+    fn record_pending_volume(env: &Env, pending: &mut PendingBatch, amount: i128) {
+        let limit: Option<PeriodLimit> = env.storage().instance().get(&DataKey::PeriodLimit);
+        if let Some(limit) = limit {
+            let mut usage = Self::current_period_usage(env, &limit);
+            usage.volume = usage.volume.saturating_add(amount);
+            env.storage().instance().set(&DataKey::PeriodUsage, &usage);
 
-#[contractimpl]
-impl BatchTransferContract {
-    /// Initializes the contract with an admin address.
-    pub fn initialize(env: Env, admin: Address) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Contract already initialized");
+            if pending.period_start != usage.period_start {
+                pending.period_start = usage.period_start;
+                pending.period_volume = 0;
+            }
+            pending.period_volume = pending.period_volume.saturating_add(amount);
         }
+    }
 
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::TotalBatches, &0u64);
+    // Internal helper to give back up to `amount` of the volume a pending
+    // batch counted toward the current period. Volume counted toward a
+    // period that has since rolled over is not given back.
+    fn release_period_volume(env: &Env, pending: &mut PendingBatch, amount: i128) {
+        let limit: Option<PeriodLimit> = env.storage().instance().get(&DataKey::PeriodLimit);
+        if let Some(limit) = limit {
+            let mut usage = Self::current_period_usage(env, &limit);
+            if usage.period_start == pending.period_start {
+                let released = amount.min(pending.period_volume);
+                usage.volume = (usage.volume - released).max(0);
+                env.storage().instance().set(&DataKey::PeriodUsage, &usage);
+                pending.period_volume -= released;
+            }
+        }
+    }
+
+    // Internal helper returning what a batch cost its source, including any
+    // fees charged to the sender on top of the transferred amounts.
+    fn amount_spent(env: &Env, result: &BatchTransferResult) -> i128 {
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        match fee_config {
+            Some(config) if config.mode == FeeMode::ChargeSender => {
+                result.total_transferred + result.total_fees
+            }
+            _ => result.total_transferred,
+        }
+    }
+
+    // Internal helper to read the treasury pool balance for a token
+    fn pool_balance(env: &Env, token: &Address) -> i128 {
         env.storage()
-            .instance()
-            .set(&DataKey::TotalTransfersProcessed, &0u64);
+            .persistent()
+            .get(&DataKey::PoolBalance(token.clone()))
+            .unwrap_or(0)
+    }
+
+    // Internal helper to update the treasury pool balance for a token
+    fn set_pool_balance(env: &Env, token: &Address, balance: i128) {
         env.storage()
+            .persistent()
+            .set(&DataKey::PoolBalance(token.clone()), &balance);
+    }
+
+    // Internal helper to add an executed batch to the global and per-token
+    // lifetime statistics
+    fn record_stats(
+        env: &Env,
+        token: &Address,
+        request_count: u32,
+        total_transferred: i128,
+        total_fees: i128,
+    ) {
+        let total_processed: u64 = env
+            .storage()
             .instance()
-            .set(&DataKey::TotalVolumeTransferred, &0i128);
+            .get(&DataKey::TotalTransfersProcessed)
+            .unwrap_or(0);
+        let total_volume: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalVolumeTransferred)
+            .unwrap_or(0);
+
+        env.storage().instance().set(
+            &DataKey::TotalTransfersProcessed,
+            &(total_processed + request_count as u64),
+        );
+        env.storage().instance().set(
+            &DataKey::TotalVolumeTransferred,
+            &total_transferred
+                .checked_add(total_volume)
+                .unwrap_or(i128::MAX),
+        );
+
+        let stats_key = DataKey::TokenStats(token.clone());
+        let mut stats: TokenStats = env
+            .storage()
+            .persistent()
+            .get(&stats_key)
+            .unwrap_or_default();
+        stats.total_batches += 1;
+        stats.total_transfers += request_count as u64;
+        stats.total_volume = stats
+            .total_volume
+            .checked_add(total_transferred)
+            .unwrap_or(i128::MAX);
+        stats.total_fees = stats
+            .total_fees
+            .checked_add(total_fees)
+            .unwrap_or(i128::MAX);
+        env.storage().persistent().set(&stats_key, &stats);
+        env.storage().persistent().extend_ttl(
+            &stats_key,
+            BATCH_RESULT_TTL_THRESHOLD,
+            BATCH_RESULT_TTL_EXTEND,
+        );
     }
 
-    /// Executes batch transfers of XLM to multiple recipients.
-    pub fn batch_transfer(
-        env: Env,
-        caller: Address,
-        token: Address,
-        transfers: Vec<TransferRequest>,
-    ) -> BatchTransferResult {
-        // Verify authorization
-        caller.require_auth();
-        Self::require_admin(&env, &caller);
+    // Internal helper to load a pending batch or panic
+    fn pending_batch(env: &Env, batch_id: u64) -> PendingBatch {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingBatch(batch_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchTransferError::BatchNotFound))
+    }
 
-        // Validate batch size
-        let request_count = transfers.len();
-        if request_count == 0 {
-            panic_with_error!(&env, BatchTransferError::EmptyBatch);
+    // Internal helper returning `amount` of a pending batch's locked funds to
+    // the pool or submitter it came from
+    fn release_locked(env: &Env, pending: &mut PendingBatch, amount: i128) {
+        if amount <= 0 {
+            return;
         }
-        if request_count > MAX_BATCH_SIZE {
-            panic_with_error!(&env, BatchTransferError::BatchTooLarge);
+        Self::release_period_volume(env, pending, amount);
+        if pending.from_pool {
+            let balance = Self::pool_balance(env, &pending.token) + amount;
+            Self::set_pool_balance(env, &pending.token, balance);
+        } else {
+            token::Client::new(env, &pending.token).transfer(
+                &env.current_contract_address(),
+                &pending.submitter,
+                &amount,
+            );
         }
+    }
+
+    // Internal helper to load a claimable batch or panic
+    fn claimable_batch(env: &Env, batch_id: u64) -> ClaimableBatch {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ClaimableBatch(batch_id))
+            .unwrap_or_else(|| panic_with_error!(env, BatchTransferError::ClaimNotFound))
+    }
 
-        // Get batch ID and increment
+    // Internal helper to allocate the next batch ID
+    fn next_batch_id(env: &Env) -> u64 {
         let batch_id: u64 = env
             .storage()
             .instance()
             .get(&DataKey::TotalBatches)
             .unwrap_or(0)
             + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalBatches, &batch_id);
+        batch_id
+    }
+
+    // Internal helper to sum the amounts of all valid requests
+    // (including any fees charged to the sender)
+    fn valid_total(env: &Env, transfers: &Vec<TransferRequest>) -> i128 {
+        let caps: Option<TransferCaps> = env.storage().instance().get(&DataKey::TransferCaps);
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        let mut total: i128 = 0;
+        for request in transfers.iter() {
+            if Self::validate_request(env, &request, &caps, &fee_config).is_ok() {
+                let cost = request
+                    .amount
+                    .checked_add(Self::sender_fee(&fee_config, request.amount))
+                    .unwrap_or(i128::MAX);
+                total = total.checked_add(cost).unwrap_or(i128::MAX);
+            }
+        }
+        total
+    }
+
+    // Internal helper returning the fee paid by the sender on top of `amount`
+    fn sender_fee(fee_config: &Option<FeeConfig>, amount: i128) -> i128 {
+        match fee_config {
+            Some(config) if config.mode == FeeMode::ChargeSender => config.fee_for(amount),
+            _ => 0,
+        }
+    }
+
+    // Internal helper running the static per-item checks, returning the
+    // failure reason recorded in `TransferResult::Failure` on error.
+    fn validate_request(
+        env: &Env,
+        request: &TransferRequest,
+        caps: &Option<TransferCaps>,
+        fee_config: &Option<FeeConfig>,
+    ) -> Result<(), TransferError> {
+        // Validate recipient address
+        if validate_address(env, &request.recipient).is_err() {
+            return Err(TransferError::InvalidAddress);
+        }
+        // Validate amount
+        if validate_amount(request.amount).is_err() {
+            return Err(TransferError::InvalidAmount);
+        }
+        // Catch typo'd amounts before any cap or fee logic
+        if validate_max_transfer_amount(env, request.amount).is_err() {
+            return Err(TransferError::ExceedsMaxAmount);
+        }
+        // Screen against the compliance denylist
+        if validate_recipient_not_blocked(env, &request.recipient).is_err() {
+            return Err(TransferError::RecipientBlocked);
+        }
+        // Validate against the recipient allowlist
+        if validate_recipient_allowed(env, &request.recipient).is_err() {
+            return Err(TransferError::RecipientNotAllowed);
+        }
+        // Frozen and closed wallets cannot receive payouts
+        if Self::recipient_wallet_inactive(env, &request.recipient) {
+            return Err(TransferError::RecipientWalletInactive);
+        }
+        // Validate against the per-transfer cap
+        if let Some(caps) = caps {
+            if validate_transfer_cap(request.amount, caps).is_err() {
+                return Err(TransferError::ExceedsTransferCap);
+            }
+        }
+        // A fee charged on top must not overflow the amount
+        if request
+            .amount
+            .checked_add(Self::sender_fee(fee_config, request.amount))
+            .is_none()
+        {
+            return Err(TransferError::InvalidAmount);
+        }
+        // A deducted fee must leave something for the recipient
+        if let Some(config) = fee_config {
+            if config.mode == FeeMode::DeductFromTransfer
+                && config.fee_for(request.amount) >= request.amount
+            {
+                return Err(TransferError::FeeExceedsAmount);
+            }
+        }
+        Ok(())
+    }
+
+    // Internal helper asking the wallet contract, when configured, whether the
+    // recipient's wallet is frozen or closed. A failed lookup counts as active
+    // so a broken wallet contract cannot revert the whole payout.
+    fn recipient_wallet_inactive(env: &Env, recipient: &Address) -> bool {
+        let contract: Option<Address> = env.storage().instance().get(&DataKey::WalletContract);
+        match contract {
+            Some(contract) => matches!(
+                WalletClient::new(env, &contract).try_get_wallet_status(recipient),
+                Ok(Ok(Some(WalletStatus::Frozen))) | Ok(Ok(Some(WalletStatus::Closed)))
+            ),
+            None => false,
+        }
+    }
+
+    // Internal helper reporting a settled spend and activity to the wallet
+    // contract, when configured. Only a spend past the owner's daily limit
+    // reverts the batch; owners without a wallet are skipped, and so is every
+    // owner while the wallet contract fails.
+    fn report_wallet_spend(env: &Env, owner: &Address, amount: i128) {
+        let contract: Option<Address> = env.storage().instance().get(&DataKey::WalletContract);
+        let Some(contract) = contract else {
+            return;
+        };
+        if amount <= 0 {
+            return;
+        }
+        let client = WalletClient::new(env, &contract);
+        let caller = env.current_contract_address();
+        if let Err(Ok(error)) = client.try_record_spend(&caller, owner, &amount) {
+            if error == soroban_sdk::Error::from_contract_error(WALLET_DAILY_LIMIT_EXCEEDED) {
+                panic_with_error!(env, BatchTransferError::WalletLimitExceeded);
+            }
+        }
+        let _ = client.try_bump_activity(&caller, owner);
+    }
+
+    // Internal helper to build the result returned for a batch awaiting approval
+    fn pending_result(env: &Env, batch_id: u64, request_count: u32) -> BatchTransferResult {
+        BatchTransferResult {
+            batch_id,
+            status: BatchStatus::PendingApproval,
+            total_requests: request_count,
+            successful: 0,
+            failed: 0,
+            total_transferred: 0,
+            total_fees: 0,
+            results: Vec::new(env),
+        }
+    }
+
+    // Internal helper that validates and executes each transfer from `source`,
+    // recording per-item results and updating the lifetime statistics.
+    fn execute_batch(
+        env: &Env,
+        header: &BatchHeader,
+        source: &Address,
+        transfers: &Vec<TransferRequest>,
+        mut available_balance: i128,
+    ) -> BatchTransferResult {
+        let batch_id = header.batch_id;
+        let submitter = &header.submitter;
+        let token = &header.token;
+        let request_count = transfers.len();
+        let starting_balance = available_balance;
 
         // Emit batch started event
-        TransferEvents::batch_started(&env, batch_id, request_count);
+        TransferEvents::batch_started(env, batch_id, request_count, submitter);
 
         // Initialize result vectors
-        let mut results: Vec<TransferResult> = Vec::new(&env);
+        let mut results: Vec<TransferResult> = Vec::new(env);
         let mut successful_count: u32 = 0;
         let mut failed_count: u32 = 0;
         let mut total_transferred: i128 = 0;
 
         // Create token client
-        let token_client = token::Client::new(&env, &token);
-
-        // Get initial balance
-        let mut available_balance = token_client.balance(&caller);
-
-        // Calculate total needed for all valid transfers and validate upfront
-        let mut total_needed: i128 = 0;
-        let mut validated_requests: Vec<(TransferRequest, bool, u32)> = Vec::new(&env);
+        let token_client = token::Client::new(env, token);
 
-        // First pass: Validate all requests and calculate total needed
-        for request in transfers.iter() {
-            let mut is_valid = true;
-            let mut error_code = 0u32;
+        // Payout messages are only built when a notifications contract is set
+        let notification_contract: Option<Address> =
+            env.storage().instance().get(&DataKey::NotificationContract);
+        let mut notifications: Vec<NotificationPayload> = Vec::new(env);
+        let history_contract: Option<Address> =
+            env.storage().instance().get(&DataKey::HistoryContract);
+        let mut history: Vec<(Address, TransactionRecord)> = Vec::new(env);
 
-            // Validate recipient address
-            if validate_address(&env, &request.recipient).is_err() {
-                is_valid = false;
-                error_code = 0; // Invalid address
-            }
-            // Validate amount
-            else if validate_amount(request.amount).is_err() {
-                is_valid = false;
-                error_code = 1; // Invalid amount
-            }
+        // Load transfer caps and the current day for daily recipient limits
+        let caps: Option<TransferCaps> = env.storage().instance().get(&DataKey::TransferCaps);
+        let track_daily = caps
+            .as_ref()
+            .map(|caps| caps.max_per_recipient_daily > 0)
+            .unwrap_or(false);
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
 
-            if is_valid {
-                total_needed = total_needed
-                    .checked_add(request.amount)
-                    .unwrap_or(i128::MAX);
-            }
+        // Load the protocol fee configuration
+        let fee_config: Option<FeeConfig> = env.storage().instance().get(&DataKey::FeeConfig);
+        let mut total_fees: i128 = 0;
 
-            validated_requests.push_back((request.clone(), is_valid, error_code));
+        // First pass: Validate all requests before any state changes
+        let mut validated_requests: Vec<(TransferRequest, Option<TransferError>)> = Vec::new(env);
+        for request in transfers.iter() {
+            let error = Self::validate_request(env, &request, &caps, &fee_config).err();
+            validated_requests.push_back((request.clone(), error));
         }
 
         // Second pass: Process each request
-        for (request, is_valid, error_code) in validated_requests.iter() {
-            if !is_valid {
+        for (request, error) in validated_requests.iter() {
+            if let Some(error) = error {
                 // Validation failed - record and continue
                 results.push_back(TransferResult::Failure(
                     request.recipient.clone(),
                     request.amount,
-                    error_code.clone(),
+                    error,
                 ));
                 failed_count += 1;
                 TransferEvents::transfer_failure(
-                    &env,
+                    env,
                     batch_id,
                     &request.recipient,
                     request.amount,
-                    error_code.clone(),
+                    error,
                 );
+                if error == TransferError::RecipientBlocked {
+                    TransferEvents::recipient_screened(env, batch_id, &request.recipient);
+                }
                 continue;
             }
 
-            // Check balance for this transfer
-            if available_balance < request.amount {
+            // Check the recipient's daily cap
+            let daily_key = DataKey::RecipientDailyVolume(request.recipient.clone(), day);
+            let daily_volume: i128 = if track_daily {
+                env.storage().temporary().get(&daily_key).unwrap_or(0)
+            } else {
+                0
+            };
+            if let Some(caps) = &caps {
+                if validate_daily_cap(daily_volume, request.amount, caps).is_err() {
+                    results.push_back(TransferResult::Failure(
+                        request.recipient.clone(),
+                        request.amount,
+                        TransferError::ExceedsDailyCap,
+                    ));
+                    failed_count += 1;
+                    TransferEvents::transfer_failure(
+                        env,
+                        batch_id,
+                        &request.recipient,
+                        request.amount,
+                        TransferError::ExceedsDailyCap,
+                    );
+                    continue;
+                }
+            }
+
+            // Check balance for this transfer (plus any fee charged to the sender)
+            let fee = fee_config
+                .as_ref()
+                .map(|config| config.fee_for(request.amount))
+                .unwrap_or(0);
+            let sender_fee = Self::sender_fee(&fee_config, request.amount);
+            let cost = request.amount.checked_add(sender_fee).unwrap_or(i128::MAX);
+            if available_balance < cost {
                 // Insufficient balance
                 results.push_back(TransferResult::Failure(
                     request.recipient.clone(),
                     request.amount,
-                    2, // Insufficient balance
+                    TransferError::InsufficientBalance,
                 ));
                 failed_count += 1;
                 TransferEvents::transfer_failure(
-                    &env,
+                    env,
                     batch_id,
                     &request.recipient,
                     request.amount,
-                    2,
+                    TransferError::InsufficientBalance,
                 );
                 continue;
             }
@@ -172,109 +2358,237 @@ impl BatchTransferContract {
             // If a transfer fails due to contract-level issues (authorization, etc.),
             // it will panic and revert the entire batch. This is acceptable as
             // we've validated all inputs and balances.
-            token_client.transfer(&caller, &request.recipient, &request.amount);
+            let net_amount = request.amount - (fee - sender_fee);
+            token_client.transfer(source, &request.recipient, &net_amount);
 
             // Transfer succeeded
-            available_balance -= request.amount;
+            available_balance -= cost;
+            total_fees += fee;
             results.push_back(TransferResult::Success(
                 request.recipient.clone(),
                 request.amount,
             ));
             successful_count += 1;
+            if track_daily {
+                env.storage()
+                    .temporary()
+                    .set(&daily_key, &(daily_volume + request.amount));
+                env.storage().temporary().extend_ttl(
+                    &daily_key,
+                    DAILY_VOLUME_TTL_LEDGERS,
+                    DAILY_VOLUME_TTL_LEDGERS,
+                );
+            }
             total_transferred = total_transferred
                 .checked_add(request.amount)
                 .unwrap_or(total_transferred);
 
-            TransferEvents::transfer_success(&env, batch_id, &request.recipient, request.amount);
+            TransferEvents::transfer_success(env, batch_id, &request.recipient, request.amount);
+            if notification_contract.is_some() {
+                notifications.push_back(NotificationPayload {
+                    user: request.recipient.clone(),
+                    body: NotificationBody::PaymentReceived(PaymentReceived {
+                        from: submitter.clone(),
+                        token: token.clone(),
+                        amount: net_amount,
+                    }),
+                    category: symbol_short!("payment"),
+                    priority: 1,
+                });
+            }
+            if history_contract.is_some() {
+                let timestamp = env.ledger().timestamp();
+                history.push_back((
+                    submitter.clone(),
+                    TransactionRecord {
+                        amount: -cost,
+                        timestamp,
+                        description: String::from_str(env, "Batch transfer sent"),
+                        category: symbol_short!("transfer"),
+                        counterparty: Some(request.recipient.clone()),
+                        tx_type: TxType::Transfer,
+                        source_contract: Some(env.current_contract_address()),
+                        batch_id: Some(batch_id),
+                    },
+                ));
+                history.push_back((
+                    request.recipient.clone(),
+                    TransactionRecord {
+                        amount: net_amount,
+                        timestamp,
+                        description: String::from_str(env, "Batch transfer received"),
+                        category: symbol_short!("transfer"),
+                        counterparty: Some(submitter.clone()),
+                        tx_type: TxType::Transfer,
+                        source_contract: Some(env.current_contract_address()),
+                        batch_id: Some(batch_id),
+                    },
+                ));
+            }
+        }
+
+        // Route collected fees to the fee collector in a single transfer
+        if let Some(config) = &fee_config {
+            Self::collect_fees(env, batch_id, source, token, config, total_fees);
+        }
+
+        Self::report_wallet_spend(env, submitter, starting_balance - available_balance);
+
+        if let Some(contract) = notification_contract {
+            if !notifications.is_empty() {
+                let _ = NotificationsClient::new(env, &contract)
+                    .try_notify_from_contract(&env.current_contract_address(), &notifications);
+            }
+        }
+        if let Some(contract) = history_contract {
+            if !history.is_empty() {
+                let _ = HistoryClient::new(env, &contract)
+                    .try_batch_record_from_contract(&env.current_contract_address(), &history);
+            }
         }
 
         // Update storage (batched at the end for efficiency)
-        let total_batches: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalBatches)
-            .unwrap_or(0);
-        let total_processed: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalTransfersProcessed)
-            .unwrap_or(0);
-        let total_volume: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalVolumeTransferred)
-            .unwrap_or(0);
+        Self::record_stats(env, token, request_count, total_transferred, total_fees);
 
-        env.storage()
-            .instance()
-            .set(&DataKey::TotalBatches, &(total_batches + 1));
-        env.storage().instance().set(
-            &DataKey::TotalTransfersProcessed,
-            &(total_processed + request_count as u64),
-        );
-        env.storage().instance().set(
-            &DataKey::TotalVolumeTransferred,
-            &total_transferred
-                .checked_add(total_volume)
-                .unwrap_or(i128::MAX),
-        );
+        // Persist a compact record for reconciliation
+        let mut failed_items: Vec<TransferResult> = Vec::new(env);
+        for result in results.iter() {
+            if let TransferResult::Failure(..) = result {
+                failed_items.push_back(result);
+            }
+        }
+        let record = BatchRecord {
+            batch_id,
+            submitter: submitter.clone(),
+            source: source.clone(),
+            token: token.clone(),
+            total_requests: request_count,
+            successful: successful_count,
+            failed: failed_count,
+            total_transferred,
+            total_fees,
+            executed_at: env.ledger().timestamp(),
+            failed_items,
+            payload_hash: header.payload_hash.clone(),
+            from_pool: header.from_pool,
+            dedupe_mode: header.dedupe_mode.clone(),
+            require_full_balance: header.require_full_balance,
+            retry_batch_id: None,
+        };
+        Self::store_batch_record(env, &record);
 
         // Emit batch completed event
         TransferEvents::batch_completed(
-            &env,
+            env,
             batch_id,
             successful_count,
             failed_count,
             total_transferred,
+            &header.payload_hash,
         );
 
         BatchTransferResult {
+            batch_id,
+            status: BatchStatus::Executed,
             total_requests: request_count,
             successful: successful_count,
             failed: failed_count,
             total_transferred,
+            total_fees,
             results,
         }
     }
 
-    /// Returns the admin address.
-    pub fn get_admin(env: Env) -> Address {
-        env.storage()
+    // Internal helper routing a batch's fees from `source` to the fee
+    // collector in a single transfer
+    fn collect_fees(
+        env: &Env,
+        batch_id: u64,
+        source: &Address,
+        token: &Address,
+        config: &FeeConfig,
+        total_fees: i128,
+    ) {
+        if total_fees <= 0 {
+            return;
+        }
+        token::Client::new(env, token).transfer(source, &config.collector, &total_fees);
+
+        let fees_collected: i128 = env
+            .storage()
             .instance()
-            .get(&DataKey::Admin)
-            .expect("Contract not initialized")
+            .get(&DataKey::TotalFeesCollected)
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &DataKey::TotalFeesCollected,
+            &fees_collected.checked_add(total_fees).unwrap_or(i128::MAX),
+        );
+
+        TransferEvents::fees_collected(env, batch_id, &config.collector, total_fees);
     }
 
-    /// Updates the admin address.
-    pub fn set_admin(env: Env, current_admin: Address, new_admin: Address) {
-        current_admin.require_auth();
-        Self::require_admin(&env, &current_admin);
+    // Internal helper to persist a batch record for reconciliation
+    fn store_batch_record(env: &Env, record: &BatchRecord) {
+        let record_key = DataKey::BatchResult(record.batch_id);
+        env.storage().persistent().set(&record_key, record);
+        env.storage().persistent().extend_ttl(
+            &record_key,
+            BATCH_RESULT_TTL_THRESHOLD,
+            BATCH_RESULT_TTL_EXTEND,
+        );
+    }
 
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    // Internal helper that refuses single-signature payouts above the
+    // multi-signer approval threshold
+    fn require_within_approval_threshold(env: &Env, amount: i128) {
+        let approval_config: Option<ApprovalConfig> =
+            env.storage().instance().get(&DataKey::ApprovalConfig);
+        if approval_config.is_some_and(|config| amount > config.threshold) {
+            panic_with_error!(env, BatchTransferError::ApprovalRequired);
+        }
     }
 
-    /// Returns the total number of batches processed.
-    pub fn get_total_batches(env: Env) -> u64 {
-        env.storage()
-            .instance()
-            .get(&DataKey::TotalBatches)
-            .unwrap_or(0)
+    // Internal helper to verify the compliance officer
+    fn require_compliance_officer(env: &Env, caller: &Address) {
+        let officer: Option<Address> = env.storage().instance().get(&DataKey::ComplianceOfficer);
+        if officer.as_ref() != Some(caller) {
+            panic_with_error!(env, BatchTransferError::Unauthorized);
+        }
     }
 
-    /// Returns the total number of transfers processed (successful + failed).
-    pub fn get_total_transfers_processed(env: Env) -> u64 {
+    // Internal helper that keeps the contract instance alive
+    fn extend_instance_ttl(env: &Env) {
         env.storage()
             .instance()
-            .get(&DataKey::TotalTransfersProcessed)
-            .unwrap_or(0)
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND);
     }
 
-    /// Returns the total volume transferred (in stroops).
-    pub fn get_total_volume_transferred(env: Env) -> i128 {
-        env.storage()
+    // Internal helper that panics while the contract is paused
+    fn require_not_paused(env: &Env) {
+        if env.storage().instance().has(&DataKey::Paused) {
+            panic_with_error!(env, BatchTransferError::ContractPaused);
+        }
+    }
+
+    // Internal helper to verify the caller submitted the batch or is the admin
+    fn require_submitter_or_admin(env: &Env, caller: &Address, submitter: &Address) {
+        if caller == submitter {
+            return;
+        }
+        Self::require_admin(env, caller);
+    }
+
+    // Internal helper to verify admin or a delegated operator
+    fn require_admin_or_operator(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
             .instance()
-            .get(&DataKey::TotalVolumeTransferred)
-            .unwrap_or(0)
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+
+        if *caller != admin && !Self::is_operator(env.clone(), caller.clone()) {
+            panic_with_error!(env, BatchTransferError::Unauthorized);
+        }
     }
 
     // Internal helper to verify admin
@@ -2,10 +2,17 @@
 
 #![cfg(test)]
 
-use crate::{BatchTransferContract, BatchTransferContractClient, TransferRequest, TransferResult};
+use crate::{
+    BatchOptions, BatchStatus, BatchTransferContract, BatchTransferContractClient,
+    BatchTransferError, DataKey, DedupeMode, FeeMode, NotificationBody, NotificationPayload,
+    NotificationResult, PaymentReceived, StreamRequest, TransactionRecord, TransferError,
+    TransferRequest, TransferResult, WalletStatus, DAILY_VOLUME_TTL_LEDGERS,
+    IDEMPOTENCY_TTL_LEDGERS, MAX_BATCH_SIZE, SECONDS_PER_DAY, WALLET_DAILY_LIMIT_EXCEEDED,
+};
 use soroban_sdk::{
-    testutils::{Address as _, Events as _, Ledger},
-    token, Address, Env, Vec,
+    contract, contractimpl, panic_with_error, symbol_short,
+    testutils::{storage::Temporary as _, Address as _, Events as _, Ledger},
+    token, Address, BytesN, Env, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -37,9 +44,20 @@ fn setup_test_env() -> (
     let admin = Address::generate(&env);
     client.initialize(&admin);
 
+    // Fund the admin with 100,000 XLM
+    token::StellarAssetClient::new(&env, &token_id).mint(&admin, &1_000_000_000_000);
+
     (env, admin, token_id, token_client, client)
 }
 
+/// Helper to build submission options with the given dedupe mode.
+fn options(dedupe_mode: DedupeMode) -> BatchOptions {
+    BatchOptions {
+        dedupe_mode,
+        ..BatchOptions::default()
+    }
+}
+
 /// Helper to create a transfer request.
 fn create_transfer_request(_env: &Env, recipient: Address, amount: i128) -> TransferRequest {
     TransferRequest { recipient, amount }
@@ -60,7 +78,7 @@ fn test_initialize_contract() {
 #[test]
 #[should_panic(expected = "Contract already initialized")]
 fn test_cannot_initialize_twice() {
-    let (env, admin, _token, _token_client, client) = setup_test_env();
+    let (env, _admin, _token, _token_client, client) = setup_test_env();
 
     let new_admin = Address::generate(&env);
     client.initialize(&new_admin);
@@ -70,7 +88,7 @@ fn test_cannot_initialize_twice() {
 
 #[test]
 fn test_batch_transfer_single_recipient() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     let recipient = Address::generate(&env);
     let amount: i128 = 10_000_000; // 1 XLM
@@ -78,7 +96,7 @@ fn test_batch_transfer_single_recipient() {
     let mut transfers: Vec<TransferRequest> = Vec::new(&env);
     transfers.push_back(create_transfer_request(&env, recipient.clone(), amount));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 1);
     assert_eq!(result.successful, 1);
@@ -93,7 +111,7 @@ fn test_batch_transfer_single_recipient() {
 
 #[test]
 fn test_batch_transfer_multiple_recipients() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     let recipient1 = Address::generate(&env);
     let recipient2 = Address::generate(&env);
@@ -108,7 +126,7 @@ fn test_batch_transfer_multiple_recipients() {
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), amount2));
     transfers.push_back(create_transfer_request(&env, recipient3.clone(), amount3));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 3);
     assert_eq!(result.successful, 3);
@@ -134,7 +152,7 @@ fn test_batch_transfer_with_invalid_amount() {
         10_000_000,
     )); // Valid
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 2);
     assert_eq!(result.successful, 1);
@@ -143,10 +161,10 @@ fn test_batch_transfer_with_invalid_amount() {
 
     // Check that first result is failure
     match result.results.get(0).unwrap() {
-        TransferResult::Failure(recv, req_amount, error_code) => {
+        TransferResult::Failure(recv, req_amount, error) => {
             assert_eq!(recv.clone(), recipient1);
             assert_eq!(req_amount.clone(), -100);
-            assert_eq!(error_code.clone(), 1); // Invalid amount
+            assert_eq!(error, TransferError::InvalidAmount);
         }
         _ => panic!("Expected failure for invalid amount"),
     }
@@ -163,7 +181,7 @@ fn test_batch_transfer_with_invalid_amount() {
 
 #[test]
 fn test_batch_transfer_with_insufficient_balance() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     let recipient1 = Address::generate(&env);
     let recipient2 = Address::generate(&env);
@@ -175,7 +193,7 @@ fn test_batch_transfer_with_insufficient_balance() {
     transfers.push_back(create_transfer_request(&env, recipient1.clone(), amount1));
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), amount2));
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 2);
     assert_eq!(result.successful, 1);
@@ -188,7 +206,7 @@ fn test_batch_transfer_with_insufficient_balance() {
 
 #[test]
 fn test_batch_transfer_partial_failures() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     let recipient1 = Address::generate(&env);
     let recipient2 = Address::generate(&env);
@@ -209,7 +227,7 @@ fn test_batch_transfer_partial_failures() {
     )); // Valid
     transfers.push_back(create_transfer_request(&env, recipient4.clone(), -100)); // Invalid: negative
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 4);
     assert_eq!(result.successful, 2);
@@ -235,7 +253,7 @@ fn test_batch_transfer_events_emitted() {
     ));
     transfers.push_back(create_transfer_request(&env, recipient2.clone(), -100)); // Invalid
 
-    client.batch_transfer(&admin, &token, &transfers);
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     let events = env.events().all();
     // Should have: batch_started, transfer_success (1), transfer_failure (1), batch_completed
@@ -267,12 +285,12 @@ fn test_batch_transfer_accumulates_stats() {
     assert_eq!(client.get_total_transfers_processed(), 0);
     assert_eq!(client.get_total_volume_transferred(), 0);
 
-    client.batch_transfer(&admin, &token, &transfers1);
+    client.batch_transfer(&admin, &token, &transfers1, &BatchOptions::default());
     assert_eq!(client.get_total_batches(), 1);
     assert_eq!(client.get_total_transfers_processed(), 1);
     assert_eq!(client.get_total_volume_transferred(), 10_000_000);
 
-    client.batch_transfer(&admin, &token, &transfers2);
+    client.batch_transfer(&admin, &token, &transfers2, &BatchOptions::default());
     assert_eq!(client.get_total_batches(), 2);
     assert_eq!(client.get_total_transfers_processed(), 2);
     assert_eq!(client.get_total_volume_transferred(), 30_000_000);
@@ -284,13 +302,13 @@ fn test_batch_transfer_empty_batch() {
     let (env, admin, token, _token_client, client) = setup_test_env();
 
     let transfers: Vec<TransferRequest> = Vec::new(&env);
-    client.batch_transfer(&admin, &token, &transfers);
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 }
 
 #[test]
 #[should_panic]
 fn test_batch_transfer_unauthorized() {
-    let (env, admin, token, _token_client, client) = setup_test_env();
+    let (env, _admin, token, _token_client, client) = setup_test_env();
 
     let unauthorized = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -299,12 +317,12 @@ fn test_batch_transfer_unauthorized() {
     transfers.push_back(create_transfer_request(&env, recipient, 10_000_000));
 
     // This should panic due to unauthorized access
-    client.batch_transfer(&unauthorized, &token, &transfers);
+    client.batch_transfer(&unauthorized, &token, &transfers, &BatchOptions::default());
 }
 
 #[test]
 fn test_batch_transfer_large_batch() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     // Create a batch with 50 recipients
     let mut transfers: Vec<TransferRequest> = Vec::new(&env);
@@ -317,7 +335,7 @@ fn test_batch_transfer_large_batch() {
         // 0.1 XLM each
     }
 
-    let result = client.batch_transfer(&admin, &token, &transfers);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
 
     assert_eq!(result.total_requests, 50);
     assert_eq!(result.successful, 50);
@@ -327,6 +345,29 @@ fn test_batch_transfer_large_batch() {
     // Note: Balance verification for all recipients would be done in integration tests
 }
 
+#[test]
+fn test_batch_transfer_max_batch_size_fits_default_budget() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &1_000, &50, &FeeMode::ChargeSender);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    for _i in 0..MAX_BATCH_SIZE {
+        transfers.push_back(create_transfer_request(
+            &env,
+            Address::generate(&env),
+            1_000_000,
+        ));
+    }
+
+    // Runs under the default test budget, which mirrors network limits
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    assert_eq!(result.successful, MAX_BATCH_SIZE);
+    assert_eq!(token_client.balance(&collector), result.total_fees);
+}
+
 // Admin Tests
 
 #[test]
@@ -343,7 +384,7 @@ fn test_set_admin() {
 
 #[test]
 fn test_multiple_simultaneous_batch_transfers() {
-    let (env, admin, token, token_client, client) = setup_test_env();
+    let (env, admin, token, _token_client, client) = setup_test_env();
 
     // First batch: 3 recipients
     let recipient1 = Address::generate(&env);
@@ -367,7 +408,7 @@ fn test_multiple_simultaneous_batch_transfers() {
         30_000_000,
     ));
 
-    let result1 = client.batch_transfer(&admin, &token, &batch1);
+    let result1 = client.batch_transfer(&admin, &token, &batch1, &BatchOptions::default());
     assert_eq!(result1.successful, 3);
     assert_eq!(result1.total_transferred, 60_000_000);
 
@@ -382,7 +423,7 @@ fn test_multiple_simultaneous_batch_transfers() {
         15_000_000,
     ));
 
-    let result2 = client.batch_transfer(&admin, &token, &batch2);
+    let result2 = client.batch_transfer(&admin, &token, &batch2, &BatchOptions::default());
     assert_eq!(result2.successful, 2);
     assert_eq!(result2.total_transferred, 20_000_000);
 
@@ -398,3 +439,2092 @@ fn test_multiple_simultaneous_batch_transfers() {
     assert_eq!(client.get_total_transfers_processed(), 5);
     assert_eq!(client.get_total_volume_transferred(), 80_000_000);
 }
+
+// Multi-Signer Approval Tests
+
+#[test]
+fn test_batch_below_threshold_executes_immediately() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env)]);
+    client.set_approval_config(&admin, &100_000_000, &approvers, &2);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        50_000_000,
+    ));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.status, BatchStatus::Executed);
+    assert_eq!(result.successful, 1);
+    assert!(client.get_pending_batch(&result.batch_id).is_none());
+}
+
+#[test]
+fn test_batch_above_threshold_requires_approvals() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let approver1 = Address::generate(&env);
+    let approver2 = Address::generate(&env);
+    let approver3 = Address::generate(&env);
+    let approvers = Vec::from_array(
+        &env,
+        [approver1.clone(), approver2.clone(), approver3.clone()],
+    );
+    client.set_approval_config(&admin, &100_000_000, &approvers, &2);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        80_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient2.clone(),
+        70_000_000,
+    ));
+
+    let admin_balance = token_client.balance(&admin);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.status, BatchStatus::PendingApproval);
+    assert_eq!(result.total_transferred, 0);
+
+    // Funds are locked in the contract while pending
+    assert_eq!(token_client.balance(&admin), admin_balance - 150_000_000);
+    assert_eq!(token_client.balance(&client.address), 150_000_000);
+    assert_eq!(token_client.balance(&recipient1), 0);
+
+    let pending = client.get_pending_batch(&result.batch_id).unwrap();
+    assert_eq!(pending.total_amount, 150_000_000);
+    assert_eq!(pending.approvals.len(), 0);
+
+    // First approval keeps the batch pending
+    let first = client.approve_batch(&approver1, &result.batch_id);
+    assert_eq!(first.status, BatchStatus::PendingApproval);
+    assert_eq!(token_client.balance(&recipient1), 0);
+
+    // Second approval reaches the threshold and executes the batch
+    let executed = client.approve_batch(&approver3, &result.batch_id);
+    assert_eq!(executed.status, BatchStatus::Executed);
+    assert_eq!(executed.batch_id, result.batch_id);
+    assert_eq!(executed.successful, 2);
+    assert_eq!(executed.total_transferred, 150_000_000);
+
+    assert_eq!(token_client.balance(&recipient1), 80_000_000);
+    assert_eq!(token_client.balance(&recipient2), 70_000_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert!(client.get_pending_batch(&result.batch_id).is_none());
+    assert_eq!(client.get_total_volume_transferred(), 150_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_approve_batch_rejects_non_approver() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    client.approve_batch(&Address::generate(&env), &result.batch_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_approve_batch_rejects_duplicate_approval() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    let approvers = Vec::from_array(&env, [approver.clone(), Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &2);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    client.approve_batch(&approver, &result.batch_id);
+    client.approve_batch(&approver, &result.batch_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_set_approval_config_rejects_impossible_quorum() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &100_000_000, &approvers, &2);
+}
+
+// Transfer Cap Tests
+
+#[test]
+fn test_per_transfer_cap_fails_oversized_items() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &50_000_000, &0);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        50_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient2.clone(),
+        50_000_001,
+    ));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+
+    match result.results.get(1).unwrap() {
+        TransferResult::Failure(recv, _, error) => {
+            assert_eq!(recv, recipient2);
+            assert_eq!(error, TransferError::ExceedsTransferCap);
+        }
+        _ => panic!("Expected failure for oversized transfer"),
+    }
+    assert_eq!(token_client.balance(&recipient1), 50_000_000);
+    assert_eq!(token_client.balance(&recipient2), 0);
+}
+
+#[test]
+fn test_daily_recipient_cap_spans_batches() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &0, &30_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+    match result.results.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::ExceedsDailyCap),
+        _ => panic!("Expected failure for daily cap"),
+    }
+    assert_eq!(client.get_recipient_daily_volume(&recipient), 20_000_000);
+
+    // A later batch on the same day is still capped
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 1));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+
+    // The cap resets the next day
+    env.ledger().with_mut(|li| {
+        li.timestamp += 86_400;
+    });
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 30_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 60_000_000);
+}
+
+#[test]
+fn test_daily_recipient_volume_is_temporary() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &0, &30_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let key = DataKey::RecipientDailyVolume(recipient, day);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().persistent().has(&key));
+        assert_eq!(
+            env.storage().temporary().get_ttl(&key),
+            DAILY_VOLUME_TTL_LEDGERS
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_set_transfer_caps_rejects_negative() {
+    let (_env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &-1, &0);
+}
+
+// Protocol Fee Tests
+
+#[test]
+fn test_fee_deducted_from_transfer() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &0, &100, &FeeMode::DeductFromTransfer);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient2.clone(),
+        20_000_000,
+    ));
+
+    let admin_balance = token_client.balance(&admin);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    assert_eq!(result.successful, 2);
+    assert_eq!(result.total_transferred, 30_000_000);
+    assert_eq!(result.total_fees, 300_000);
+    assert_eq!(token_client.balance(&recipient1), 9_900_000);
+    assert_eq!(token_client.balance(&recipient2), 19_800_000);
+    assert_eq!(token_client.balance(&collector), 300_000);
+    assert_eq!(token_client.balance(&admin), admin_balance - 30_000_000);
+    assert_eq!(client.get_total_fees_collected(), 300_000);
+}
+
+#[test]
+fn test_fee_charged_to_sender() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &5_000, &50, &FeeMode::ChargeSender);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let admin_balance = token_client.balance(&admin);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    // 5_000 flat + 0.5% of 10_000_000
+    assert_eq!(result.total_fees, 55_000);
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+    assert_eq!(token_client.balance(&collector), 55_000);
+    assert_eq!(token_client.balance(&admin), admin_balance - 10_055_000);
+}
+
+#[test]
+fn test_fee_exceeding_amount_fails_item() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &1_000, &0, &FeeMode::DeductFromTransfer);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        1_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        1_001,
+    ));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+    match result.results.get(0).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::FeeExceedsAmount),
+        _ => panic!("Expected failure when fee consumes the whole amount"),
+    }
+
+    // Clearing the fee config stops fee collection
+    client.clear_fee_config(&admin);
+    assert!(client.get_fee_config().is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_set_fee_config_rejects_excessive_bps() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &0, &10_001, &FeeMode::ChargeSender);
+}
+
+// Duplicate Recipient Tests
+
+#[test]
+fn test_dedupe_merge_combines_repeated_recipient() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let other = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, other.clone(), 5_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 2_000_000));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &options(DedupeMode::Merge));
+    assert_eq!(result.total_requests, 2);
+    assert_eq!(result.successful, 2);
+    match result.results.get(0).unwrap() {
+        TransferResult::Success(recv, amount) => {
+            assert_eq!(recv, recipient);
+            assert_eq!(amount, 12_000_000);
+        }
+        _ => panic!("Expected merged transfer to succeed"),
+    }
+    assert_eq!(token_client.balance(&recipient), 12_000_000);
+    assert_eq!(token_client.balance(&other), 5_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_dedupe_reject_fails_batch() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    client.batch_transfer(&admin, &token, &transfers, &options(DedupeMode::Reject));
+}
+
+#[test]
+fn test_dedupe_allow_pays_each_entry() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 2);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+}
+
+// Preflight Validation Tests
+
+#[test]
+fn test_validate_batch_reports_without_transferring() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &500_000_000_000, &0);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let recipient3 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(&env, recipient2.clone(), -5));
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient3.clone(),
+        500_000_000_001,
+    ));
+
+    let admin_balance = token_client.balance(&admin);
+    let report = client.validate_batch(&admin, &token, &transfers);
+
+    assert_eq!(report.total_requests, 3);
+    assert_eq!(report.valid, 1);
+    assert_eq!(report.invalid, 2);
+    assert_eq!(report.total_needed, 10_000_000);
+    assert_eq!(report.available_balance, admin_balance);
+    assert!(report.sufficient_balance);
+    assert!(!report.requires_approval);
+    assert!(!report.exceeds_period_limit);
+
+    match report.items.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::InvalidAmount),
+        _ => panic!("Expected invalid amount"),
+    }
+    match report.items.get(2).unwrap() {
+        TransferResult::Failure(_, _, error) => {
+            assert_eq!(error, TransferError::ExceedsTransferCap)
+        }
+        _ => panic!("Expected per-transfer cap violation"),
+    }
+
+    // Nothing moved and no batch was recorded
+    assert_eq!(token_client.balance(&admin), admin_balance);
+    assert_eq!(token_client.balance(&recipient1), 0);
+    assert_eq!(client.get_total_batches(), 0);
+}
+
+#[test]
+fn test_validate_batch_flags_insufficient_balance_in_order() {
+    let (env, _admin, token, token_client, client) = setup_test_env();
+
+    let sender = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&sender, &15_000_000);
+    assert_eq!(token_client.balance(&sender), 15_000_000);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+
+    let report = client.validate_batch(&sender, &token, &transfers);
+    assert_eq!(report.total_needed, 20_000_000);
+    assert!(!report.sufficient_balance);
+    assert_eq!(report.valid, 1);
+    match report.items.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => {
+            assert_eq!(error, TransferError::InsufficientBalance)
+        }
+        _ => panic!("Expected insufficient balance"),
+    }
+}
+
+#[test]
+fn test_validate_batch_flags_fee_overflow_and_period_limit() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &5_000, &0, &FeeMode::ChargeSender);
+    client.set_period_limit(&admin, &5_000_000, &100);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        i128::MAX,
+    ));
+
+    let report = client.validate_batch(&admin, &token, &transfers);
+    assert_eq!(report.valid, 1);
+    assert_eq!(report.total_needed, 10_005_000);
+    assert!(report.exceeds_period_limit);
+    match report.items.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::InvalidAmount),
+        _ => panic!("Expected invalid amount"),
+    }
+}
+
+#[test]
+fn test_validate_batch_excludes_daily_cap_failures_from_total() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.set_transfer_caps(&admin, &0, &30_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+
+    let report = client.validate_batch(&admin, &token, &transfers);
+    assert_eq!(report.valid, 1);
+    assert_eq!(report.total_needed, 20_000_000);
+    match report.items.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::ExceedsDailyCap),
+        _ => panic!("Expected failure for daily cap"),
+    }
+
+    // Execution spends exactly the reported total
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.total_transferred, report.total_needed);
+}
+
+// Treasury Pool Tests
+
+#[test]
+fn test_batch_transfer_from_pool() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.deposit_pool(&admin, &token, &100_000_000);
+    assert_eq!(client.get_pool_balance(&token), 100_000_000);
+    assert_eq!(token_client.balance(&client.address), 100_000_000);
+
+    let admin_balance = token_client.balance(&admin);
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        60_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient2.clone(),
+        50_000_000,
+    ));
+
+    let result =
+        client.batch_transfer_from_pool(&admin, &token, &transfers, &BatchOptions::default());
+
+    // Second transfer exceeds what is left in the pool
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+    assert_eq!(token_client.balance(&recipient1), 60_000_000);
+    assert_eq!(token_client.balance(&recipient2), 0);
+    assert_eq!(client.get_pool_balance(&token), 40_000_000);
+
+    // The admin wallet is untouched
+    assert_eq!(token_client.balance(&admin), admin_balance);
+}
+
+#[test]
+fn test_pool_pending_batch_returns_leftover_to_pool() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    client.set_approval_config(&admin, &0, &Vec::from_array(&env, [approver.clone()]), &1);
+    client.deposit_pool(&admin, &token, &100_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 30_000_000));
+
+    let result =
+        client.batch_transfer_from_pool(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.status, BatchStatus::PendingApproval);
+    assert_eq!(client.get_pool_balance(&token), 70_000_000);
+
+    let executed = client.approve_batch(&approver, &result.batch_id);
+    assert_eq!(executed.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 30_000_000);
+    assert_eq!(client.get_pool_balance(&token), 70_000_000);
+    assert_eq!(token_client.balance(&client.address), 70_000_000);
+}
+
+#[test]
+fn test_withdraw_pool() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.deposit_pool(&admin, &token, &100_000_000);
+
+    let treasury = Address::generate(&env);
+    client.withdraw_pool(&admin, &token, &40_000_000, &treasury);
+
+    assert_eq!(client.get_pool_balance(&token), 60_000_000);
+    assert_eq!(token_client.balance(&treasury), 40_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_withdraw_pool_rejects_overdraw() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.deposit_pool(&admin, &token, &10_000_000);
+    client.withdraw_pool(&admin, &token, &10_000_001, &Address::generate(&env));
+}
+
+// Streaming Payout Tests
+
+#[test]
+fn test_stream_vests_linearly() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+
+    let recipient = Address::generate(&env);
+    let stream_id = client.create_stream(&admin, &recipient, &token, &100_000_000, &2_000, &12_000);
+    assert_eq!(token_client.balance(&client.address), 100_000_000);
+
+    // Nothing is claimable before the start
+    assert_eq!(client.get_claimable(&stream_id), 0);
+    assert_eq!(client.claim_stream(&recipient, &stream_id), 0);
+
+    // A quarter of the way through
+    env.ledger().with_mut(|li| {
+        li.timestamp = 4_500;
+    });
+    assert_eq!(client.claim_stream(&recipient, &stream_id), 25_000_000);
+    assert_eq!(token_client.balance(&recipient), 25_000_000);
+
+    // After the end everything remaining is claimable
+    env.ledger().with_mut(|li| {
+        li.timestamp = 20_000;
+    });
+    assert_eq!(client.claim_stream(&recipient, &stream_id), 75_000_000);
+    assert_eq!(token_client.balance(&recipient), 100_000_000);
+
+    let stream = client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.claimed_amount, 100_000_000);
+    assert_eq!(client.claim_stream(&recipient, &stream_id), 0);
+}
+
+#[test]
+fn test_create_streams_in_batch() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut streams: Vec<StreamRequest> = Vec::new(&env);
+    streams.push_back(StreamRequest {
+        recipient: recipient1.clone(),
+        total_amount: 10_000_000,
+        start_time: 0,
+        end_time: 100,
+    });
+    streams.push_back(StreamRequest {
+        recipient: recipient2.clone(),
+        total_amount: 20_000_000,
+        start_time: 0,
+        end_time: 200,
+    });
+
+    let stream_ids = client.create_streams(&admin, &token, &streams);
+    assert_eq!(stream_ids, Vec::from_array(&env, [1u64, 2u64]));
+    assert_eq!(token_client.balance(&client.address), 30_000_000);
+    assert_eq!(client.get_stream(&2).unwrap().recipient, recipient2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_claim_stream_rejects_other_recipient() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let stream_id = client.create_stream(&admin, &recipient, &token, &10_000_000, &0, &100);
+
+    client.claim_stream(&Address::generate(&env), &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_create_stream_rejects_invalid_schedule() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.create_stream(
+        &admin,
+        &Address::generate(&env),
+        &token,
+        &10_000_000,
+        &100,
+        &100,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_create_streams_above_approval_threshold_is_refused() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    client.set_approval_config(&admin, &5_000_000, &Vec::from_array(&env, [approver]), &1);
+
+    client.create_stream(
+        &admin,
+        &Address::generate(&env),
+        &token,
+        &10_000_000,
+        &100,
+        &200,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_create_streams_rejects_blocked_recipient() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let officer = Address::generate(&env);
+    client.set_compliance_officer(&admin, &officer);
+    let blocked = Address::generate(&env);
+    client.block_recipient(&officer, &blocked);
+
+    let mut streams: Vec<StreamRequest> = Vec::new(&env);
+    streams.push_back(StreamRequest {
+        recipient: Address::generate(&env),
+        total_amount: 10_000_000,
+        start_time: 0,
+        end_time: 100,
+    });
+    streams.push_back(StreamRequest {
+        recipient: blocked,
+        total_amount: 10_000_000,
+        start_time: 0,
+        end_time: 100,
+    });
+
+    client.create_streams(&admin, &token, &streams);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_create_stream_rejects_past_start() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+
+    client.create_stream(
+        &admin,
+        &Address::generate(&env),
+        &token,
+        &10_000_000,
+        &500,
+        &2_000,
+    );
+}
+
+// Payroll Registry Tests
+
+#[test]
+fn test_run_payroll_pays_registered_employees() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let other_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &other_token).mint(&admin, &1_000_000_000);
+    let other_client = token::Client::new(&env, &other_token);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    client.add_employee(&admin, &alice, &30_000_000, &token);
+    client.add_employee(&admin, &bob, &20_000_000, &other_token);
+    client.add_employee(&admin, &carol, &10_000_000, &token);
+    assert_eq!(client.get_employees().len(), 3);
+
+    let results = client.run_payroll(&admin);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results.get(0).unwrap().total_transferred, 40_000_000);
+    assert_eq!(results.get(1).unwrap().total_transferred, 20_000_000);
+
+    assert_eq!(token_client.balance(&alice), 30_000_000);
+    assert_eq!(token_client.balance(&carol), 10_000_000);
+    assert_eq!(other_client.balance(&bob), 20_000_000);
+
+    // A second run pays again from the stored registry
+    client.run_payroll(&admin);
+    assert_eq!(token_client.balance(&alice), 60_000_000);
+    assert_eq!(client.get_total_batches(), 4);
+}
+
+#[test]
+fn test_update_and_remove_employee() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.add_employee(&admin, &alice, &30_000_000, &token);
+    client.add_employee(&admin, &bob, &20_000_000, &token);
+
+    // Re-adding updates the salary without duplicating the entry
+    client.add_employee(&admin, &alice, &35_000_000, &token);
+    assert_eq!(client.get_employees().len(), 2);
+    assert_eq!(client.get_employee(&alice).unwrap().salary, 35_000_000);
+
+    client.remove_employee(&admin, &bob);
+    assert_eq!(
+        client.get_employees(),
+        Vec::from_array(&env, [alice.clone()])
+    );
+    assert!(client.get_employee(&bob).is_none());
+
+    client.run_payroll(&admin);
+    assert_eq!(token_client.balance(&alice), 35_000_000);
+    assert_eq!(token_client.balance(&bob), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_remove_unknown_employee() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.remove_employee(&admin, &Address::generate(&env));
+}
+
+// Batch Record Tests
+
+#[test]
+fn test_batch_result_is_stored() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(&env, recipient2.clone(), 0));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    let record = client.get_batch_result(&result.batch_id).unwrap();
+    assert_eq!(record.batch_id, result.batch_id);
+    assert_eq!(record.source, admin);
+    assert_eq!(record.token, token);
+    assert_eq!(record.successful, 1);
+    assert_eq!(record.failed, 1);
+    assert_eq!(record.total_transferred, 10_000_000);
+    assert_eq!(record.failed_items.len(), 1);
+    match record.failed_items.get(0).unwrap() {
+        TransferResult::Failure(recv, amount, error) => {
+            assert_eq!(recv, recipient2);
+            assert_eq!(amount, 0);
+            assert_eq!(error, TransferError::InvalidAmount);
+        }
+        _ => panic!("Expected only failures to be stored"),
+    }
+
+    assert!(client.get_batch_result(&(result.batch_id + 1)).is_none());
+}
+
+#[test]
+fn test_pending_batch_has_no_result_until_executed() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    client.set_approval_config(&admin, &0, &Vec::from_array(&env, [approver.clone()]), &1);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert!(client.get_batch_result(&result.batch_id).is_none());
+
+    client.approve_batch(&approver, &result.batch_id);
+    let record = client.get_batch_result(&result.batch_id).unwrap();
+    assert_eq!(record.successful, 1);
+    assert_eq!(record.source, client.address);
+}
+
+// Period Limit Tests
+
+#[test]
+fn test_period_limit_blocks_excess_volume() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.set_period_limit(&admin, &50_000_000, &100);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        30_000_000,
+    ));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(client.get_period_usage().unwrap().volume, 30_000_000);
+
+    // A second batch within the same period would exceed the cap
+    let result = client.try_batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert!(result.is_err());
+
+    // Once the period rolls over the cap resets
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 100;
+    });
+    assert_eq!(client.get_period_usage().unwrap().volume, 0);
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(client.get_period_usage().unwrap().volume, 30_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_period_limit_applies_to_pool_withdrawals() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.deposit_pool(&admin, &token, &100_000_000);
+    client.set_period_limit(&admin, &50_000_000, &100);
+
+    client.withdraw_pool(&admin, &token, &60_000_000, &Address::generate(&env));
+}
+
+#[test]
+fn test_period_limit_applies_to_streams() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.set_period_limit(&admin, &50_000_000, &100);
+
+    let recipient = Address::generate(&env);
+    client.create_stream(&admin, &recipient, &token, &30_000_000, &0, &100);
+    assert_eq!(client.get_period_usage().unwrap().volume, 30_000_000);
+
+    // Funding a second stream would push the period past its cap
+    let result = client.try_create_stream(&admin, &recipient, &token, &30_000_000, &0, &100);
+    assert!(result.is_err());
+    assert_eq!(token_client.balance(&client.address), 30_000_000);
+}
+
+// Operator Role Tests
+
+#[test]
+fn test_operator_can_submit_pool_batches() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.add_operator(&admin, &operator);
+    assert!(client.is_operator(&operator));
+
+    client.deposit_pool(&admin, &token, &50_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+
+    let result =
+        client.batch_transfer_from_pool(&operator, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+    assert_eq!(client.get_pool_balance(&token), 30_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_removed_operator_cannot_submit() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.add_operator(&admin, &operator);
+    client.remove_operator(&admin, &operator);
+    assert!(!client.is_operator(&operator));
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    client.batch_transfer_from_pool(&operator, &token, &transfers, &BatchOptions::default());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_operator_cannot_change_admin() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.add_operator(&admin, &operator);
+
+    client.set_admin(&operator, &operator);
+}
+
+// Recipient Allowlist Tests
+
+#[test]
+fn test_allowlist_enforcement() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let vendor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.add_recipient(&admin, &vendor);
+    assert!(client.is_recipient_allowed(&vendor));
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, vendor.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, stranger.clone(), 10_000_000));
+
+    // Not enforced: both recipients are paid
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 2);
+
+    // Enforced: unknown recipients fail with code 6
+    client.set_allowlist_enforced(&admin, &true);
+    assert!(client.is_allowlist_enforced());
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    match result.results.get(1).unwrap() {
+        TransferResult::Failure(recv, _, error) => {
+            assert_eq!(recv, stranger);
+            assert_eq!(error, TransferError::RecipientNotAllowed);
+        }
+        _ => panic!("Expected failure for unknown recipient"),
+    }
+    assert_eq!(token_client.balance(&vendor), 20_000_000);
+    assert_eq!(token_client.balance(&stranger), 10_000_000);
+
+    // Removed vendors are no longer payable
+    client.remove_recipient(&admin, &vendor);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 0);
+}
+
+// Denylist Tests
+
+#[test]
+fn test_blocked_recipient_is_screened() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let officer = Address::generate(&env);
+    client.set_compliance_officer(&admin, &officer);
+
+    let blocked = Address::generate(&env);
+    let clean = Address::generate(&env);
+    client.block_recipient(&officer, &blocked);
+    assert!(client.is_recipient_blocked(&blocked));
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, blocked.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, clean.clone(), 10_000_000));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    match result.results.get(0).unwrap() {
+        TransferResult::Failure(recv, _, error) => {
+            assert_eq!(recv, blocked);
+            assert_eq!(error, TransferError::RecipientBlocked);
+        }
+        _ => panic!("Expected blocked recipient to fail"),
+    }
+    assert_eq!(token_client.balance(&blocked), 0);
+
+    client.unblock_recipient(&officer, &blocked);
+    assert!(!client.is_recipient_blocked(&blocked));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_admin_cannot_manage_denylist() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.set_compliance_officer(&admin, &Address::generate(&env));
+    client.block_recipient(&admin, &Address::generate(&env));
+}
+
+// Idempotency Key Tests
+
+#[test]
+fn test_replayed_idempotency_key_returns_original_batch() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let keyed = BatchOptions {
+        idempotency_key: Some(BytesN::from_array(&env, &[7u8; 32])),
+        ..BatchOptions::default()
+    };
+    let first = client.batch_transfer(&admin, &token, &transfers, &keyed);
+    assert_eq!(first.status, BatchStatus::Executed);
+
+    let replay = client.batch_transfer(&admin, &token, &transfers, &keyed);
+    assert_eq!(replay.status, BatchStatus::Duplicate);
+    assert_eq!(replay.batch_id, first.batch_id);
+    assert_eq!(replay.successful, 0);
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+    assert_eq!(client.get_total_batches(), 1);
+
+    // Once the window has passed the key can be used again
+    env.ledger().with_mut(|li| {
+        li.sequence_number += IDEMPOTENCY_TTL_LEDGERS + 1;
+    });
+    let later = client.batch_transfer(&admin, &token, &transfers, &keyed);
+    assert_eq!(later.status, BatchStatus::Executed);
+    assert_eq!(later.batch_id, first.batch_id + 1);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+}
+
+#[test]
+fn test_idempotency_key_is_scoped_to_submitter() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.add_operator(&admin, &operator);
+    token::StellarAssetClient::new(&env, &token).mint(&operator, &10_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let keyed = BatchOptions {
+        idempotency_key: Some(BytesN::from_array(&env, &[7u8; 32])),
+        ..BatchOptions::default()
+    };
+    let first = client.batch_transfer(&admin, &token, &transfers, &keyed);
+    let other = client.batch_transfer(&operator, &token, &transfers, &keyed);
+    assert_eq!(other.status, BatchStatus::Executed);
+    assert_ne!(other.batch_id, first.batch_id);
+    assert_eq!(token_client.balance(&recipient), 20_000_000);
+}
+
+// Claimable Transfer Tests
+
+#[test]
+fn test_claimable_batch_claim_and_reclaim() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let claimer = Address::generate(&env);
+    let idle = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, claimer.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, idle.clone(), 20_000_000));
+
+    let start_balance = token_client.balance(&admin);
+    let deadline = env.ledger().timestamp() + 1_000;
+    let batch_id = client.batch_transfer_claimable(&admin, &token, &transfers, &deadline);
+    assert_eq!(token_client.balance(&admin), start_balance - 30_000_000);
+
+    assert_eq!(client.claim_transfer(&claimer, &batch_id), 10_000_000);
+    assert_eq!(token_client.balance(&claimer), 10_000_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = deadline + 1;
+    });
+    assert_eq!(client.reclaim_expired(&batch_id), 20_000_000);
+    assert_eq!(token_client.balance(&admin), start_balance - 10_000_000);
+    assert_eq!(token_client.balance(&idle), 0);
+    assert!(client.get_claimable_batch(&batch_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_claim_after_deadline_fails() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let deadline = env.ledger().timestamp() + 1_000;
+    let batch_id = client.batch_transfer_claimable(&admin, &token, &transfers, &deadline);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = deadline + 1;
+    });
+    client.claim_transfer(&recipient, &batch_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_reclaim_before_deadline_fails() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+
+    let deadline = env.ledger().timestamp() + 1_000;
+    let batch_id = client.batch_transfer_claimable(&admin, &token, &transfers, &deadline);
+    client.reclaim_expired(&batch_id);
+}
+
+#[test]
+fn test_claimable_batch_charges_fees_and_records_batch() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let collector = Address::generate(&env);
+    client.set_fee_config(&admin, &collector, &0, &100, &FeeMode::DeductFromTransfer);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+
+    let start_balance = token_client.balance(&admin);
+    let deadline = env.ledger().timestamp() + 1_000;
+    let batch_id = client.batch_transfer_claimable(&admin, &token, &transfers, &deadline);
+    assert_eq!(token_client.balance(&admin), start_balance - 10_000_000);
+    assert_eq!(token_client.balance(&collector), 100_000);
+
+    // The recipient claims the amount net of the deducted fee
+    assert_eq!(client.claim_transfer(&recipient, &batch_id), 9_900_000);
+
+    let record = client.get_batch_result(&batch_id).unwrap();
+    assert_eq!(record.total_transferred, 10_000_000);
+    assert_eq!(record.total_fees, 100_000);
+    let stats = client.get_token_stats(&token);
+    assert_eq!(stats.total_batches, 1);
+    assert_eq!(stats.total_volume, 10_000_000);
+    assert_eq!(client.get_total_fees_collected(), 100_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_claimable_batch_above_approval_threshold_is_refused() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    client.set_approval_config(&admin, &5_000_000, &Vec::from_array(&env, [approver]), &1);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+
+    let deadline = env.ledger().timestamp() + 1_000;
+    client.batch_transfer_claimable(&admin, &token, &transfers, &deadline);
+}
+
+// Split Disbursement Tests
+
+#[test]
+fn test_split_transfer_distributes_by_bps() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let partner_a = Address::generate(&env);
+    let partner_b = Address::generate(&env);
+    let partner_c = Address::generate(&env);
+    let mut splits: Vec<(Address, u32)> = Vec::new(&env);
+    splits.push_back((partner_a.clone(), 3_333));
+    splits.push_back((partner_b.clone(), 3_333));
+    splits.push_back((partner_c.clone(), 3_334));
+
+    let result = client.batch_split_transfer(&admin, &token, &100, &splits);
+    assert_eq!(result.successful, 3);
+    assert_eq!(result.total_transferred, 100);
+    assert_eq!(token_client.balance(&partner_a), 33);
+    assert_eq!(token_client.balance(&partner_b), 33);
+    // Rounding remainder goes to the last recipient
+    assert_eq!(token_client.balance(&partner_c), 34);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_split_transfer_rejects_incomplete_shares() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let mut splits: Vec<(Address, u32)> = Vec::new(&env);
+    splits.push_back((Address::generate(&env), 5_000));
+    splits.push_back((Address::generate(&env), 4_000));
+
+    client.batch_split_transfer(&admin, &token, &100, &splits);
+}
+
+// Pause Tests
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_paused_contract_rejects_batches() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    client.pause(&admin);
+    assert!(client.is_paused());
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+}
+
+#[test]
+fn test_unpause_resumes_payouts() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.pause(&admin);
+    client.unpause(&admin);
+    assert!(!client.is_paused());
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+}
+
+#[test]
+fn test_paused_contract_rejects_configuration_changes() {
+    let (env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.pause(&admin);
+    let paused = Some(Ok(BatchTransferError::ContractPaused.into()));
+    let other = Address::generate(&env);
+    assert_eq!(client.try_set_admin(&admin, &other).err(), paused);
+    assert_eq!(client.try_add_operator(&admin, &other).err(), paused);
+    assert_eq!(client.try_add_recipient(&admin, &other).err(), paused);
+    assert_eq!(
+        client.try_set_allowlist_enforced(&admin, &true).err(),
+        paused
+    );
+    assert_eq!(client.try_set_max_transfer_amount(&admin, &1).err(), paused);
+    assert_eq!(client.try_pause(&admin).err(), paused);
+    assert_eq!(client.get_admin(), admin);
+
+    client.unpause(&admin);
+    client.add_operator(&admin, &other);
+    assert!(client.is_operator(&other));
+}
+
+// Pending Batch Amendment Tests
+
+#[test]
+fn test_amend_scheduled_adjusts_locked_funds_and_resets_approvals() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let approver1 = Address::generate(&env);
+    let approver2 = Address::generate(&env);
+    let approvers = Vec::from_array(&env, [approver1.clone(), approver2.clone()]);
+    client.set_approval_config(&admin, &0, &approvers, &2);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 50_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    client.approve_batch(&approver1, &result.batch_id);
+
+    // Fix a typo'd amount before execution
+    let mut corrected: Vec<TransferRequest> = Vec::new(&env);
+    corrected.push_back(create_transfer_request(&env, recipient.clone(), 30_000_000));
+    client.amend_scheduled(&admin, &result.batch_id, &corrected);
+
+    let pending = client.get_pending_batch(&result.batch_id).unwrap();
+    assert_eq!(pending.total_amount, 30_000_000);
+    assert_eq!(pending.approvals.len(), 0);
+    assert_eq!(token_client.balance(&client.address), 30_000_000);
+
+    client.approve_batch(&approver1, &result.batch_id);
+    let executed = client.approve_batch(&approver2, &result.batch_id);
+    assert_eq!(executed.status, BatchStatus::Executed);
+    assert_eq!(token_client.balance(&recipient), 30_000_000);
+}
+
+#[test]
+fn test_cancel_scheduled_refunds_submitter() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        50_000_000,
+    ));
+
+    let admin_balance = token_client.balance(&admin);
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(token_client.balance(&admin), admin_balance - 50_000_000);
+
+    assert_eq!(
+        client.cancel_scheduled(&admin, &result.batch_id),
+        50_000_000
+    );
+    assert_eq!(token_client.balance(&admin), admin_balance);
+    assert!(client.get_pending_batch(&result.batch_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_cancel_scheduled_rejects_other_callers() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        50_000_000,
+    ));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    client.cancel_scheduled(&Address::generate(&env), &result.batch_id);
+}
+
+#[test]
+fn test_amend_and_cancel_scheduled_give_back_period_volume() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+    client.set_period_limit(&admin, &50_000_000, &100);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 40_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &options(DedupeMode::Merge));
+    assert_eq!(client.get_period_usage().unwrap().volume, 40_000_000);
+
+    // Amendments merge repeated recipients like the original submission
+    let mut corrected: Vec<TransferRequest> = Vec::new(&env);
+    corrected.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    corrected.push_back(create_transfer_request(&env, recipient.clone(), 5_000_000));
+    client.amend_scheduled(&admin, &result.batch_id, &corrected);
+    let pending = client.get_pending_batch(&result.batch_id).unwrap();
+    assert_eq!(pending.transfers.len(), 1);
+    assert_eq!(pending.total_amount, 15_000_000);
+    assert_eq!(client.get_period_usage().unwrap().volume, 15_000_000);
+
+    client.cancel_scheduled(&admin, &result.batch_id);
+    assert_eq!(client.get_period_usage().unwrap().volume, 0);
+}
+
+#[test]
+fn test_cancel_scheduled_gives_back_top_up_from_a_later_period() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+    client.set_period_limit(&admin, &50_000_000, &100);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 20_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    // The top-up counts toward the period it was made in
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 100;
+    });
+    let mut corrected: Vec<TransferRequest> = Vec::new(&env);
+    corrected.push_back(create_transfer_request(&env, recipient, 50_000_000));
+    client.amend_scheduled(&admin, &result.batch_id, &corrected);
+    assert_eq!(client.get_period_usage().unwrap().volume, 30_000_000);
+
+    // Only the volume counted in this period is given back
+    client.cancel_scheduled(&admin, &result.batch_id);
+    assert_eq!(client.get_period_usage().unwrap().volume, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_amend_scheduled_applies_dedupe_reject() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approvers = Vec::from_array(&env, [Address::generate(&env)]);
+    client.set_approval_config(&admin, &0, &approvers, &1);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &options(DedupeMode::Reject));
+
+    transfers.push_back(create_transfer_request(&env, recipient, 10_000_000));
+    client.amend_scheduled(&admin, &result.batch_id, &transfers);
+}
+
+// Token Statistics Tests
+
+#[test]
+fn test_token_stats_are_tracked_per_token() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let other_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &other_token).mint(&admin, &1_000_000_000);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        20_000_000,
+    ));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    let mut other: Vec<TransferRequest> = Vec::new(&env);
+    other.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        5_000,
+    ));
+    client.batch_transfer(&admin, &other_token, &other, &BatchOptions::default());
+
+    let stats = client.get_token_stats(&token);
+    assert_eq!(stats.total_batches, 1);
+    assert_eq!(stats.total_transfers, 2);
+    assert_eq!(stats.total_volume, 30_000_000);
+
+    let other_stats = client.get_token_stats(&other_token);
+    assert_eq!(other_stats.total_batches, 1);
+    assert_eq!(other_stats.total_volume, 5_000);
+
+    assert_eq!(
+        client
+            .get_token_stats(&Address::generate(&env))
+            .total_batches,
+        0
+    );
+}
+
+// Savings Goal Payout Tests
+
+/// Minimal stand-in for the savings-goals contract's `contribute` entrypoint.
+#[contract]
+struct MockSavingsGoals;
+
+#[contractimpl]
+impl MockSavingsGoals {
+    pub fn set_owner(env: Env, goal_id: u64, owner: Address) {
+        env.storage().persistent().set(&goal_id, &owner);
+    }
+
+    pub fn contribute(
+        env: Env,
+        contributor: Address,
+        goal_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> i128 {
+        contributor.require_auth();
+        let owner: Address = env.storage().persistent().get(&goal_id).unwrap();
+        token::Client::new(&env, &token).transfer(&contributor, &owner, &amount);
+        amount
+    }
+}
+
+#[test]
+fn test_batch_transfer_to_goals_contributes_each_goal() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let goals_id = env.register(MockSavingsGoals, ());
+    let goals = MockSavingsGoalsClient::new(&env, &goals_id);
+    let saver = Address::generate(&env);
+    goals.set_owner(&1, &saver);
+
+    let mut contributions: Vec<(Address, u64, i128)> = Vec::new(&env);
+    contributions.push_back((goals_id.clone(), 1, 25_000_000));
+    // Unknown goal fails without reverting the batch
+    contributions.push_back((goals_id.clone(), 2, 10_000_000));
+
+    let result = client.batch_transfer_to_goals(&admin, &token, &contributions);
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+    assert_eq!(result.total_transferred, 25_000_000);
+    match result.results.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => {
+            assert_eq!(error, TransferError::GoalContributionFailed)
+        }
+        _ => panic!("Expected failure for unknown goal"),
+    }
+    assert_eq!(token_client.balance(&saver), 25_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_batch_transfer_to_goals_above_approval_threshold_is_refused() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let approver = Address::generate(&env);
+    client.set_approval_config(&admin, &5_000_000, &Vec::from_array(&env, [approver]), &1);
+
+    let goals_id = env.register(MockSavingsGoals, ());
+    MockSavingsGoalsClient::new(&env, &goals_id).set_owner(&1, &Address::generate(&env));
+
+    let mut contributions: Vec<(Address, u64, i128)> = Vec::new(&env);
+    contributions.push_back((goals_id, 1, 10_000_000));
+    client.batch_transfer_to_goals(&admin, &token, &contributions);
+}
+
+// Full Balance Enforcement Tests
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_require_full_balance_rejects_underfunded_batch() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let balance = token_client.balance(&admin);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        balance,
+    ));
+    transfers.push_back(create_transfer_request(&env, Address::generate(&env), 1));
+
+    let strict = BatchOptions {
+        require_full_balance: true,
+        ..BatchOptions::default()
+    };
+    client.batch_transfer(&admin, &token, &transfers, &strict);
+}
+
+// Payload Hash Tests
+
+#[test]
+fn test_payload_hash_is_stored_with_batch_record() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+
+    let payload_hash = BytesN::from_array(&env, &[42u8; 32]);
+    let audited = BatchOptions {
+        payload_hash: Some(payload_hash.clone()),
+        ..BatchOptions::default()
+    };
+    let result = client.batch_transfer(&admin, &token, &transfers, &audited);
+
+    let record = client.get_batch_result(&result.batch_id).unwrap();
+    assert_eq!(record.payload_hash, Some(payload_hash));
+
+    let unaudited = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    let record = client.get_batch_result(&unaudited.batch_id).unwrap();
+    assert_eq!(record.payload_hash, None);
+}
+
+// Recipient Notification Tests
+
+/// Minimal stand-in for the batch-notifications contract.
+#[contract]
+struct MockNotifications;
+
+#[contractimpl]
+impl MockNotifications {
+    pub fn notify_from_contract(
+        env: Env,
+        source_contract: Address,
+        payloads: Vec<NotificationPayload>,
+    ) -> NotificationResult {
+        source_contract.require_auth();
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("sent"), &payloads);
+        NotificationResult {
+            successful_count: payloads.len(),
+            failures: Vec::new(&env),
+            suppressed_count: 0,
+        }
+    }
+
+    pub fn sent_payloads(env: Env) -> Vec<NotificationPayload> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("sent"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_successful_transfers_notify_recipients() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let notifications_id = env.register(MockNotifications, ());
+    let notifications = MockNotificationsClient::new(&env, &notifications_id);
+    client.set_notification_contract(&admin, &notifications_id);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, Address::generate(&env), -1));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    let sent = notifications.sent_payloads();
+    assert_eq!(sent.len(), 1);
+    let payload = sent.get(0).unwrap();
+    assert_eq!(payload.user, recipient);
+    assert_eq!(
+        payload.body,
+        NotificationBody::PaymentReceived(PaymentReceived {
+            from: admin.clone(),
+            token: token.clone(),
+            amount: 10_000_000,
+        })
+    );
+    assert_eq!(payload.category, symbol_short!("payment"));
+
+    // Pool payouts name the submitter as the payer, not this contract
+    client.deposit_pool(&admin, &token, &10_000_000);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    client.batch_transfer_from_pool(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(
+        notifications.sent_payloads().get(0).unwrap().body,
+        NotificationBody::PaymentReceived(PaymentReceived {
+            from: admin,
+            token,
+            amount: 10_000_000,
+        })
+    );
+}
+
+/// Minimal stand-in for the batch-history contract.
+#[contract]
+struct MockHistory;
+
+#[contractimpl]
+impl MockHistory {
+    pub fn batch_record_from_contract(
+        env: Env,
+        source_contract: Address,
+        records: Vec<(Address, TransactionRecord)>,
+    ) -> u32 {
+        source_contract.require_auth();
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("records"), &records);
+        records.len()
+    }
+
+    pub fn recorded(env: Env) -> Vec<(Address, TransactionRecord)> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("records"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_successful_transfers_are_recorded_in_history() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let history_id = env.register(MockHistory, ());
+    let history = MockHistoryClient::new(&env, &history_id);
+    client.set_history_contract(&admin, &history_id);
+    assert_eq!(client.get_history_contract(), Some(history_id));
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, Address::generate(&env), -1));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+
+    let records = history.recorded();
+    assert_eq!(records.len(), 2);
+    let (sender, sent) = records.get(0).unwrap();
+    assert_eq!(sender, admin);
+    assert_eq!(sent.amount, -10_000_000);
+    let (receiver, received) = records.get(1).unwrap();
+    assert_eq!(receiver, recipient);
+    assert_eq!(received.amount, 10_000_000);
+    assert_eq!(received.counterparty, Some(admin.clone()));
+
+    // Pool payouts are recorded against the submitter, not this contract
+    client.deposit_pool(&admin, &token, &10_000_000);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    client.batch_transfer_from_pool(&admin, &token, &transfers, &BatchOptions::default());
+    let (sender, _) = history.recorded().get(0).unwrap();
+    assert_eq!(sender, admin);
+
+    client.clear_history_contract(&admin);
+    assert_eq!(client.get_history_contract(), None);
+}
+
+/// Minimal stand-in for the batch-wallet-creation contract.
+#[contract]
+struct MockWallet;
+
+#[contractimpl]
+impl MockWallet {
+    pub fn set_status(env: Env, owner: Address, status: WalletStatus) {
+        env.storage().persistent().set(&owner, &status);
+    }
+
+    pub fn get_wallet_status(env: Env, owner: Address) -> Option<WalletStatus> {
+        env.storage().persistent().get(&owner)
+    }
+
+    pub fn set_daily_limit(env: Env, owner: Address, limit: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("limit"), owner), &limit);
+    }
+
+    pub fn record_spend(env: Env, _caller_contract: Address, owner: Address, amount: i128) {
+        let limit: i128 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("limit"), owner.clone()))
+            .unwrap_or(i128::MAX);
+        let spent = Self::get_spent(env.clone(), owner.clone()) + amount;
+        if spent > limit {
+            panic_with_error!(
+                &env,
+                soroban_sdk::Error::from_contract_error(WALLET_DAILY_LIMIT_EXCEEDED)
+            );
+        }
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("spent"), owner), &spent);
+    }
+
+    pub fn get_spent(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("spent"), owner))
+            .unwrap_or(0)
+    }
+
+    pub fn bump_activity(env: Env, _caller_contract: Address, owner: Address) {
+        let count = Self::get_activity(env.clone(), owner.clone()) + 1;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("active"), owner), &count);
+    }
+
+    pub fn get_activity(env: Env, owner: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("active"), owner))
+            .unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_inactive_recipient_wallets_are_not_paid() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let wallet_id = env.register(MockWallet, ());
+    let wallets = MockWalletClient::new(&env, &wallet_id);
+    client.set_wallet_contract(&admin, &wallet_id);
+    assert_eq!(client.get_wallet_contract(), Some(wallet_id));
+
+    let active = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let closed = Address::generate(&env);
+    let unregistered = Address::generate(&env);
+    wallets.set_status(&active, &WalletStatus::Active);
+    wallets.set_status(&frozen, &WalletStatus::Frozen);
+    wallets.set_status(&closed, &WalletStatus::Closed);
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, active.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, frozen.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, closed.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(
+        &env,
+        unregistered.clone(),
+        10_000_000,
+    ));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 2);
+    for i in [1, 2] {
+        match result.results.get(i).unwrap() {
+            TransferResult::Failure(_, _, error) => {
+                assert_eq!(error, TransferError::RecipientWalletInactive)
+            }
+            _ => panic!("Expected failure for inactive wallet"),
+        }
+    }
+    assert_eq!(token_client.balance(&active), 10_000_000);
+    assert_eq!(token_client.balance(&frozen), 0);
+    assert_eq!(token_client.balance(&unregistered), 10_000_000);
+
+    // Without a wallet contract the status is not consulted
+    client.clear_wallet_contract(&admin);
+    assert_eq!(client.get_wallet_contract(), None);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, frozen.clone(), 10_000_000));
+    client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(token_client.balance(&frozen), 10_000_000);
+}
+
+#[test]
+fn test_failing_wallet_contract_does_not_block_payouts() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    // A contract without `get_wallet_status` stands in for a broken one
+    client.set_wallet_contract(&admin, &token);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 10_000_000);
+}
+
+#[test]
+fn test_settled_batches_are_reported_to_the_wallet_contract() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let wallet_id = env.register(MockWallet, ());
+    let wallets = MockWalletClient::new(&env, &wallet_id);
+    client.set_wallet_contract(&admin, &wallet_id);
+    wallets.set_daily_limit(&admin, &25_000_000);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(&env, recipient2.clone(), 5_000_000));
+    let options = BatchOptions::default();
+    client.batch_transfer(&admin, &token, &transfers, &options);
+    assert_eq!(wallets.get_spent(&admin), 15_000_000);
+    assert_eq!(wallets.get_activity(&admin), 1);
+
+    // A batch past the submitter's daily limit reverts entirely
+    let result = client.try_batch_transfer(&admin, &token, &transfers, &options);
+    assert_eq!(
+        result.err(),
+        Some(Ok(BatchTransferError::WalletLimitExceeded.into()))
+    );
+    assert_eq!(token_client.balance(&recipient1), 10_000_000);
+    assert_eq!(wallets.get_spent(&admin), 15_000_000);
+    assert_eq!(wallets.get_activity(&admin), 1);
+}
+
+// Maximum Transfer Amount Tests
+
+#[test]
+fn test_max_transfer_amount_fails_oversized_items() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    client.set_max_transfer_amount(&admin, &50_000_000);
+    assert_eq!(client.get_max_transfer_amount(), 50_000_000);
+
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient1.clone(),
+        40_000_000,
+    ));
+    // Typo with an extra zero
+    transfers.push_back(create_transfer_request(
+        &env,
+        recipient2.clone(),
+        400_000_000,
+    ));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.successful, 1);
+    match result.results.get(1).unwrap() {
+        TransferResult::Failure(_, _, error) => assert_eq!(error, TransferError::ExceedsMaxAmount),
+        _ => panic!("Expected failure for amount above the maximum"),
+    }
+    assert_eq!(token_client.balance(&recipient2), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_set_max_transfer_amount_rejects_negative() {
+    let (_env, admin, _token, _token_client, client) = setup_test_env();
+
+    client.set_max_transfer_amount(&admin, &-1);
+}
+
+// Retry Tests
+
+#[test]
+fn test_retry_failed_reprocesses_only_failures() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let paid = Address::generate(&env);
+    let unpaid = Address::generate(&env);
+    let balance = token_client.balance(&admin);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, paid.clone(), balance));
+    transfers.push_back(create_transfer_request(&env, unpaid.clone(), 10_000_000));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    assert_eq!(result.failed, 1);
+
+    // Top up and retry just the failed transfer
+    token::StellarAssetClient::new(&env, &token).mint(&admin, &10_000_000);
+    let retry = client.retry_failed(&admin, &result.batch_id);
+    assert_eq!(retry.total_requests, 1);
+    assert_eq!(retry.successful, 1);
+    assert_eq!(token_client.balance(&unpaid), 10_000_000);
+    assert_eq!(token_client.balance(&paid), balance);
+
+    let record = client.get_batch_result(&result.batch_id).unwrap();
+    assert_eq!(record.retry_batch_id, Some(retry.batch_id));
+}
+
+#[test]
+fn test_retry_failed_replays_original_submitter_and_options() {
+    let (env, admin, token, token_client, client) = setup_test_env();
+
+    let operator = Address::generate(&env);
+    client.add_operator(&admin, &operator);
+    let token_admin = token::StellarAssetClient::new(&env, &token);
+    token_admin.mint(&operator, &10_000_000);
+
+    let recipient = Address::generate(&env);
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 10_000_000));
+    transfers.push_back(create_transfer_request(&env, recipient.clone(), 5_000_000));
+    let payload_hash = BytesN::from_array(&env, &[9u8; 32]);
+    let keyed = BatchOptions {
+        dedupe_mode: DedupeMode::Merge,
+        idempotency_key: Some(BytesN::from_array(&env, &[3u8; 32])),
+        require_full_balance: false,
+        payload_hash: Some(payload_hash.clone()),
+    };
+    let result = client.batch_transfer(&operator, &token, &transfers, &keyed);
+    assert_eq!(result.failed, 1);
+
+    // The retry is paid by the operator, not the admin, under the same options
+    token_admin.mint(&operator, &15_000_000);
+    let admin_balance = token_client.balance(&admin);
+    let retry = client.retry_failed(&admin, &result.batch_id);
+    assert_eq!(retry.status, BatchStatus::Executed);
+    assert_eq!(retry.successful, 1);
+    assert_eq!(token_client.balance(&recipient), 15_000_000);
+    assert_eq!(token_client.balance(&admin), admin_balance);
+
+    let record = client.get_batch_result(&retry.batch_id).unwrap();
+    assert_eq!(record.submitter, operator);
+    assert_eq!(record.dedupe_mode, DedupeMode::Merge);
+    assert_eq!(record.payload_hash, Some(payload_hash));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn test_retry_failed_only_once() {
+    let (env, admin, token, _token_client, client) = setup_test_env();
+
+    let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+    transfers.push_back(create_transfer_request(
+        &env,
+        Address::generate(&env),
+        10_000_000,
+    ));
+    transfers.push_back(create_transfer_request(&env, Address::generate(&env), 0));
+
+    let result = client.batch_transfer(&admin, &token, &transfers, &BatchOptions::default());
+    client.retry_failed(&admin, &result.batch_id);
+    client.retry_failed(&admin, &result.batch_id);
+}
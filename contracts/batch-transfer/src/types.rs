@@ -1,7 +1,40 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, String, Symbol, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
 
+/// Maximum protocol fee in basis points (100%).
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+/// Basis points that the shares of a split disbursement must sum to.
+pub const TOTAL_SPLIT_BPS: u32 = 10_000;
+
+/// Ledgers before expiry at which a stored batch record's TTL is extended.
+pub const BATCH_RESULT_TTL_THRESHOLD: u32 = 100_000;
+
+/// TTL, in ledgers, given to stored batch records (~30 days).
+pub const BATCH_RESULT_TTL_EXTEND: u32 = 518_400;
+
+/// Ledgers before expiry at which the contract instance TTL is extended.
+///
+/// Kept above `IDEMPOTENCY_TTL_LEDGERS` so configuration and counters outlive
+/// every remembered idempotency key.
+pub const INSTANCE_TTL_THRESHOLD: u32 = 100_000;
+
+/// TTL, in ledgers, given to the contract instance (~30 days).
+pub const INSTANCE_TTL_EXTEND: u32 = 518_400;
+
+/// Window, in ledgers, during which a used idempotency key is remembered (~1 day).
+pub const IDEMPOTENCY_TTL_LEDGERS: u32 = 17_280;
+
+/// Length of the window used for per-recipient daily caps.
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// TTL, in ledgers, of a recipient's daily volume entry (~1 day).
+pub const DAILY_VOLUME_TTL_LEDGERS: u32 = 17_280;
+
+/// Code of the batch-wallet-creation contract's `DailyLimitExceeded` error.
+pub const WALLET_DAILY_LIMIT_EXCEEDED: u32 = 17;
+
 #[derive(Clone, Debug)]
 #[contracttype]
 pub struct TransferRequest {
@@ -9,23 +42,427 @@ pub struct TransferRequest {
     pub amount: i128,
 }
 
+/// Reason a single transfer in a batch failed.
+///
+/// Discriminants match the numeric codes previously reported in results
+/// and `transfer_failure` events.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+#[repr(u32)]
+pub enum TransferError {
+    /// Recipient address is invalid
+    InvalidAddress = 0,
+    /// Amount must be positive
+    InvalidAmount = 1,
+    /// Source balance cannot cover the transfer
+    InsufficientBalance = 2,
+    /// Amount exceeds the per-transfer cap
+    ExceedsTransferCap = 3,
+    /// Recipient would exceed their daily cap
+    ExceedsDailyCap = 4,
+    /// Amount does not cover the deducted fee
+    FeeExceedsAmount = 5,
+    /// Recipient is not on the enforced allowlist
+    RecipientNotAllowed = 6,
+    /// Recipient is on the compliance denylist
+    RecipientBlocked = 7,
+    /// Savings goal contract rejected the contribution
+    GoalContributionFailed = 8,
+    /// Amount exceeds the configured maximum transfer amount
+    ExceedsMaxAmount = 9,
+    /// Recipient's wallet is frozen or closed
+    RecipientWalletInactive = 10,
+}
+
+/// Outcome of a single transfer.
 #[derive(Clone, Debug)]
 #[contracttype]
 pub enum TransferResult {
     Success(Address, i128),
-    Failure(Address, i128, u32),
+    Failure(Address, i128, TransferError),
+}
+
+/// How a batch treats recipients that appear more than once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DedupeMode {
+    /// Reject the whole batch if any recipient is repeated
+    Reject,
+    /// Merge repeated recipients into a single transfer
+    Merge,
+    /// Pay each entry separately
+    Allow,
+}
+
+/// Submission options for `batch_transfer` and `batch_transfer_from_pool`.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct BatchOptions {
+    /// How repeated recipients are handled
+    pub dedupe_mode: DedupeMode,
+    /// Client-supplied key; a key reused by the same submitter within
+    /// `IDEMPOTENCY_TTL_LEDGERS` returns the original batch instead of
+    /// paying again
+    pub idempotency_key: Option<BytesN<32>>,
+    /// Fail the whole batch up front if the source cannot cover every valid
+    /// transfer, instead of failing later items individually
+    pub require_full_balance: bool,
+    /// Hash of the approved off-chain payout file, kept with the batch record
+    pub payload_hash: Option<BytesN<32>>,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            dedupe_mode: DedupeMode::Allow,
+            idempotency_key: None,
+            require_full_balance: false,
+            payload_hash: None,
+        }
+    }
+}
+
+/// Execution status of a submitted batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum BatchStatus {
+    /// Transfers were executed
+    Executed,
+    /// Batch total exceeds the approval threshold and is awaiting approvals
+    PendingApproval,
+    /// Idempotency key was already used; nothing was executed
+    Duplicate,
 }
 
 #[derive(Clone, Debug)]
 #[contracttype]
 pub struct BatchTransferResult {
+    pub batch_id: u64,
+    pub status: BatchStatus,
     pub total_requests: u32,
     pub successful: u32,
     pub failed: u32,
     pub total_transferred: i128,
+    pub total_fees: i128,
     pub results: Vec<TransferResult>,
 }
 
+/// Compact record of an executed batch kept for reconciliation.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct BatchRecord {
+    pub batch_id: u64,
+    /// Admin or operator that submitted the batch
+    pub submitter: Address,
+    /// Address the funds were paid from
+    pub source: Address,
+    pub token: Address,
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub total_transferred: i128,
+    pub total_fees: i128,
+    /// Ledger timestamp of execution
+    pub executed_at: u64,
+    /// Only the failed items; successes can be derived from events
+    pub failed_items: Vec<TransferResult>,
+    /// Caller-supplied hash of the off-chain payout file, if any
+    pub payload_hash: Option<BytesN<32>>,
+    /// Whether the batch was paid from the treasury pool
+    pub from_pool: bool,
+    /// Submission options, replayed by `retry_failed`
+    pub dedupe_mode: DedupeMode,
+    pub require_full_balance: bool,
+    /// Batch that re-attempted this batch's failures, once retried
+    pub retry_batch_id: Option<u64>,
+}
+
+/// Lifetime statistics for a single payout token.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[contracttype]
+pub struct TokenStats {
+    /// Executed batches paid in this token
+    pub total_batches: u64,
+    /// Transfers processed (successful + failed)
+    pub total_transfers: u64,
+    /// Requested amount of successful transfers, before fees
+    pub total_volume: i128,
+    /// Protocol fees collected
+    pub total_fees: i128,
+}
+
+/// Preflight report produced by `validate_batch`.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct BatchValidationReport {
+    pub total_requests: u32,
+    pub valid: u32,
+    pub invalid: u32,
+    /// Total cost of all statically valid items, including sender-paid fees
+    pub total_needed: i128,
+    /// Caller's current token balance
+    pub available_balance: i128,
+    /// Whether the balance covers `total_needed`
+    pub sufficient_balance: bool,
+    /// Whether submission would be held for multi-signer approval
+    pub requires_approval: bool,
+    /// Whether submission would be rejected with `PeriodLimitExceeded`
+    pub exceeds_period_limit: bool,
+    /// Expected per-item outcome, using the same failure reasons as a submission
+    pub items: Vec<TransferResult>,
+}
+
+/// Message enqueued with the batch-notifications contract.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct NotificationPayload {
+    pub user: Address,
+    pub body: NotificationBody,
+    pub category: Symbol,
+    pub priority: u32,
+}
+
+/// The batch-notifications body variants this contract sends.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum NotificationBody {
+    Text(String),
+    PaymentReceived(PaymentReceived),
+}
+
+/// Payout details for a paid recipient.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentReceived {
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Mirror of the batch-wallet-creation contract's `WalletStatus`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum WalletStatus {
+    Active,
+    Frozen,
+    Closed,
+}
+
+/// Mirror of the batch-history contract's `TxType`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TxType {
+    Transfer,
+    Payment,
+    Conversion,
+    GoalContribution,
+    BudgetSpend,
+    Other,
+}
+
+/// Entry appended to a user's history in the batch-history contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TransactionRecord {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub description: String,
+    pub category: Symbol,
+    pub counterparty: Option<Address>,
+    pub tx_type: TxType,
+    pub source_contract: Option<Address>,
+    pub batch_id: Option<u64>,
+}
+
+/// Dispatch summary returned by the batch-notifications contract.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct NotificationResult {
+    pub successful_count: u32,
+    pub failures: Vec<FailedNotification>,
+    pub suppressed_count: u32,
+}
+
+/// Undelivered payload reported by the batch-notifications contract.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct FailedNotification {
+    pub user: Address,
+    pub error: u32,
+}
+
+/// A payroll registry entry.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Employee {
+    pub address: Address,
+    /// Amount paid on each payroll run
+    pub salary: i128,
+    /// Token the salary is paid in
+    pub token: Address,
+}
+
+/// Request to create a linearly vesting stream.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct StreamRequest {
+    pub recipient: Address,
+    pub total_amount: i128,
+    /// Ledger timestamp at which vesting begins
+    pub start_time: u64,
+    /// Ledger timestamp at which the full amount is vested
+    pub end_time: u64,
+}
+
+/// A linearly vesting payout held by the contract.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Stream {
+    pub stream_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub claimed_amount: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl Stream {
+    /// Returns the amount vested at `now`.
+    pub fn vested_at(&self, now: u64) -> i128 {
+        if now <= self.start_time {
+            return 0;
+        }
+        if now >= self.end_time {
+            return self.total_amount;
+        }
+        let elapsed = (now - self.start_time) as i128;
+        let duration = (self.end_time - self.start_time) as i128;
+        // Split the multiplication to avoid overflowing on large totals
+        (self.total_amount / duration) * elapsed
+            + (self.total_amount % duration) * elapsed / duration
+    }
+
+    /// Returns the vested amount not yet claimed at `now`.
+    pub fn claimable_at(&self, now: u64) -> i128 {
+        self.vested_at(now) - self.claimed_amount
+    }
+}
+
+/// A batch of escrowed transfers that recipients must claim before a deadline.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct ClaimableBatch {
+    pub batch_id: u64,
+    pub sender: Address,
+    pub token: Address,
+    /// Ledger timestamp after which unclaimed funds can be reclaimed
+    pub claim_deadline: u64,
+    /// Unclaimed amount per recipient
+    pub claims: Map<Address, i128>,
+}
+
+/// Multi-signer approval configuration for large batches.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct ApprovalConfig {
+    /// Batches whose total exceeds this amount require approval
+    pub threshold: i128,
+    /// Addresses allowed to approve pending batches
+    pub approvers: Vec<Address>,
+    /// Number of distinct approvals required before execution
+    pub required_approvals: u32,
+}
+
+/// Admin-configured amount caps. A value of zero disables the cap.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct TransferCaps {
+    /// Maximum amount of a single transfer
+    pub max_per_transfer: i128,
+    /// Maximum total a single recipient may receive per day
+    pub max_per_recipient_daily: i128,
+}
+
+/// How the protocol fee is charged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum FeeMode {
+    /// Fee is taken out of the amount the recipient receives
+    DeductFromTransfer,
+    /// Fee is paid by the sender on top of the transfer amount
+    ChargeSender,
+}
+
+/// Protocol fee configuration.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct FeeConfig {
+    /// Address receiving collected fees
+    pub collector: Address,
+    /// Flat fee per successful transfer
+    pub flat_fee: i128,
+    /// Proportional fee in basis points
+    pub fee_bps: u32,
+    pub mode: FeeMode,
+}
+
+impl FeeConfig {
+    /// Returns the fee owed on a transfer of `amount`.
+    pub fn fee_for(&self, amount: i128) -> i128 {
+        let proportional = amount
+            .checked_mul(self.fee_bps as i128)
+            .map(|v| v / MAX_FEE_BPS as i128)
+            .unwrap_or(i128::MAX);
+        self.flat_fee.saturating_add(proportional)
+    }
+}
+
+/// Admin-configured cap on total volume per rolling window of ledgers.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PeriodLimit {
+    /// Maximum volume that may be paid out per period
+    pub max_volume: i128,
+    /// Length of a period in ledgers
+    pub period_ledgers: u32,
+}
+
+/// Volume paid out in the current period.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PeriodUsage {
+    /// Ledger sequence at which the current period began
+    pub period_start: u32,
+    pub volume: i128,
+}
+
+/// A batch whose funds are locked in the contract until enough approvals are collected.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PendingBatch {
+    pub batch_id: u64,
+    pub submitter: Address,
+    pub token: Address,
+    pub transfers: Vec<TransferRequest>,
+    pub total_amount: i128,
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    /// Whether the locked funds came from the treasury pool
+    pub from_pool: bool,
+    /// Caller-supplied hash of the off-chain payout file, if any
+    pub payload_hash: Option<BytesN<32>>,
+    /// How repeated recipients were handled at submission, reapplied on amendment
+    pub dedupe_mode: DedupeMode,
+    /// Submission option carried into the executed batch's record
+    pub require_full_balance: bool,
+    /// Start of the period this batch's locked funds last counted toward
+    pub period_start: u32,
+    /// Volume this batch counted toward that period, given back on release
+    pub period_volume: i128,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -33,14 +470,69 @@ pub enum DataKey {
     TotalBatches,
     TotalTransfersProcessed,
     TotalVolumeTransferred,
+    /// Multi-signer approval configuration
+    ApprovalConfig,
+    /// Batch awaiting approval by ID
+    PendingBatch(u64),
+    /// Per-transfer and daily per-recipient caps
+    TransferCaps,
+    /// Amount received by a recipient on a given day (temporary storage)
+    RecipientDailyVolume(Address, u64),
+    /// Protocol fee configuration
+    FeeConfig,
+    /// Total fees routed to fee collectors
+    TotalFeesCollected,
+    /// Contract-held treasury pool balance per token
+    PoolBalance(Address),
+    /// Vesting stream by ID
+    Stream(u64),
+    /// Counter for stream IDs
+    StreamCounter,
+    /// Payroll record by employee address
+    Employee(Address),
+    /// List of registered employee addresses
+    Employees,
+    /// Stored record of an executed batch
+    BatchResult(u64),
+    /// Per-period volume cap
+    PeriodLimit,
+    /// Volume used in the current period
+    PeriodUsage,
+    /// Delegated operator flag by address
+    Operator(Address),
+    /// Whether transfers are restricted to allowlisted recipients
+    AllowlistEnforced,
+    /// Allowlisted recipient flag by address
+    AllowedRecipient(Address),
+    /// Address managing the recipient denylist
+    ComplianceOfficer,
+    /// Denylisted recipient flag by address
+    BlockedRecipient(Address),
+    /// Batch ID recorded for a submitter's idempotency key
+    IdempotencyKey(Address, BytesN<32>),
+    /// Escrowed claimable batch by ID
+    ClaimableBatch(u64),
+    /// Set while payouts are paused
+    Paused,
+    /// Lifetime statistics by token address
+    TokenStats(Address),
+    /// Batch-notifications contract used for payout messages
+    NotificationContract,
+    /// Batch-history contract that records settled transfers
+    HistoryContract,
+    /// Batch-wallet-creation contract consulted for recipient wallet status
+    WalletContract,
+    /// Hard ceiling on any single transfer amount
+    MaxTransferAmount,
 }
 
 pub struct TransferEvents;
 
 impl TransferEvents {
-    pub fn batch_started(env: &Env, batch_id: u64, request_count: u32) {
+    pub fn batch_started(env: &Env, batch_id: u64, request_count: u32, submitter: &Address) {
         let topics = (symbol_short!("batch"), symbol_short!("started"));
-        env.events().publish(topics, (batch_id, request_count));
+        env.events()
+            .publish(topics, (batch_id, request_count, submitter.clone()));
     }
 
     pub fn transfer_success(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
@@ -57,7 +549,7 @@ impl TransferEvents {
         batch_id: u64,
         recipient: &Address,
         requested_amount: i128,
-        error_code: u32,
+        error: TransferError,
     ) {
         let topics = (
             symbol_short!("transfer"),
@@ -65,7 +557,170 @@ impl TransferEvents {
             batch_id,
         );
         env.events()
-            .publish(topics, (recipient.clone(), requested_amount, error_code));
+            .publish(topics, (recipient.clone(), requested_amount, error));
+    }
+
+    pub fn batch_pending(env: &Env, batch_id: u64, submitter: &Address, total_amount: i128) {
+        let topics = (symbol_short!("batch"), symbol_short!("pending"), batch_id);
+        env.events()
+            .publish(topics, (submitter.clone(), total_amount));
+    }
+
+    pub fn batch_approved(env: &Env, batch_id: u64, approver: &Address, approvals: u32) {
+        let topics = (symbol_short!("batch"), symbol_short!("approved"), batch_id);
+        env.events().publish(topics, (approver.clone(), approvals));
+    }
+
+    pub fn batch_retried(env: &Env, batch_id: u64, retry_batch_id: u64, retried: u32) {
+        let topics = (symbol_short!("batch"), symbol_short!("retried"), batch_id);
+        env.events().publish(topics, (retry_batch_id, retried));
+    }
+
+    pub fn batch_amended(env: &Env, batch_id: u64, caller: &Address, total_amount: i128) {
+        let topics = (symbol_short!("batch"), symbol_short!("amended"), batch_id);
+        env.events().publish(topics, (caller.clone(), total_amount));
+    }
+
+    pub fn batch_cancelled(env: &Env, batch_id: u64, caller: &Address, refunded: i128) {
+        let topics = (symbol_short!("batch"), symbol_short!("cancelled"), batch_id);
+        env.events().publish(topics, (caller.clone(), refunded));
+    }
+
+    pub fn fees_collected(env: &Env, batch_id: u64, collector: &Address, total_fees: i128) {
+        let topics = (symbol_short!("fee"), symbol_short!("collected"), batch_id);
+        env.events()
+            .publish(topics, (collector.clone(), total_fees));
+    }
+
+    pub fn pool_deposit(env: &Env, token: &Address, from: &Address, amount: i128) {
+        let topics = (
+            symbol_short!("pool"),
+            symbol_short!("deposit"),
+            token.clone(),
+        );
+        env.events().publish(topics, (from.clone(), amount));
+    }
+
+    pub fn pool_withdraw(env: &Env, token: &Address, to: &Address, amount: i128) {
+        let topics = (
+            symbol_short!("pool"),
+            symbol_short!("withdraw"),
+            token.clone(),
+        );
+        env.events().publish(topics, (to.clone(), amount));
+    }
+
+    pub fn stream_created(
+        env: &Env,
+        stream_id: u64,
+        recipient: &Address,
+        total_amount: i128,
+        start_time: u64,
+        end_time: u64,
+    ) {
+        let topics = (symbol_short!("stream"), symbol_short!("created"), stream_id);
+        env.events().publish(
+            topics,
+            (recipient.clone(), total_amount, start_time, end_time),
+        );
+    }
+
+    pub fn stream_claimed(env: &Env, stream_id: u64, recipient: &Address, amount: i128) {
+        let topics = (symbol_short!("stream"), symbol_short!("claimed"), stream_id);
+        env.events().publish(topics, (recipient.clone(), amount));
+    }
+
+    pub fn employee_added(env: &Env, employee: &Address, salary: i128) {
+        let topics = (symbol_short!("payroll"), symbol_short!("added"));
+        env.events().publish(topics, (employee.clone(), salary));
+    }
+
+    pub fn employee_removed(env: &Env, employee: &Address) {
+        let topics = (symbol_short!("payroll"), symbol_short!("removed"));
+        env.events().publish(topics, employee.clone());
+    }
+
+    pub fn payroll_completed(env: &Env, employee_count: u32, batch_count: u32) {
+        let topics = (symbol_short!("payroll"), symbol_short!("completed"));
+        env.events().publish(topics, (employee_count, batch_count));
+    }
+
+    pub fn paused(env: &Env, admin: &Address) {
+        let topics = (symbol_short!("contract"), symbol_short!("paused"));
+        env.events().publish(topics, admin.clone());
+    }
+
+    pub fn unpaused(env: &Env, admin: &Address) {
+        let topics = (symbol_short!("contract"), symbol_short!("unpaused"));
+        env.events().publish(topics, admin.clone());
+    }
+
+    pub fn operator_added(env: &Env, operator: &Address) {
+        let topics = (symbol_short!("operator"), symbol_short!("added"));
+        env.events().publish(topics, operator.clone());
+    }
+
+    pub fn operator_removed(env: &Env, operator: &Address) {
+        let topics = (symbol_short!("operator"), symbol_short!("removed"));
+        env.events().publish(topics, operator.clone());
+    }
+
+    pub fn recipient_allowed(env: &Env, recipient: &Address) {
+        let topics = (symbol_short!("allowlst"), symbol_short!("added"));
+        env.events().publish(topics, recipient.clone());
+    }
+
+    pub fn recipient_disallowed(env: &Env, recipient: &Address) {
+        let topics = (symbol_short!("allowlst"), symbol_short!("removed"));
+        env.events().publish(topics, recipient.clone());
+    }
+
+    pub fn recipient_blocked(env: &Env, officer: &Address, recipient: &Address) {
+        let topics = (symbol_short!("denylist"), symbol_short!("blocked"));
+        env.events()
+            .publish(topics, (officer.clone(), recipient.clone()));
+    }
+
+    pub fn recipient_unblocked(env: &Env, officer: &Address, recipient: &Address) {
+        let topics = (symbol_short!("denylist"), symbol_short!("unblocked"));
+        env.events()
+            .publish(topics, (officer.clone(), recipient.clone()));
+    }
+
+    pub fn recipient_screened(env: &Env, batch_id: u64, recipient: &Address) {
+        let topics = (
+            symbol_short!("denylist"),
+            symbol_short!("screened"),
+            batch_id,
+        );
+        env.events().publish(topics, recipient.clone());
+    }
+
+    pub fn claim_created(
+        env: &Env,
+        batch_id: u64,
+        sender: &Address,
+        total_amount: i128,
+        claim_deadline: u64,
+    ) {
+        let topics = (symbol_short!("claim"), symbol_short!("created"), batch_id);
+        env.events()
+            .publish(topics, (sender.clone(), total_amount, claim_deadline));
+    }
+
+    pub fn transfer_claimed(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
+        let topics = (symbol_short!("claim"), symbol_short!("claimed"), batch_id);
+        env.events().publish(topics, (recipient.clone(), amount));
+    }
+
+    pub fn claim_expired(env: &Env, batch_id: u64, recipient: &Address, amount: i128) {
+        let topics = (symbol_short!("claim"), symbol_short!("expired"), batch_id);
+        env.events().publish(topics, (recipient.clone(), amount));
+    }
+
+    pub fn batch_replayed(env: &Env, batch_id: u64, submitter: &Address) {
+        let topics = (symbol_short!("batch"), symbol_short!("replayed"), batch_id);
+        env.events().publish(topics, submitter.clone());
     }
 
     pub fn batch_completed(
@@ -74,9 +729,12 @@ impl TransferEvents {
         successful: u32,
         failed: u32,
         total_transferred: i128,
+        payload_hash: &Option<BytesN<32>>,
     ) {
         let topics = (symbol_short!("batch"), symbol_short!("completed"), batch_id);
-        env.events()
-            .publish(topics, (successful, failed, total_transferred));
+        env.events().publish(
+            topics,
+            (successful, failed, total_transferred, payload_hash.clone()),
+        );
     }
 }
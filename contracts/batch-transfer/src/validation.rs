@@ -1,12 +1,26 @@
 //! Validation utilities for batch transfers.
 
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::types::{DataKey, DedupeMode, TransferCaps, TransferRequest};
 
 /// Validation error types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ValidationError {
     /// Invalid transfer amount
     InvalidAmount,
+    /// Amount exceeds the per-transfer cap
+    ExceedsTransferCap,
+    /// Amount would exceed the recipient's daily cap
+    ExceedsDailyCap,
+    /// Recipient appears more than once in the batch
+    DuplicateRecipient,
+    /// Recipient is not on the enforced allowlist
+    RecipientNotAllowed,
+    /// Recipient is on the compliance denylist
+    RecipientBlocked,
+    /// Amount exceeds the configured maximum transfer amount
+    ExceedsMaxAmount,
 }
 
 /// Validates a recipient address.
@@ -14,6 +28,39 @@ pub fn validate_address(_env: &Env, _address: &Address) -> Result<(), Validation
     Ok(())
 }
 
+/// Screens a recipient against the compliance denylist.
+pub fn validate_recipient_not_blocked(
+    env: &Env,
+    recipient: &Address,
+) -> Result<(), ValidationError> {
+    if env
+        .storage()
+        .persistent()
+        .has(&DataKey::BlockedRecipient(recipient.clone()))
+    {
+        return Err(ValidationError::RecipientBlocked);
+    }
+    Ok(())
+}
+
+/// Validates a recipient against the allowlist, when enforcement is on.
+pub fn validate_recipient_allowed(env: &Env, recipient: &Address) -> Result<(), ValidationError> {
+    let enforced: bool = env
+        .storage()
+        .instance()
+        .get(&DataKey::AllowlistEnforced)
+        .unwrap_or(false);
+    if enforced
+        && !env
+            .storage()
+            .persistent()
+            .has(&DataKey::AllowedRecipient(recipient.clone()))
+    {
+        return Err(ValidationError::RecipientNotAllowed);
+    }
+    Ok(())
+}
+
 /// Validates a transfer amount.
 /// Ensures the amount is positive and within reasonable bounds.
 pub fn validate_amount(amount: i128) -> Result<(), ValidationError> {
@@ -24,6 +71,79 @@ pub fn validate_amount(amount: i128) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validates an amount against the configured maximum transfer amount.
+pub fn validate_max_transfer_amount(env: &Env, amount: i128) -> Result<(), ValidationError> {
+    let max_amount: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxTransferAmount)
+        .unwrap_or(0);
+    if max_amount > 0 && amount > max_amount {
+        return Err(ValidationError::ExceedsMaxAmount);
+    }
+    Ok(())
+}
+
+/// Validates an amount against the per-transfer cap.
+pub fn validate_transfer_cap(amount: i128, caps: &TransferCaps) -> Result<(), ValidationError> {
+    if caps.max_per_transfer > 0 && amount > caps.max_per_transfer {
+        return Err(ValidationError::ExceedsTransferCap);
+    }
+    Ok(())
+}
+
+/// Validates that a transfer keeps the recipient within their daily cap.
+pub fn validate_daily_cap(
+    received_today: i128,
+    amount: i128,
+    caps: &TransferCaps,
+) -> Result<(), ValidationError> {
+    if caps.max_per_recipient_daily > 0
+        && received_today.saturating_add(amount) > caps.max_per_recipient_daily
+    {
+        return Err(ValidationError::ExceedsDailyCap);
+    }
+    Ok(())
+}
+
+/// Applies the dedupe mode to a batch.
+///
+/// In `Merge` mode positive amounts for the same recipient are summed into the
+/// first occurrence; non-positive entries are kept so they fail validation.
+pub fn dedupe_transfers(
+    env: &Env,
+    transfers: &Vec<TransferRequest>,
+    mode: &DedupeMode,
+) -> Result<Vec<TransferRequest>, ValidationError> {
+    if *mode == DedupeMode::Allow {
+        return Ok(transfers.clone());
+    }
+
+    let mut deduped: Vec<TransferRequest> = Vec::new(env);
+    for request in transfers.iter() {
+        let existing = deduped
+            .iter()
+            .position(|r| r.recipient == request.recipient && r.amount > 0 && request.amount > 0);
+        let repeated = deduped.iter().any(|r| r.recipient == request.recipient);
+
+        match mode {
+            DedupeMode::Reject if repeated => return Err(ValidationError::DuplicateRecipient),
+            DedupeMode::Merge => {
+                if let Some(index) = existing {
+                    let index = index as u32;
+                    let mut merged = deduped.get(index).unwrap();
+                    merged.amount = merged.amount.saturating_add(request.amount);
+                    deduped.set(index, merged);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        deduped.push_back(request);
+    }
+    Ok(deduped)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,6 +167,72 @@ mod tests {
         assert_eq!(validate_amount(0), Err(ValidationError::InvalidAmount));
     }
 
+    #[test]
+    fn test_validate_transfer_cap() {
+        let caps = TransferCaps {
+            max_per_transfer: 1000,
+            max_per_recipient_daily: 0,
+        };
+        assert!(validate_transfer_cap(1000, &caps).is_ok());
+        assert_eq!(
+            validate_transfer_cap(1001, &caps),
+            Err(ValidationError::ExceedsTransferCap)
+        );
+
+        let uncapped = TransferCaps {
+            max_per_transfer: 0,
+            max_per_recipient_daily: 0,
+        };
+        assert!(validate_transfer_cap(i128::MAX, &uncapped).is_ok());
+    }
+
+    #[test]
+    fn test_validate_daily_cap() {
+        let caps = TransferCaps {
+            max_per_transfer: 0,
+            max_per_recipient_daily: 1000,
+        };
+        assert!(validate_daily_cap(400, 600, &caps).is_ok());
+        assert_eq!(
+            validate_daily_cap(400, 601, &caps),
+            Err(ValidationError::ExceedsDailyCap)
+        );
+    }
+
+    #[test]
+    fn test_dedupe_transfers() {
+        let env = Env::default();
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let mut transfers: Vec<TransferRequest> = Vec::new(&env);
+        transfers.push_back(TransferRequest {
+            recipient: alice.clone(),
+            amount: 100,
+        });
+        transfers.push_back(TransferRequest {
+            recipient: bob.clone(),
+            amount: 50,
+        });
+        transfers.push_back(TransferRequest {
+            recipient: alice.clone(),
+            amount: 25,
+        });
+
+        let allowed = dedupe_transfers(&env, &transfers, &DedupeMode::Allow).unwrap();
+        assert_eq!(allowed.len(), 3);
+
+        let merged = dedupe_transfers(&env, &transfers, &DedupeMode::Merge).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged.get(0).unwrap().recipient, alice);
+        assert_eq!(merged.get(0).unwrap().amount, 125);
+        assert_eq!(merged.get(1).unwrap().amount, 50);
+
+        assert_eq!(
+            dedupe_transfers(&env, &transfers, &DedupeMode::Reject).err(),
+            Some(ValidationError::DuplicateRecipient)
+        );
+    }
+
     #[test]
     fn test_validate_address() {
         let env = Env::default();
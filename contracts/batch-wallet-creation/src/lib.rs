@@ -5,16 +5,26 @@ mod types;
 mod validation;
 
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, Address, Env, Vec,
+    contract, contractclient, contracterror, contractimpl, panic_with_error, symbol_short, token,
+    xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
 };
 
 pub use crate::types::{
-    BatchCreateResult, DataKey, Wallet, WalletCreateRequest, WalletCreateResult, WalletEvents, MAX_BATCH_SIZE,
+    BatchBudgetResult, BatchCreateResult, BatchUpdateResult, BudgetRequest, DailySpend, DataKey,
+    GuardianConfig, PendingRecovery, StatusUpdate, TierLimits, Wallet, WalletActivity,
+    WalletCreateRequest, WalletCreateResult, WalletEvents, WalletSigners, WalletStatus,
+    WalletUpdateRequest,
+    WalletUpdateResult, MAX_BATCH_SIZE, MAX_GUARDIANS, MAX_PAGE_SIZE, MAX_SIGNERS,
+    MAX_WALLETS_PER_OWNER, RECOVERY_TIMELOCK_SECS, SECONDS_PER_DAY,
+};
+use crate::validation::{
+    owner_has_wallet_type, owner_wallet_indexes, validate_address, validate_funding,
+    validate_metadata, wallet_exists,
 };
-use crate::validation::{validate_address, wallet_exists};
 
 /// Error codes for the batch wallet creation contract.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum BatchWalletError {
     /// Contract not initialized
@@ -27,12 +37,58 @@ pub enum BatchWalletError {
     EmptyBatch = 4,
     /// Batch exceeds maximum size
     BatchTooLarge = 5,
+    /// Owner has no wallet
+    WalletNotFound = 6,
+    /// Label is too long or wallet type is unknown
+    InvalidMetadata = 7,
+    /// Wallet is not in the status required for this operation
+    InvalidStatus = 8,
+    /// New owner already has a wallet
+    WalletAlreadyExists = 9,
+    /// No recovery is pending for the wallet
+    RecoveryNotFound = 10,
+    /// Recovery timelock has not elapsed yet
+    RecoveryLocked = 11,
+    /// Signer is already registered, not registered, or the set is full
+    InvalidSigner = 12,
+    /// Threshold is zero or exceeds the number of signers
+    InvalidThreshold = 13,
+    /// Tier has no limits configured, or the limits are negative
+    InvalidTier = 14,
+    /// Caller contract is not trusted to report wallet activity
+    UntrustedContract = 15,
+    /// Spend amount is not positive, or funding is negative or has no token
+    InvalidAmount = 16,
+    /// Spend would exceed the owner's daily limit
+    DailyLimitExceeded = 17,
+    /// Guardian list has duplicates, includes the owner, or is too long
+    InvalidGuardians = 18,
+    /// Caller is not one of the owner's guardians
+    NotGuardian = 19,
+    /// A recovery is already pending for the owner
+    RecoveryPending = 20,
+    /// Invite code is unknown or already used, or its expiry is in the past
+    InvalidInvite = 21,
+    /// Invite code has expired
+    InviteExpired = 22,
+    /// Contract already has an admin
+    AlreadyInitialized = 23,
+    /// Admin balance cannot cover a wallet's initial funding
+    InsufficientBalance = 24,
+    /// Budget is negative, or no budget-allocation contract is configured
+    InvalidBudget = 25,
+    /// Amount would take a wallet past its tier's maximum balance
+    BalanceLimitExceeded = 26,
 }
 
-impl From<BatchWalletError> for soroban_sdk::Error {
-    fn from(e: BatchWalletError) -> Self {
-        soroban_sdk::Error::from_contract_error(e as u32)
-    }
+/// Allocation entrypoint exposed by the budget-allocation contract.
+#[contractclient(name = "BudgetClient")]
+pub trait BudgetInterface {
+    fn batch_allocate_budget(
+        env: Env,
+        admin: Address,
+        requests: Vec<BudgetRequest>,
+    ) -> BatchBudgetResult;
 }
 
 #[contract]
@@ -43,7 +99,7 @@ impl BatchWalletContract {
     /// Initializes the contract with an admin address.
     pub fn initialize(env: Env, admin: Address) {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Contract already initialized");
+            panic_with_error!(&env, BatchWalletError::AlreadyInitialized);
         }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -52,6 +108,16 @@ impl BatchWalletContract {
     }
 
     /// Executes batch creation of wallets for multiple owners.
+    ///
+    /// An owner can hold up to `MAX_WALLETS_PER_OWNER` wallets, one per
+    /// wallet type; each new wallet takes the owner's next free index.
+    ///
+    /// Requests with `initial_funding` are funded from the admin's balance of
+    /// `funding_token`, paid to the deployed account contract when there is
+    /// one and to the owner otherwise.
+    ///
+    /// Requests with a `budget` have it allocated to the owner through the
+    /// budget-allocation contract in the same transaction.
     pub fn batch_create_wallets(
         env: Env,
         caller: Address,
@@ -92,25 +158,66 @@ impl BatchWalletContract {
             .instance()
             .get(&DataKey::TotalWalletsCreated)
             .unwrap_or(0) + 1;
+        let wasm_hash: Option<BytesN<32>> = env.storage().instance().get(&DataKey::WalletWasmHash);
+        // Admin balances per funding token, fetched lazily
+        let mut balances: Map<Address, i128> = Map::new(&env);
+        let budget_contract: Option<Address> =
+            env.storage().instance().get(&DataKey::BudgetContract);
+        let mut budgets: Vec<BudgetRequest> = Vec::new(&env);
 
         // Process each request
         for request in requests.iter() {
             let mut is_valid = true;
-            let mut error_code = 0u32;
+            let mut error = BatchWalletError::InvalidBatch;
 
             // Validate owner address
             if validate_address(&request.owner).is_err() {
                 is_valid = false;
-                error_code = 0; // Invalid address
             }
-            // Check if wallet already exists
-            else if wallet_exists(&env, &request.owner) {
+            // Check the owner has room for another wallet of this type
+            else if owner_has_wallet_type(&env, &request.owner, &request.wallet_type)
+                || owner_wallet_indexes(&env, &request.owner).len() >= MAX_WALLETS_PER_OWNER
+            {
+                is_valid = false;
+                error = BatchWalletError::WalletAlreadyExists;
+            }
+            // Check label and wallet type
+            else if validate_metadata(&request.label, &request.wallet_type).is_err() {
+                is_valid = false;
+                error = BatchWalletError::InvalidMetadata;
+            }
+            // Check initial funding
+            else if validate_funding(request.initial_funding, &request.funding_token).is_err() {
+                is_valid = false;
+                error = BatchWalletError::InvalidAmount;
+            }
+            // Check the budget, which needs a budget-allocation contract
+            else if request.budget < 0 || (request.budget > 0 && budget_contract.is_none()) {
                 is_valid = false;
-                error_code = 1; // Wallet already exists
+                error = BatchWalletError::InvalidBudget;
+            }
+            // Check the admin can cover the funding
+            else if let Some(funding_token) = request.funding_token.clone() {
+                let balance = balances.get(funding_token.clone()).unwrap_or_else(|| {
+                    token::Client::new(&env, &funding_token).balance(&caller)
+                });
+                balances.set(funding_token, balance);
+                if request.initial_funding > balance {
+                    is_valid = false;
+                    error = BatchWalletError::InsufficientBalance;
+                } else if !Self::check_balance_limit(
+                    env.clone(),
+                    request.owner.clone(),
+                    request.initial_funding,
+                ) {
+                    is_valid = false;
+                    error = BatchWalletError::BalanceLimitExceeded;
+                }
             }
 
             if !is_valid {
                 // Validation failed - record and continue
+                let error_code = error as u32;
                 results.push_back(WalletCreateResult::Failure(
                     request.owner.clone(),
                     error_code,
@@ -125,29 +232,65 @@ impl BatchWalletContract {
                 continue;
             }
 
-            // Create wallet
-            let wallet = Wallet {
-                id: next_wallet_id,
-                owner: request.owner.clone(),
-                created_at: env.ledger().timestamp(),
-            };
+            // Create and store the wallet, deploying its account contract
+            let wallet = Self::new_wallet(
+                &env,
+                &wasm_hash,
+                next_wallet_id,
+                &request.owner,
+                &request.label,
+                &request.wallet_type,
+            );
 
-            // Store wallet
-            env.storage().persistent().set(&DataKey::Wallets(request.owner.clone()), &wallet);
+            // Fund wallet
+            if let Some(funding_token) = request.funding_token.clone() {
+                if request.initial_funding > 0 {
+                    let recipient = wallet.contract.clone().unwrap_or(request.owner.clone());
+                    token::Client::new(&env, &funding_token).transfer(
+                        &caller,
+                        &recipient,
+                        &request.initial_funding,
+                    );
+                    let balance = balances.get(funding_token.clone()).unwrap_or(0);
+                    balances.set(funding_token.clone(), balance - request.initial_funding);
+                    WalletEvents::wallet_funded(
+                        &env,
+                        &request.owner,
+                        &funding_token,
+                        request.initial_funding,
+                    );
+                }
+            }
+
+            // Queue the owner's budget
+            if request.budget > 0 {
+                budgets.push_back(BudgetRequest {
+                    user: request.owner.clone(),
+                    amount: request.budget,
+                });
+            }
 
             // Increment ID
             next_wallet_id += 1;
 
             // Record success
-            results.push_back(WalletCreateResult::Success(request.owner.clone()));
-            successful_count += 1;
-
             WalletEvents::wallet_created(
                 &env,
                 batch_id,
                 &request.owner,
                 wallet.id,
             );
+
+            results.push_back(WalletCreateResult::Success(wallet));
+            successful_count += 1;
+        }
+
+        // Allocate the queued budgets; a failure reverts the whole batch
+        if let Some(budget_contract) = budget_contract {
+            if !budgets.is_empty() {
+                BudgetClient::new(&env, &budget_contract)
+                    .batch_allocate_budget(&env.current_contract_address(), &budgets);
+            }
         }
 
         // Update storage
@@ -168,6 +311,10 @@ impl BatchWalletContract {
         env.storage()
             .instance()
             .set(&DataKey::TotalWalletsCreated, &(total_created + successful_count as u64));
+        let live: u64 = env.storage().instance().get(&DataKey::LiveWallets).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::LiveWallets, &(live + successful_count as u64));
 
         // Emit batch completed event
         WalletEvents::batch_completed(
@@ -185,12 +332,226 @@ impl BatchWalletContract {
         }
     }
 
+    /// Issues invite codes that end users redeem with
+    /// `create_wallet_with_invite`, one wallet per code.
+    ///
+    /// `code_hashes` are SHA-256 hashes of each code followed by the XDR of
+    /// the invited owner's address, so the codes never appear on chain before
+    /// use and a code seen in the mempool cannot be redeemed by anyone else.
+    /// The number of codes is the signup quota; unused codes stop working at
+    /// `expiry`.
+    pub fn create_invites(env: Env, admin: Address, code_hashes: Vec<BytesN<32>>, expiry: u64) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if code_hashes.is_empty() {
+            panic_with_error!(&env, BatchWalletError::EmptyBatch);
+        }
+        if code_hashes.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchWalletError::BatchTooLarge);
+        }
+        if expiry <= env.ledger().timestamp() {
+            panic_with_error!(&env, BatchWalletError::InvalidInvite);
+        }
+
+        for code_hash in code_hashes.iter() {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Invite(code_hash), &expiry);
+        }
+
+        WalletEvents::invites_created(&env, code_hashes.len(), expiry);
+    }
+
+    /// Creates a personal wallet for `owner` by redeeming an invite code
+    /// issued to that owner.
+    pub fn create_wallet_with_invite(env: Env, owner: Address, code_preimage: Bytes) -> Wallet {
+        owner.require_auth();
+
+        // Bind the code to its owner so a front-runner cannot claim it
+        let mut preimage = code_preimage;
+        preimage.append(&owner.clone().to_xdr(&env));
+        let code_hash: BytesN<32> = env.crypto().sha256(&preimage).into();
+        let key = DataKey::Invite(code_hash.clone());
+        let expiry: u64 = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::InvalidInvite));
+        if env.ledger().timestamp() >= expiry {
+            panic_with_error!(&env, BatchWalletError::InviteExpired);
+        }
+
+        let wallet_type = symbol_short!("personal");
+        if owner_has_wallet_type(&env, &owner, &wallet_type)
+            || owner_wallet_indexes(&env, &owner).len() >= MAX_WALLETS_PER_OWNER
+        {
+            panic_with_error!(&env, BatchWalletError::WalletAlreadyExists);
+        }
+        env.storage().persistent().remove(&key);
+
+        let total_created: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalWalletsCreated)
+            .unwrap_or(0);
+        let wasm_hash: Option<BytesN<32>> = env.storage().instance().get(&DataKey::WalletWasmHash);
+        let wallet = Self::new_wallet(
+            &env,
+            &wasm_hash,
+            total_created + 1,
+            &owner,
+            &String::from_str(&env, ""),
+            &wallet_type,
+        );
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalWalletsCreated, &(total_created + 1));
+        let live: u64 = env.storage().instance().get(&DataKey::LiveWallets).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::LiveWallets, &(live + 1));
+
+        WalletEvents::invite_redeemed(&env, &code_hash, &owner, wallet.id);
+        wallet
+    }
+
+    /// Returns the expiry of an unused invite, if the code hash is live.
+    pub fn get_invite_expiry(env: Env, code_hash: BytesN<32>) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::Invite(code_hash))
+    }
+
+    /// Changes the tier, status or label of many wallets, recording a result
+    /// per request like `batch_create_wallets`.
+    ///
+    /// Failures report a `BatchWalletError` code: `WalletNotFound`,
+    /// `InvalidStatus` for a closed wallet or a request to close one (only the
+    /// owner can close a wallet, via `close_wallet`), `InvalidMetadata` for a
+    /// label that is too long, or `InvalidTier` for a tier with no limits
+    /// configured.
+    pub fn batch_update_wallets(
+        env: Env,
+        caller: Address,
+        requests: Vec<WalletUpdateRequest>,
+    ) -> BatchUpdateResult {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+
+        let request_count = requests.len();
+        if request_count == 0 {
+            panic_with_error!(&env, BatchWalletError::EmptyBatch);
+        }
+        if request_count > MAX_BATCH_SIZE {
+            panic_with_error!(&env, BatchWalletError::BatchTooLarge);
+        }
+
+        let batch_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalBatches)
+            .unwrap_or(0)
+            + 1;
+        WalletEvents::batch_started(&env, batch_id, request_count);
+
+        let mut results: Vec<WalletUpdateResult> = Vec::new(&env);
+        let mut successful_count: u32 = 0;
+        let mut failed_count: u32 = 0;
+
+        for request in requests.iter() {
+            let wallet: Option<Wallet> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Wallets(request.owner.clone(), request.wallet_index));
+
+            let error = match &wallet {
+                None => Some(BatchWalletError::WalletNotFound),
+                Some(wallet) if wallet.status == WalletStatus::Closed => {
+                    Some(BatchWalletError::InvalidStatus)
+                }
+                Some(_) if request.status == StatusUpdate::Set(WalletStatus::Closed) => {
+                    Some(BatchWalletError::InvalidStatus)
+                }
+                Some(wallet)
+                    if request.label.as_ref().is_some_and(|label| {
+                        validate_metadata(label, &wallet.wallet_type).is_err()
+                    }) =>
+                {
+                    Some(BatchWalletError::InvalidMetadata)
+                }
+                Some(_)
+                    if request.tier.is_some_and(|tier| {
+                        tier != 0 && !env.storage().instance().has(&DataKey::TierLimits(tier))
+                    }) =>
+                {
+                    Some(BatchWalletError::InvalidTier)
+                }
+                Some(_) => None,
+            };
+
+            if let Some(error) = error {
+                let code = error as u32;
+                results.push_back(WalletUpdateResult::Failure(
+                    request.owner.clone(),
+                    request.wallet_index,
+                    code,
+                ));
+                failed_count += 1;
+                WalletEvents::wallet_update_failure(
+                    &env,
+                    batch_id,
+                    &request.owner,
+                    request.wallet_index,
+                    code,
+                );
+                continue;
+            }
+
+            let mut wallet = wallet.unwrap();
+            if let Some(label) = request.label.clone() {
+                wallet.label = label;
+                WalletEvents::metadata_updated(
+                    &env,
+                    &request.owner,
+                    wallet.id,
+                    &wallet.wallet_type,
+                );
+            }
+            if let StatusUpdate::Set(status) = request.status {
+                if status != wallet.status {
+                    wallet.status = status;
+                    WalletEvents::status_changed(&env, &request.owner, wallet.id, status);
+                }
+            }
+            Self::store_wallet(&env, &wallet);
+            if let Some(tier) = request.tier {
+                Self::set_owner_tier(&env, &request.owner, tier);
+            }
+
+            results.push_back(WalletUpdateResult::Success(
+                request.owner.clone(),
+                request.wallet_index,
+            ));
+            successful_count += 1;
+        }
+
+        env.storage().instance().set(&DataKey::TotalBatches, &batch_id);
+        WalletEvents::batch_completed(&env, batch_id, successful_count, failed_count);
+
+        BatchUpdateResult {
+            total_requests: request_count,
+            successful: successful_count,
+            failed: failed_count,
+            results,
+        }
+    }
+
     /// Returns the admin address.
     pub fn get_admin(env: Env) -> Address {
         env.storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Contract not initialized")
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::NotInitialized))
     }
 
     /// Updates the admin address.
@@ -201,6 +562,52 @@ impl BatchWalletContract {
         env.storage().instance().set(&DataKey::Admin, &new_admin);
     }
 
+    /// Enables deploying an account contract for every new wallet.
+    ///
+    /// The contract is instantiated from `wasm_hash`, whose constructor must
+    /// take the owner address, and its address is stored on the `Wallet`.
+    pub fn set_wallet_wasm_hash(env: Env, admin: Address, wasm_hash: BytesN<32>) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().set(&DataKey::WalletWasmHash, &wasm_hash);
+    }
+
+    /// Returns to minting wallet IDs without deploying a contract.
+    pub fn clear_wallet_wasm_hash(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::WalletWasmHash);
+    }
+
+    /// Returns the account contract wasm hash, if deployment is enabled.
+    pub fn get_wallet_wasm_hash(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::WalletWasmHash)
+    }
+
+    /// Sets the budget-allocation contract used to provision budgets for new
+    /// wallets. This contract must be registered there with `add_allocator`.
+    pub fn set_budget_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().set(&DataKey::BudgetContract, &contract);
+    }
+
+    /// Stops provisioning budgets; requests with a budget then fail.
+    pub fn clear_budget_contract(env: Env, admin: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::BudgetContract);
+    }
+
+    /// Returns the configured budget-allocation contract, if any.
+    pub fn get_budget_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BudgetContract)
+    }
+
     /// Returns the total number of batches processed.
     pub fn get_total_batches(env: Env) -> u64 {
         env.storage()
@@ -217,9 +624,852 @@ impl BatchWalletContract {
             .unwrap_or(0)
     }
 
-    /// Returns wallet information for a given address.
-    pub fn get_wallet(env: Env, address: Address) -> Option<Wallet> {
-        env.storage().persistent().get(&DataKey::Wallets(address))
+    /// Returns the number of wallets currently stored, excluding deleted ones.
+    pub fn get_live_wallet_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::LiveWallets)
+            .unwrap_or(0)
+    }
+
+    /// Returns the owner's wallet at `wallet_index`, if any.
+    pub fn get_wallet(env: Env, owner: Address, wallet_index: u32) -> Option<Wallet> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Wallets(owner, wallet_index))
+    }
+
+    /// Returns every wallet held by the owner, in index order.
+    pub fn get_owner_wallets(env: Env, owner: Address) -> Vec<Wallet> {
+        let mut wallets = Vec::new(&env);
+        for wallet_index in owner_wallet_indexes(&env, &owner).iter() {
+            wallets.push_back(Self::load_wallet(&env, &owner, wallet_index));
+        }
+        wallets
+    }
+
+    /// Lists wallets in creation order, skipping the first `offset`.
+    /// At most `MAX_PAGE_SIZE` wallets are returned.
+    pub fn list_wallets(env: Env, offset: u32, limit: u32) -> Vec<Wallet> {
+        if limit == 0 {
+            return Vec::new(&env);
+        }
+        let from_id = offset as u64 + 1;
+        let to_id = from_id + limit.min(MAX_PAGE_SIZE) as u64 - 1;
+        Self::wallets_in_range(&env, from_id, to_id)
+    }
+
+    /// Returns the wallets whose IDs fall in `from_id..=to_id`, capped at
+    /// `MAX_PAGE_SIZE` wallets from `from_id`.
+    pub fn get_wallets_created_between(env: Env, from_id: u64, to_id: u64) -> Vec<Wallet> {
+        let from_id = from_id.max(1);
+        if to_id < from_id {
+            return Vec::new(&env);
+        }
+        let to_id = to_id.min(from_id + MAX_PAGE_SIZE as u64 - 1);
+        Self::wallets_in_range(&env, from_id, to_id)
+    }
+
+    /// Changes the label and type of one of the owner's wallets.
+    pub fn update_wallet_metadata(
+        env: Env,
+        owner: Address,
+        wallet_index: u32,
+        label: String,
+        wallet_type: Symbol,
+    ) {
+        owner.require_auth();
+
+        if validate_metadata(&label, &wallet_type).is_err() {
+            panic_with_error!(&env, BatchWalletError::InvalidMetadata);
+        }
+
+        let mut wallet = Self::load_open_wallet(&env, &owner, wallet_index);
+        if wallet.wallet_type != wallet_type && owner_has_wallet_type(&env, &owner, &wallet_type) {
+            panic_with_error!(&env, BatchWalletError::WalletAlreadyExists);
+        }
+        wallet.label = label;
+        wallet.wallet_type = wallet_type;
+        Self::store_wallet(&env, &wallet);
+
+        WalletEvents::metadata_updated(&env, &owner, wallet.id, &wallet.wallet_type);
+    }
+
+    /// Returns the owner's status as seen by paying contracts: Frozen if any
+    /// wallet is frozen, Closed if every wallet is closed, Active otherwise.
+    /// None if the owner has no wallet.
+    pub fn get_wallet_status(env: Env, owner: Address) -> Option<WalletStatus> {
+        let mut status = None;
+        for wallet in Self::get_owner_wallets(env, owner).iter() {
+            match wallet.status {
+                WalletStatus::Frozen => return Some(WalletStatus::Frozen),
+                WalletStatus::Active => status = Some(WalletStatus::Active),
+                WalletStatus::Closed => {
+                    if status.is_none() {
+                        status = Some(WalletStatus::Closed);
+                    }
+                }
+            }
+        }
+        status
+    }
+
+    /// Freezes an active wallet so sibling contracts stop paying out to its owner.
+    pub fn freeze_wallet(env: Env, admin: Address, owner: Address, wallet_index: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::set_status(
+            &env,
+            &owner,
+            wallet_index,
+            WalletStatus::Active,
+            WalletStatus::Frozen,
+        );
+    }
+
+    /// Returns a frozen wallet to active.
+    pub fn unfreeze_wallet(env: Env, admin: Address, owner: Address, wallet_index: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+        Self::set_status(
+            &env,
+            &owner,
+            wallet_index,
+            WalletStatus::Frozen,
+            WalletStatus::Active,
+        );
+    }
+
+    /// Permanently closes one of the owner's wallets. Frozen wallets cannot be
+    /// closed until the admin unfreezes them.
+    pub fn close_wallet(env: Env, owner: Address, wallet_index: u32) {
+        owner.require_auth();
+        Self::set_status(
+            &env,
+            &owner,
+            wallet_index,
+            WalletStatus::Active,
+            WalletStatus::Closed,
+        );
+    }
+
+    /// Registers an additional signer on one of the owner's wallets.
+    pub fn add_signer(env: Env, owner: Address, wallet_index: u32, signer: Address) {
+        owner.require_auth();
+
+        let mut wallet = Self::load_open_wallet(&env, &owner, wallet_index);
+        if wallet.signers.contains(&signer) || wallet.signers.len() >= MAX_SIGNERS {
+            panic_with_error!(&env, BatchWalletError::InvalidSigner);
+        }
+        wallet.signers.push_back(signer);
+        Self::store_signers(&env, &wallet);
+    }
+
+    /// Removes a signer, which must leave at least `threshold` signers.
+    pub fn remove_signer(env: Env, owner: Address, wallet_index: u32, signer: Address) {
+        owner.require_auth();
+
+        let mut wallet = Self::load_open_wallet(&env, &owner, wallet_index);
+        let index = wallet
+            .signers
+            .first_index_of(&signer)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::InvalidSigner));
+        if wallet.signers.len() <= wallet.threshold {
+            panic_with_error!(&env, BatchWalletError::InvalidThreshold);
+        }
+        wallet.signers.remove(index);
+        Self::store_signers(&env, &wallet);
+    }
+
+    /// Sets how many signers must approve an M-of-N action.
+    pub fn set_threshold(env: Env, owner: Address, wallet_index: u32, threshold: u32) {
+        owner.require_auth();
+
+        let mut wallet = Self::load_open_wallet(&env, &owner, wallet_index);
+        if threshold == 0 || threshold > wallet.signers.len() {
+            panic_with_error!(&env, BatchWalletError::InvalidThreshold);
+        }
+        wallet.threshold = threshold;
+        Self::store_signers(&env, &wallet);
+    }
+
+    /// Returns the signers and threshold of one of the owner's wallets, if any.
+    pub fn get_wallet_signers(
+        env: Env,
+        owner: Address,
+        wallet_index: u32,
+    ) -> Option<WalletSigners> {
+        Self::get_wallet(env, owner, wallet_index).map(|wallet| WalletSigners {
+            signers: wallet.signers,
+            threshold: wallet.threshold,
+        })
+    }
+
+    /// Configures the limits of a tier. Tier 0, the default, is unlimited
+    /// unless configured here.
+    pub fn set_tier_limits(env: Env, admin: Address, tier: u32, limits: TierLimits) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if limits.max_daily_spend < 0 || limits.max_balance < 0 {
+            panic_with_error!(&env, BatchWalletError::InvalidTier);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TierLimits(tier), &limits);
+    }
+
+    /// Returns the limits configured for a tier, if any.
+    pub fn get_tier_limits(env: Env, tier: u32) -> Option<TierLimits> {
+        env.storage().instance().get(&DataKey::TierLimits(tier))
+    }
+
+    /// Moves all of the owner's wallets to `tier`, which must have limits
+    /// configured unless it is the default tier 0.
+    pub fn upgrade_tier(env: Env, admin: Address, owner: Address, tier: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if tier != 0 && !env.storage().instance().has(&DataKey::TierLimits(tier)) {
+            panic_with_error!(&env, BatchWalletError::InvalidTier);
+        }
+        if !wallet_exists(&env, &owner) {
+            panic_with_error!(&env, BatchWalletError::WalletNotFound);
+        }
+        Self::set_owner_tier(&env, &owner, tier);
+    }
+
+    /// Returns the limits that apply to the owner, or None if the owner has
+    /// no wallet or their tier is unlimited.
+    pub fn get_owner_limits(env: Env, owner: Address) -> Option<TierLimits> {
+        let first = owner_wallet_indexes(&env, &owner).first()?;
+        let tier = Self::load_wallet(&env, &owner, first).tier;
+        Self::get_tier_limits(env, tier)
+    }
+
+    /// Returns whether the owner may spend `amount` today under their tier.
+    ///
+    /// Sibling contracts consult this before settling; it is false for
+    /// owners without an active wallet.
+    pub fn check_limit(env: Env, owner: Address, amount: i128) -> bool {
+        if Self::get_wallet_status(env.clone(), owner.clone()) != Some(WalletStatus::Active) {
+            return false;
+        }
+        amount <= Self::get_remaining_daily_limit(env, owner)
+    }
+
+    /// Returns whether a wallet of the owner's may hold `balance` under their
+    /// tier's `max_balance`; owners without a wallet fall under tier 0.
+    ///
+    /// Sibling contracts consult this alongside `check_limit` before paying
+    /// into a wallet.
+    pub fn check_balance_limit(env: Env, owner: Address, balance: i128) -> bool {
+        Self::get_owner_limits(env.clone(), owner)
+            .or_else(|| Self::get_tier_limits(env, 0))
+            .is_none_or(|limits| balance <= limits.max_balance)
+    }
+
+    /// Allows `contract` to report activity on wallets, such as spends.
+    pub fn add_trusted_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::TrustedContract(contract), &true);
+    }
+
+    /// Revokes a contract's permission to report wallet activity.
+    pub fn remove_trusted_contract(env: Env, admin: Address, contract: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TrustedContract(contract));
+    }
+
+    /// Returns whether `contract` may report wallet activity.
+    pub fn is_trusted_contract(env: Env, contract: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::TrustedContract(contract))
+    }
+
+    /// Records a settled spend by `owner`, reported by a trusted contract such
+    /// as batch-transfer or batch-payment.
+    ///
+    /// Panics with `DailyLimitExceeded` if the spend would take the owner past
+    /// their tier's daily limit, reverting the caller's settlement.
+    pub fn record_spend(env: Env, caller_contract: Address, owner: Address, amount: i128) {
+        caller_contract.require_auth();
+        Self::require_trusted(&env, &caller_contract);
+
+        if amount <= 0 {
+            panic_with_error!(&env, BatchWalletError::InvalidAmount);
+        }
+        if Self::get_wallet_status(env.clone(), owner.clone()) != Some(WalletStatus::Active) {
+            panic_with_error!(&env, BatchWalletError::InvalidStatus);
+        }
+        if amount > Self::get_remaining_daily_limit(env.clone(), owner.clone()) {
+            panic_with_error!(&env, BatchWalletError::DailyLimitExceeded);
+        }
+
+        let spent = Self::spent_today(&env, &owner)
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::InvalidAmount));
+        let spend = DailySpend {
+            day: env.ledger().timestamp() / SECONDS_PER_DAY,
+            spent,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::DailySpend(owner.clone()), &spend);
+
+        WalletEvents::spend_recorded(&env, &caller_contract, &owner, amount);
+    }
+
+    /// Records activity on each of the owner's open wallets, reported by a
+    /// trusted contract. Every wallet keeps its own last-active timestamp and
+    /// running count so dormant wallets can be found on chain.
+    pub fn bump_activity(env: Env, caller_contract: Address, owner: Address) {
+        caller_contract.require_auth();
+        Self::require_trusted(&env, &caller_contract);
+
+        let wallets = Self::get_owner_wallets(env.clone(), owner.clone());
+        if wallets.is_empty() {
+            panic_with_error!(&env, BatchWalletError::WalletNotFound);
+        }
+
+        let mut bumped = 0u32;
+        for wallet in wallets.iter() {
+            if wallet.status == WalletStatus::Closed {
+                continue;
+            }
+            let key = DataKey::Activity(wallet.id);
+            let count = env
+                .storage()
+                .persistent()
+                .get::<_, WalletActivity>(&key)
+                .map_or(0, |activity| activity.count)
+                + 1;
+            let activity = WalletActivity {
+                last_active: env.ledger().timestamp(),
+                count,
+            };
+            env.storage().persistent().set(&key, &activity);
+            WalletEvents::activity_bumped(&env, &caller_contract, &owner, wallet.id, count);
+            bumped += 1;
+        }
+        if bumped == 0 {
+            panic_with_error!(&env, BatchWalletError::InvalidStatus);
+        }
+    }
+
+    /// Returns the activity reported for one of the owner's wallets, if any.
+    pub fn get_activity(env: Env, owner: Address, wallet_index: u32) -> Option<WalletActivity> {
+        let wallet: Wallet = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Wallets(owner, wallet_index))?;
+        env.storage()
+            .persistent()
+            .get(&DataKey::Activity(wallet.id))
+    }
+
+    /// Returns how much the owner can still spend today, or `i128::MAX` if
+    /// their tier has no daily limit.
+    pub fn get_remaining_daily_limit(env: Env, owner: Address) -> i128 {
+        let spent = Self::spent_today(&env, &owner);
+        match Self::get_owner_limits(env, owner) {
+            Some(limits) => (limits.max_daily_spend - spent).max(0),
+            None => i128::MAX,
+        }
+    }
+
+    /// Moves one of the caller's active wallets to `new_owner`, who must also
+    /// authorize the transfer, cancelling any recovery pending against the
+    /// caller. The signer set is reset to the new owner, and the wallet takes
+    /// the new owner's next free index. Moving the caller's last wallet hands
+    /// their guardians over as `execute_recovery` does.
+    pub fn transfer_wallet(
+        env: Env,
+        current_owner: Address,
+        wallet_index: u32,
+        new_owner: Address,
+    ) {
+        current_owner.require_auth();
+        new_owner.require_auth();
+
+        let wallet = Self::load_wallet(&env, &current_owner, wallet_index);
+        if wallet.status != WalletStatus::Active {
+            panic_with_error!(&env, BatchWalletError::InvalidStatus);
+        }
+        let key = DataKey::PendingRecovery(current_owner.clone());
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().remove(&key);
+            WalletEvents::recovery_cancelled(&env, &current_owner);
+        }
+        Self::move_wallet(&env, wallet, &new_owner);
+        if !wallet_exists(&env, &current_owner) {
+            Self::move_guardians(&env, &current_owner, &new_owner);
+        }
+    }
+
+    /// Deletes a closed wallet, reclaiming its storage.
+    ///
+    /// Only closed wallets can be deleted: closing is the owner's confirmation
+    /// that the wallet is emptied, since this contract holds no balances. A
+    /// pending recovery against the owner blocks deletion. The wallet ID is
+    /// not reused.
+    pub fn delete_wallet(env: Env, admin: Address, owner: Address, wallet_index: u32) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        let wallet = Self::load_wallet(&env, &owner, wallet_index);
+        if wallet.status != WalletStatus::Closed
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::PendingRecovery(owner.clone()))
+        {
+            panic_with_error!(&env, BatchWalletError::InvalidStatus);
+        }
+
+        Self::remove_wallet(&env, &wallet);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::WalletIndex(wallet.id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Activity(wallet.id));
+        if !wallet_exists(&env, &owner) {
+            let storage = env.storage().persistent();
+            storage.remove(&DataKey::Guardians(owner.clone()));
+            storage.remove(&DataKey::DailySpend(owner.clone()));
+        }
+        let live: u64 = env.storage().instance().get(&DataKey::LiveWallets).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::LiveWallets, &live.saturating_sub(1));
+
+        WalletEvents::wallet_deleted(&env, &owner, wallet.id);
+    }
+
+    /// Starts moving a lost owner's wallets to `new_owner`, who must not hold
+    /// any wallet yet.
+    ///
+    /// The move only takes effect through `execute_recovery` once
+    /// `RECOVERY_TIMELOCK_SECS` have passed; until then the original owner
+    /// can call `cancel_recovery`. Starting again replaces the pending one.
+    pub fn recover_wallet(env: Env, admin: Address, old_owner: Address, new_owner: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        if !wallet_exists(&env, &old_owner) {
+            panic_with_error!(&env, BatchWalletError::WalletNotFound);
+        }
+        if wallet_exists(&env, &new_owner) {
+            panic_with_error!(&env, BatchWalletError::WalletAlreadyExists);
+        }
+
+        let recovery = PendingRecovery {
+            new_owner: new_owner.clone(),
+            executable_at: env.ledger().timestamp() + RECOVERY_TIMELOCK_SECS,
+            approvals: Vec::new(&env),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingRecovery(old_owner.clone()), &recovery);
+
+        WalletEvents::recovery_started(&env, &old_owner, &new_owner, recovery.executable_at);
+    }
+
+    /// Sets the guardians who can jointly recover the owner's wallets.
+    ///
+    /// An empty list with a threshold of 0 removes guardian recovery.
+    pub fn set_guardians(env: Env, owner: Address, guardians: Vec<Address>, threshold: u32) {
+        owner.require_auth();
+
+        if !wallet_exists(&env, &owner) {
+            panic_with_error!(&env, BatchWalletError::WalletNotFound);
+        }
+        if guardians.len() > MAX_GUARDIANS || guardians.contains(&owner) {
+            panic_with_error!(&env, BatchWalletError::InvalidGuardians);
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardians.first_index_of(&guardian) != Some(i as u32) {
+                panic_with_error!(&env, BatchWalletError::InvalidGuardians);
+            }
+        }
+        if threshold > guardians.len() || (threshold == 0 && !guardians.is_empty()) {
+            panic_with_error!(&env, BatchWalletError::InvalidThreshold);
+        }
+
+        let key = DataKey::Guardians(owner.clone());
+        if guardians.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            let config = GuardianConfig {
+                guardians: guardians.clone(),
+                threshold,
+            };
+            env.storage().persistent().set(&key, &config);
+        }
+
+        WalletEvents::guardians_set(&env, &owner, guardians.len(), threshold);
+    }
+
+    /// Returns the owner's guardians, if any.
+    pub fn get_guardians(env: Env, owner: Address) -> Option<GuardianConfig> {
+        env.storage().persistent().get(&DataKey::Guardians(owner))
+    }
+
+    /// Starts a guardian recovery of the owner's wallets to `new_owner`,
+    /// counting as the initiating guardian's approval.
+    ///
+    /// It can be executed once `threshold` guardians approved and
+    /// `RECOVERY_TIMELOCK_SECS` have passed; the owner can cancel it until then.
+    pub fn initiate_recovery(env: Env, guardian: Address, owner: Address, new_owner: Address) {
+        guardian.require_auth();
+        Self::require_guardian(&env, &owner, &guardian);
+
+        if !wallet_exists(&env, &owner) {
+            panic_with_error!(&env, BatchWalletError::WalletNotFound);
+        }
+        if wallet_exists(&env, &new_owner) {
+            panic_with_error!(&env, BatchWalletError::WalletAlreadyExists);
+        }
+        let key = DataKey::PendingRecovery(owner.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, BatchWalletError::RecoveryPending);
+        }
+
+        let recovery = PendingRecovery {
+            new_owner: new_owner.clone(),
+            executable_at: env.ledger().timestamp() + RECOVERY_TIMELOCK_SECS,
+            approvals: Vec::from_array(&env, [guardian.clone()]),
+        };
+        env.storage().persistent().set(&key, &recovery);
+
+        WalletEvents::recovery_started(&env, &owner, &new_owner, recovery.executable_at);
+        WalletEvents::recovery_approved(&env, &owner, &guardian, 1);
+    }
+
+    /// Adds a guardian's approval to the owner's pending guardian recovery.
+    pub fn approve_recovery(env: Env, guardian: Address, owner: Address) {
+        guardian.require_auth();
+        Self::require_guardian(&env, &owner, &guardian);
+
+        let key = DataKey::PendingRecovery(owner.clone());
+        let mut recovery: PendingRecovery = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::RecoveryNotFound));
+        if recovery.approvals.is_empty() {
+            // Admin-initiated recoveries take no guardian approvals
+            panic_with_error!(&env, BatchWalletError::RecoveryPending);
+        }
+        if recovery.approvals.contains(&guardian) {
+            return;
+        }
+        recovery.approvals.push_back(guardian.clone());
+        env.storage().persistent().set(&key, &recovery);
+
+        WalletEvents::recovery_approved(&env, &owner, &guardian, recovery.approvals.len());
+    }
+
+    /// Completes a recovery whose timelock has elapsed, moving every wallet
+    /// that is not closed along with the owner's guardians. Guardian
+    /// recoveries also need `threshold` approvals. Callable by anyone.
+    pub fn execute_recovery(env: Env, old_owner: Address) {
+        let key = DataKey::PendingRecovery(old_owner.clone());
+        let recovery: PendingRecovery = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(&env, BatchWalletError::RecoveryNotFound));
+        if env.ledger().timestamp() < recovery.executable_at {
+            panic_with_error!(&env, BatchWalletError::RecoveryLocked);
+        }
+        let guardians: Option<GuardianConfig> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Guardians(old_owner.clone()));
+        if !recovery.approvals.is_empty() {
+            let approved = guardians
+                .as_ref()
+                .is_some_and(|config| recovery.approvals.len() >= config.threshold);
+            if !approved {
+                panic_with_error!(&env, BatchWalletError::RecoveryLocked);
+            }
+        }
+
+        let mut moved = 0u32;
+        for wallet in Self::get_owner_wallets(env.clone(), old_owner.clone()).iter() {
+            if wallet.status != WalletStatus::Closed {
+                Self::move_wallet(&env, wallet, &recovery.new_owner);
+                moved += 1;
+            }
+        }
+        if moved == 0 {
+            panic_with_error!(&env, BatchWalletError::InvalidStatus);
+        }
+        env.storage().persistent().remove(&key);
+        Self::move_guardians(&env, &old_owner, &recovery.new_owner);
+    }
+
+    /// Cancels a pending recovery. Callable by the original owner or the admin.
+    pub fn cancel_recovery(env: Env, caller: Address, old_owner: Address) {
+        caller.require_auth();
+        if caller != old_owner {
+            Self::require_admin(&env, &caller);
+        }
+
+        let key = DataKey::PendingRecovery(old_owner.clone());
+        if !env.storage().persistent().has(&key) {
+            panic_with_error!(&env, BatchWalletError::RecoveryNotFound);
+        }
+        env.storage().persistent().remove(&key);
+
+        WalletEvents::recovery_cancelled(&env, &old_owner);
+    }
+
+    /// Returns the recovery pending against the owner's wallets, if any.
+    pub fn get_pending_recovery(env: Env, old_owner: Address) -> Option<PendingRecovery> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingRecovery(old_owner))
+    }
+
+    // Internal helper loading a wallet that has not been closed
+    fn load_open_wallet(env: &Env, owner: &Address, wallet_index: u32) -> Wallet {
+        let wallet = Self::load_wallet(env, owner, wallet_index);
+        if wallet.status == WalletStatus::Closed {
+            panic_with_error!(env, BatchWalletError::InvalidStatus);
+        }
+        wallet
+    }
+
+    // Internal helper creating and storing a wallet, deploying its account
+    // contract salted by wallet ID when a wasm hash is configured
+    fn new_wallet(
+        env: &Env,
+        wasm_hash: &Option<BytesN<32>>,
+        id: u64,
+        owner: &Address,
+        label: &String,
+        wallet_type: &Symbol,
+    ) -> Wallet {
+        let contract = wasm_hash.as_ref().map(|hash| {
+            let mut salt = [0u8; 32];
+            salt[24..].copy_from_slice(&id.to_be_bytes());
+            let address = env
+                .deployer()
+                .with_current_contract(BytesN::from_array(env, &salt))
+                .deploy_v2(hash.clone(), (owner.clone(),));
+            WalletEvents::contract_deployed(env, owner, &address);
+            address
+        });
+
+        let mut wallet = Wallet {
+            id,
+            owner: owner.clone(),
+            index: 0,
+            created_at: env.ledger().timestamp(),
+            label: label.clone(),
+            wallet_type: wallet_type.clone(),
+            status: WalletStatus::Active,
+            signers: Vec::from_array(env, [owner.clone()]),
+            threshold: 1,
+            contract,
+            tier: 0,
+        };
+        Self::add_wallet(env, &mut wallet, owner);
+        wallet
+    }
+
+    // Internal helper moving all of the owner's wallets to `tier`
+    fn set_owner_tier(env: &Env, owner: &Address, tier: u32) {
+        for wallet_index in owner_wallet_indexes(env, owner).iter() {
+            let mut wallet = Self::load_wallet(env, owner, wallet_index);
+            wallet.tier = tier;
+            Self::store_wallet(env, &wallet);
+        }
+
+        WalletEvents::tier_changed(env, owner, tier);
+    }
+
+    // Internal helper persisting a changed signer set
+    fn store_signers(env: &Env, wallet: &Wallet) {
+        Self::store_wallet(env, wallet);
+
+        WalletEvents::signers_updated(
+            env,
+            &wallet.owner,
+            wallet.id,
+            wallet.signers.len(),
+            wallet.threshold,
+        );
+    }
+
+    // Internal helper appending a wallet to `owner`'s collection at the next
+    // free index and pointing the ID index at it. The wallet joins the tier of
+    // the owner's existing wallets.
+    fn add_wallet(env: &Env, wallet: &mut Wallet, owner: &Address) {
+        let mut indexes = owner_wallet_indexes(env, owner);
+        if let Some(first) = indexes.first() {
+            wallet.tier = Self::load_wallet(env, owner, first).tier;
+        }
+        wallet.owner = owner.clone();
+        wallet.index = indexes.last().map(|last| last + 1).unwrap_or(0);
+        indexes.push_back(wallet.index);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::OwnerWallets(owner.clone()), &indexes);
+        env.storage()
+            .persistent()
+            .set(&DataKey::WalletIndex(wallet.id), &(owner.clone(), wallet.index));
+        Self::store_wallet(env, wallet);
+    }
+
+    // Internal helper removing a wallet from its owner's collection
+    fn remove_wallet(env: &Env, wallet: &Wallet) {
+        let mut indexes = owner_wallet_indexes(env, &wallet.owner);
+        if let Some(position) = indexes.first_index_of(wallet.index) {
+            indexes.remove(position);
+        }
+        let key = DataKey::OwnerWallets(wallet.owner.clone());
+        if indexes.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &indexes);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Wallets(wallet.owner.clone(), wallet.index));
+    }
+
+    // Internal helper re-keying a wallet under its new owner
+    fn move_wallet(env: &Env, mut wallet: Wallet, new_owner: &Address) {
+        if owner_has_wallet_type(env, new_owner, &wallet.wallet_type)
+            || owner_wallet_indexes(env, new_owner).len() >= MAX_WALLETS_PER_OWNER
+        {
+            panic_with_error!(env, BatchWalletError::WalletAlreadyExists);
+        }
+        let old_owner = wallet.owner.clone();
+        Self::remove_wallet(env, &wallet);
+
+        wallet.signers = Vec::from_array(env, [new_owner.clone()]);
+        wallet.threshold = 1;
+        Self::add_wallet(env, &mut wallet, new_owner);
+
+        WalletEvents::wallet_transferred(env, &old_owner, new_owner, wallet.id);
+    }
+
+    // Internal helper handing an owner's guardians over to the owner their
+    // wallets moved to, unless the new owner already has guardians or is one
+    fn move_guardians(env: &Env, old_owner: &Address, new_owner: &Address) {
+        let storage = env.storage().persistent();
+        let old_key = DataKey::Guardians(old_owner.clone());
+        let Some(config) = storage.get::<_, GuardianConfig>(&old_key) else {
+            return;
+        };
+        storage.remove(&old_key);
+        let new_key = DataKey::Guardians(new_owner.clone());
+        if !storage.has(&new_key) && !config.guardians.contains(new_owner) {
+            storage.set(&new_key, &config);
+        }
+    }
+
+    // Internal helper collecting the indexed wallets with IDs in `from_id..=to_id`
+    fn wallets_in_range(env: &Env, from_id: u64, to_id: u64) -> Vec<Wallet> {
+        let total: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalWalletsCreated)
+            .unwrap_or(0);
+        let mut wallets = Vec::new(env);
+        for id in from_id..=to_id.min(total) {
+            let slot: Option<(Address, u32)> =
+                env.storage().persistent().get(&DataKey::WalletIndex(id));
+            if let Some(wallet) = slot.and_then(|(owner, wallet_index)| {
+                env.storage()
+                    .persistent()
+                    .get::<_, Wallet>(&DataKey::Wallets(owner, wallet_index))
+            }) {
+                wallets.push_back(wallet);
+            }
+        }
+        wallets
+    }
+
+    // Internal helper persisting a wallet under its (owner, index) key
+    fn store_wallet(env: &Env, wallet: &Wallet) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Wallets(wallet.owner.clone(), wallet.index), wallet);
+    }
+
+    // Internal helper loading a wallet or panicking if there is none
+    fn load_wallet(env: &Env, owner: &Address, wallet_index: u32) -> Wallet {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Wallets(owner.clone(), wallet_index))
+            .unwrap_or_else(|| panic_with_error!(env, BatchWalletError::WalletNotFound))
+    }
+
+    // Internal helper moving a wallet from `from` to `to`
+    fn set_status(
+        env: &Env,
+        owner: &Address,
+        wallet_index: u32,
+        from: WalletStatus,
+        to: WalletStatus,
+    ) {
+        let mut wallet = Self::load_wallet(env, owner, wallet_index);
+        if wallet.status != from {
+            panic_with_error!(env, BatchWalletError::InvalidStatus);
+        }
+        wallet.status = to;
+        Self::store_wallet(env, &wallet);
+
+        WalletEvents::status_changed(env, owner, wallet.id, to);
+    }
+
+    // Internal helper returning what the owner has spent on the current day
+    fn spent_today(env: &Env, owner: &Address) -> i128 {
+        let today = env.ledger().timestamp() / SECONDS_PER_DAY;
+        env.storage()
+            .persistent()
+            .get::<_, DailySpend>(&DataKey::DailySpend(owner.clone()))
+            .filter(|spend| spend.day == today)
+            .map(|spend| spend.spent)
+            .unwrap_or(0)
+    }
+
+    // Internal helper to verify `guardian` is one of the owner's guardians
+    fn require_guardian(env: &Env, owner: &Address, guardian: &Address) {
+        let config: Option<GuardianConfig> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Guardians(owner.clone()));
+        if !config.is_some_and(|config| config.guardians.contains(guardian)) {
+            panic_with_error!(env, BatchWalletError::NotGuardian);
+        }
+    }
+
+    // Internal helper to verify a contract may report wallet activity
+    fn require_trusted(env: &Env, contract: &Address) {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::TrustedContract(contract.clone()))
+        {
+            panic_with_error!(env, BatchWalletError::UntrustedContract);
+        }
     }
 
     // Internal helper to verify admin
@@ -228,7 +1478,7 @@ impl BatchWalletContract {
             .storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Contract not initialized");
+            .unwrap_or_else(|| panic_with_error!(env, BatchWalletError::NotInitialized));
 
         if *caller != admin {
             panic_with_error!(env, BatchWalletError::Unauthorized);
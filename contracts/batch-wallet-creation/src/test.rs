@@ -3,11 +3,17 @@
 #![cfg(test)]
 
 use crate::{
-    BatchWalletContract, BatchWalletContractClient, WalletCreateRequest, WalletCreateResult,
+    BatchBudgetResult, BatchWalletContract, BatchWalletContractClient, BatchWalletError,
+    BudgetRequest, DataKey, StatusUpdate, TierLimits, WalletCreateRequest,
+    WalletCreateResult, WalletStatus, WalletUpdateRequest, WalletUpdateResult, MAX_BATCH_SIZE,
+    RECOVERY_TIMELOCK_SECS, SECONDS_PER_DAY,
 };
 use soroban_sdk::{
+    contract, contractimpl, symbol_short,
     testutils::{Address as _, Events as _, Ledger},
-    Address, Env, Vec,
+    token,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, String, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -29,8 +35,15 @@ fn setup_test_env() -> (Env, Address, BatchWalletContractClient<'static>) {
 }
 
 /// Helper to create a wallet creation request.
-fn create_wallet_request(_env: &Env, owner: Address) -> WalletCreateRequest {
-    WalletCreateRequest { owner }
+fn create_wallet_request(env: &Env, owner: Address) -> WalletCreateRequest {
+    WalletCreateRequest {
+        owner,
+        label: String::from_str(env, "Main"),
+        wallet_type: symbol_short!("personal"),
+        initial_funding: 0,
+        funding_token: None,
+        budget: 0,
+    }
 }
 
 // Initialization Tests
@@ -45,14 +58,32 @@ fn test_initialize_contract() {
 }
 
 #[test]
-#[should_panic(expected = "Contract already initialized")]
+#[should_panic(expected = "Error(Contract, #23)")]
 fn test_cannot_initialize_twice() {
-    let (env, admin, client) = setup_test_env();
+    let (env, _admin, client) = setup_test_env();
 
     let new_admin = Address::generate(&env);
     client.initialize(&new_admin);
 }
 
+#[test]
+fn test_uninitialized_contract_reports_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(BatchWalletContract, ());
+    let client = BatchWalletContractClient::new(&env, &contract_id);
+
+    let caller = Address::generate(&env);
+    assert_eq!(
+        client.try_get_admin().err(),
+        Some(Ok(BatchWalletError::NotInitialized.into()))
+    );
+    assert_eq!(
+        client.try_set_admin(&caller, &caller).err(),
+        Some(Ok(BatchWalletError::NotInitialized.into()))
+    );
+}
+
 // Batch Wallet Creation Tests
 
 #[test]
@@ -71,9 +102,13 @@ fn test_batch_create_wallets_single() {
     assert_eq!(result.failed, 0);
     assert_eq!(result.results.len(), 1);
 
-    // Check wallet was created
-    let wallet = client.get_wallet(&owner).unwrap();
+    // Check wallet was created and returned in the result
+    let wallet = client.get_wallet(&owner, &0).unwrap();
     assert_eq!(wallet.owner, owner);
+    match result.results.get(0).unwrap() {
+        WalletCreateResult::Success(created) => assert_eq!(created, wallet),
+        _ => panic!("Expected success"),
+    }
     assert_eq!(wallet.id, 1);
 }
 
@@ -97,11 +132,11 @@ fn test_batch_create_wallets_multiple() {
     assert_eq!(result.failed, 0);
 
     // Check wallets were created with sequential IDs
-    let wallet1 = client.get_wallet(&owner1).unwrap();
+    let wallet1 = client.get_wallet(&owner1, &0).unwrap();
     assert_eq!(wallet1.id, 1);
-    let wallet2 = client.get_wallet(&owner2).unwrap();
+    let wallet2 = client.get_wallet(&owner2, &0).unwrap();
     assert_eq!(wallet2.id, 2);
-    let wallet3 = client.get_wallet(&owner3).unwrap();
+    let wallet3 = client.get_wallet(&owner3, &0).unwrap();
     assert_eq!(wallet3.id, 3);
 }
 
@@ -135,26 +170,26 @@ fn test_batch_create_wallets_partial_failures() {
     match result.results.get(0).unwrap() {
         WalletCreateResult::Failure(addr, error_code) => {
             assert_eq!(addr, owner1);
-            assert_eq!(error_code, 1); // Already exists
+            assert_eq!(error_code, BatchWalletError::WalletAlreadyExists as u32);
         }
         _ => panic!("Expected failure for duplicate"),
     }
     match result.results.get(1).unwrap() {
         WalletCreateResult::Failure(addr, error_code) => {
             assert_eq!(addr, owner2);
-            assert_eq!(error_code, 1); // Already exists
+            assert_eq!(error_code, BatchWalletError::WalletAlreadyExists as u32);
         }
         _ => panic!("Expected failure for duplicate"),
     }
     match result.results.get(2).unwrap() {
-        WalletCreateResult::Success(addr) => {
-            assert_eq!(addr, owner3);
+        WalletCreateResult::Success(wallet) => {
+            assert_eq!(wallet.owner, owner3);
         }
         _ => panic!("Expected success for new wallet"),
     }
 
     // Check wallet3 was created
-    let wallet3 = client.get_wallet(&owner3).unwrap();
+    let wallet3 = client.get_wallet(&owner3, &0).unwrap();
     assert_eq!(wallet3.id, 3); // IDs continue from previous batch
 }
 
@@ -202,19 +237,33 @@ fn test_batch_create_wallets_accumulates_stats() {
 }
 
 #[test]
-#[should_panic]
 fn test_batch_create_wallets_empty_batch() {
     let (env, admin, client) = setup_test_env();
 
     let requests: Vec<WalletCreateRequest> = Vec::new(&env);
-    client.batch_create_wallets(&admin, &requests);
+    let result = client.try_batch_create_wallets(&admin, &requests);
+    assert_eq!(result.err(), Some(Ok(BatchWalletError::EmptyBatch.into())));
 }
 
 #[test]
-#[should_panic]
-fn test_batch_create_wallets_unauthorized() {
+fn test_batch_create_wallets_too_large() {
     let (env, admin, client) = setup_test_env();
 
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    for _ in 0..=MAX_BATCH_SIZE {
+        requests.push_back(create_wallet_request(&env, Address::generate(&env)));
+    }
+
+    let result = client.try_batch_create_wallets(&admin, &requests);
+    assert_eq!(result.err(), Some(Ok(BatchWalletError::BatchTooLarge.into())));
+    assert_eq!(client.get_total_batches(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_batch_create_wallets_unauthorized() {
+    let (env, _admin, client) = setup_test_env();
+
     let unauthorized = Address::generate(&env);
     let owner = Address::generate(&env);
 
@@ -246,12 +295,914 @@ fn test_batch_create_wallets_large_batch() {
     assert_eq!(result.failed, 0);
 
     // Check some wallets
-    let wallet1 = client.get_wallet(&owners.get(0).unwrap()).unwrap();
+    let wallet1 = client.get_wallet(&owners.get(0).unwrap(), &0).unwrap();
     assert_eq!(wallet1.id, 1);
-    let wallet50 = client.get_wallet(&owners.get(49).unwrap()).unwrap();
+    let wallet50 = client.get_wallet(&owners.get(49).unwrap(), &0).unwrap();
     assert_eq!(wallet50.id, 50);
 }
 
+// Metadata Tests
+
+#[test]
+fn test_wallet_metadata_is_stored_and_updated() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(WalletCreateRequest {
+        owner: owner.clone(),
+        label: String::from_str(&env, "Rainy day"),
+        wallet_type: symbol_short!("savings"),
+        initial_funding: 0,
+        funding_token: None,
+        budget: 0,
+    });
+    client.batch_create_wallets(&admin, &requests);
+
+    let wallet = client.get_wallet(&owner, &0).unwrap();
+    assert_eq!(wallet.label, String::from_str(&env, "Rainy day"));
+    assert_eq!(wallet.wallet_type, symbol_short!("savings"));
+
+    client.update_wallet_metadata(
+        &owner,
+        &0,
+        &String::from_str(&env, "Shop"),
+        &symbol_short!("business"),
+    );
+    let wallet = client.get_wallet(&owner, &0).unwrap();
+    assert_eq!(wallet.label, String::from_str(&env, "Shop"));
+    assert_eq!(wallet.wallet_type, symbol_short!("business"));
+}
+
+#[test]
+fn test_batch_create_wallets_rejects_unknown_wallet_type() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(WalletCreateRequest {
+        owner: owner.clone(),
+        label: String::from_str(&env, "Main"),
+        wallet_type: symbol_short!("casino"),
+        initial_funding: 0,
+        funding_token: None,
+        budget: 0,
+    });
+
+    let result = client.batch_create_wallets(&admin, &requests);
+    assert_eq!(result.failed, 1);
+    match result.results.get(0).unwrap() {
+        WalletCreateResult::Failure(addr, code) => {
+            assert_eq!(addr, owner);
+            assert_eq!(code, BatchWalletError::InvalidMetadata as u32);
+        }
+        _ => panic!("Expected failure"),
+    }
+    assert!(client.get_wallet(&owner, &0).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_update_wallet_metadata_requires_wallet() {
+    let (env, _admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    client.update_wallet_metadata(
+        &owner,
+        &0,
+        &String::from_str(&env, "Main"),
+        &symbol_short!("personal"),
+    );
+}
+
+// Owner Collection Tests
+
+#[test]
+fn test_owner_can_hold_one_wallet_per_type() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut savings = create_wallet_request(&env, owner.clone());
+    savings.wallet_type = symbol_short!("savings");
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    requests.push_back(savings.clone());
+    requests.push_back(savings);
+
+    let result = client.batch_create_wallets(&admin, &requests);
+    assert_eq!(result.successful, 2);
+    match result.results.get(2).unwrap() {
+        WalletCreateResult::Failure(_, code) => {
+            assert_eq!(code, BatchWalletError::WalletAlreadyExists as u32)
+        }
+        _ => panic!("Expected failure for a second savings wallet"),
+    }
+
+    let wallets = client.get_owner_wallets(&owner);
+    assert_eq!(wallets.len(), 2);
+    assert_eq!(wallets.get(0).unwrap().index, 0);
+    assert_eq!(wallets.get(1).unwrap().index, 1);
+    assert_eq!(client.get_wallet(&owner, &1).unwrap().wallet_type, symbol_short!("savings"));
+
+    // Switching to a type the owner already holds is rejected
+    assert!(client
+        .try_update_wallet_metadata(
+            &owner,
+            &0,
+            &String::from_str(&env, "Main"),
+            &symbol_short!("savings"),
+        )
+        .is_err());
+
+    // Freezing any wallet freezes the owner for paying contracts
+    client.freeze_wallet(&admin, &owner, &1);
+    assert_eq!(client.get_wallet_status(&owner), Some(WalletStatus::Frozen));
+    client.unfreeze_wallet(&admin, &owner, &1);
+    client.close_wallet(&owner, &1);
+    assert_eq!(client.get_wallet_status(&owner), Some(WalletStatus::Active));
+}
+
+// Tier Tests
+
+#[test]
+fn test_tiers_and_check_limit() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    // Tier 0 is unlimited until configured
+    assert_eq!(client.get_wallet(&owner, &0).unwrap().tier, 0);
+    assert!(client.check_limit(&owner, &1_000_000));
+    assert!(client.try_upgrade_tier(&admin, &owner, &1).is_err());
+
+    let gold = TierLimits {
+        max_daily_spend: 5_000,
+        max_balance: 50_000,
+    };
+    client.set_tier_limits(&admin, &1, &gold);
+    client.upgrade_tier(&admin, &owner, &1);
+    assert_eq!(client.get_owner_limits(&owner), Some(gold));
+    assert!(client.check_limit(&owner, &5_000));
+    assert!(!client.check_limit(&owner, &5_001));
+    assert!(client.check_balance_limit(&owner, &50_000));
+    assert!(!client.check_balance_limit(&owner, &50_001));
+    assert!(client.check_balance_limit(&Address::generate(&env), &50_001));
+
+    // New wallets join the owner's tier
+    let mut savings = create_wallet_request(&env, owner.clone());
+    savings.wallet_type = symbol_short!("savings");
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(savings);
+    client.batch_create_wallets(&admin, &requests);
+    assert_eq!(client.get_wallet(&owner, &1).unwrap().tier, 1);
+
+    // Frozen owners and unknown addresses cannot spend
+    client.freeze_wallet(&admin, &owner, &0);
+    assert!(!client.check_limit(&owner, &1));
+    assert!(!client.check_limit(&Address::generate(&env), &1));
+}
+
+// Daily Spend Tests
+
+#[test]
+fn test_record_spend_tracks_daily_limit() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    client.set_tier_limits(
+        &admin,
+        &1,
+        &TierLimits {
+            max_daily_spend: 1_000,
+            max_balance: 10_000,
+        },
+    );
+    client.upgrade_tier(&admin, &owner, &1);
+
+    let payments = Address::generate(&env);
+    assert!(client.try_record_spend(&payments, &owner, &100).is_err());
+    client.add_trusted_contract(&admin, &payments);
+    assert!(client.is_trusted_contract(&payments));
+
+    client.record_spend(&payments, &owner, &600);
+    assert_eq!(client.get_remaining_daily_limit(&owner), 400);
+    assert!(client.check_limit(&owner, &400));
+    assert!(!client.check_limit(&owner, &401));
+    assert!(client.try_record_spend(&payments, &owner, &401).is_err());
+
+    // The limit resets the next day
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_DAY);
+    assert_eq!(client.get_remaining_daily_limit(&owner), 1_000);
+
+    client.remove_trusted_contract(&admin, &payments);
+    assert!(!client.is_trusted_contract(&payments));
+}
+
+#[test]
+fn test_record_spend_rejects_overflowing_total() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    let payments = Address::generate(&env);
+    client.add_trusted_contract(&admin, &payments);
+
+    // Tier 0 is unlimited, so only the running total can overflow
+    client.record_spend(&payments, &owner, &i128::MAX);
+    assert_eq!(
+        client.try_record_spend(&payments, &owner, &1).err(),
+        Some(Ok(BatchWalletError::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn test_bump_activity_from_trusted_contract() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    let mut savings = create_wallet_request(&env, owner.clone());
+    savings.wallet_type = symbol_short!("savings");
+    requests.push_back(savings);
+    client.batch_create_wallets(&admin, &requests);
+
+    let payments = Address::generate(&env);
+    assert!(client.try_bump_activity(&payments, &owner).is_err());
+    client.add_trusted_contract(&admin, &payments);
+    assert_eq!(client.get_activity(&owner, &0), None);
+
+    client.bump_activity(&payments, &owner);
+    client.close_wallet(&owner, &1);
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.bump_activity(&payments, &owner);
+
+    // Each wallet keeps its own counter, and closed wallets are skipped
+    let activity = client.get_activity(&owner, &0).unwrap();
+    assert_eq!(activity.count, 2);
+    assert_eq!(activity.last_active, env.ledger().timestamp());
+    let closed = client.get_activity(&owner, &1).unwrap();
+    assert_eq!(closed.count, 1);
+    assert_eq!(closed.last_active, env.ledger().timestamp() - 500);
+
+    // The counter follows the wallet to a new owner
+    let new_owner = Address::generate(&env);
+    client.transfer_wallet(&owner, &0, &new_owner);
+    assert_eq!(client.get_activity(&new_owner, &0).unwrap().count, 2);
+    assert_eq!(
+        client.try_bump_activity(&payments, &owner).err(),
+        Some(Ok(BatchWalletError::InvalidStatus.into()))
+    );
+
+    // Owners without wallets cannot be bumped
+    let stranger = Address::generate(&env);
+    assert!(client.try_bump_activity(&payments, &stranger).is_err());
+}
+
+// Batch Update Tests
+
+#[test]
+fn test_batch_update_wallets() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner1 = Address::generate(&env);
+    let owner2 = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner1.clone()));
+    requests.push_back(create_wallet_request(&env, owner2.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    client.set_tier_limits(
+        &admin,
+        &2,
+        &TierLimits {
+            max_daily_spend: 100,
+            max_balance: 1_000,
+        },
+    );
+    client.close_wallet(&owner2, &0);
+
+    let update = |owner: &Address, wallet_index: u32, tier: Option<u32>| WalletUpdateRequest {
+        owner: owner.clone(),
+        wallet_index,
+        tier,
+        status: StatusUpdate::Set(WalletStatus::Frozen),
+        label: Some(String::from_str(&env, "Renamed")),
+    };
+    let mut updates: Vec<WalletUpdateRequest> = Vec::new(&env);
+    updates.push_back(update(&owner1, 0, Some(2)));
+    updates.push_back(update(&owner1, 5, None));
+    updates.push_back(update(&owner2, 0, None));
+    updates.push_back(update(&owner1, 0, Some(9)));
+    // Only the owner can close a wallet
+    let mut close = update(&owner1, 0, None);
+    close.status = StatusUpdate::Set(WalletStatus::Closed);
+    updates.push_back(close);
+
+    let result = client.batch_update_wallets(&admin, &updates);
+    assert_eq!(result.total_requests, 5);
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 4);
+    let codes: [u32; 4] = core::array::from_fn(|i| match result.results.get(i as u32 + 1).unwrap() {
+        WalletUpdateResult::Failure(_, _, code) => code,
+        _ => panic!("Expected failure"),
+    });
+    assert_eq!(
+        codes,
+        [
+            BatchWalletError::WalletNotFound as u32,
+            BatchWalletError::InvalidStatus as u32,
+            BatchWalletError::InvalidTier as u32,
+            BatchWalletError::InvalidStatus as u32,
+        ]
+    );
+
+    let wallet = client.get_wallet(&owner1, &0).unwrap();
+    assert_eq!(wallet.status, WalletStatus::Frozen);
+    assert_eq!(wallet.label, String::from_str(&env, "Renamed"));
+    assert_eq!(wallet.tier, 2);
+    assert_eq!(client.get_total_batches(), 2);
+}
+
+// Listing Tests
+
+#[test]
+fn test_list_wallets_and_range_queries() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut owners: Vec<Address> = Vec::new(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    for _ in 0..5 {
+        let owner = Address::generate(&env);
+        owners.push_back(owner.clone());
+        requests.push_back(create_wallet_request(&env, owner));
+    }
+    client.batch_create_wallets(&admin, &requests);
+
+    let page = client.list_wallets(&1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, 2);
+    assert_eq!(page.get(1).unwrap().owner, owners.get(2).unwrap());
+    assert_eq!(client.list_wallets(&4, &10).len(), 1);
+    assert_eq!(client.list_wallets(&5, &10).len(), 0);
+    assert_eq!(client.list_wallets(&0, &0).len(), 0);
+
+    let range = client.get_wallets_created_between(&2, &4);
+    assert_eq!(range.len(), 3);
+    assert_eq!(range.get(2).unwrap().id, 4);
+    assert_eq!(client.get_wallets_created_between(&4, &2).len(), 0);
+
+    // The index follows ownership transfers
+    let new_owner = Address::generate(&env);
+    client.transfer_wallet(&owners.get(0).unwrap(), &0, &new_owner);
+    assert_eq!(client.list_wallets(&0, &1).get(0).unwrap().owner, new_owner);
+}
+
+// Funding Tests
+
+#[test]
+fn test_batch_create_wallets_with_initial_funding() {
+    let (env, admin, client) = setup_test_env();
+
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+    let token_id = token_contract.address();
+    let token_client = token::Client::new(&env, &token_id);
+    token::StellarAssetClient::new(&env, &token_id).mint(&admin, &1_000);
+
+    let funded = Address::generate(&env);
+    let unfunded = Address::generate(&env);
+    let too_much = Address::generate(&env);
+    let no_token = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    for (owner, amount, funding_token) in [
+        (funded.clone(), 600, Some(token_id.clone())),
+        (unfunded.clone(), 0, None),
+        (too_much.clone(), 600, Some(token_id.clone())),
+        (no_token.clone(), 100, None),
+    ] {
+        let mut request = create_wallet_request(&env, owner);
+        request.initial_funding = amount;
+        request.funding_token = funding_token;
+        requests.push_back(request);
+    }
+
+    let result = client.batch_create_wallets(&admin, &requests);
+    assert_eq!(result.successful, 2);
+    match result.results.get(2).unwrap() {
+        WalletCreateResult::Failure(_, code) => {
+            assert_eq!(code, BatchWalletError::InsufficientBalance as u32)
+        }
+        _ => panic!("Expected failure for insufficient balance"),
+    }
+    match result.results.get(3).unwrap() {
+        WalletCreateResult::Failure(_, code) => {
+            assert_eq!(code, BatchWalletError::InvalidAmount as u32)
+        }
+        _ => panic!("Expected failure for funding without a token"),
+    }
+    assert_eq!(token_client.balance(&funded), 600);
+    assert_eq!(token_client.balance(&admin), 400);
+    assert!(client.get_wallet(&too_much, &0).is_none());
+
+    // Funding cannot exceed the tier's maximum balance
+    client.set_tier_limits(
+        &admin,
+        &0,
+        &TierLimits {
+            max_daily_spend: 1_000,
+            max_balance: 300,
+        },
+    );
+    let mut request = create_wallet_request(&env, too_much.clone());
+    request.initial_funding = 301;
+    request.funding_token = Some(token_id);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(request);
+    let result = client.batch_create_wallets(&admin, &requests);
+    match result.results.get(0).unwrap() {
+        WalletCreateResult::Failure(_, code) => {
+            assert_eq!(code, BatchWalletError::BalanceLimitExceeded as u32)
+        }
+        _ => panic!("Expected failure for funding over the maximum balance"),
+    }
+    assert_eq!(token_client.balance(&admin), 400);
+}
+
+/// Minimal stand-in for the budget-allocation contract.
+#[contract]
+struct MockBudget;
+
+#[contractimpl]
+impl MockBudget {
+    pub fn batch_allocate_budget(
+        env: Env,
+        admin: Address,
+        requests: Vec<BudgetRequest>,
+    ) -> BatchBudgetResult {
+        admin.require_auth();
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("requests"), &requests);
+        BatchBudgetResult {
+            successful: requests.len(),
+            failed: 0,
+            total_amount: 0,
+        }
+    }
+
+    pub fn allocated(env: Env) -> Vec<BudgetRequest> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("requests"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_batch_create_wallets_provisions_budgets() {
+    let (env, admin, client) = setup_test_env();
+
+    let budgeted = Address::generate(&env);
+    let unbudgeted = Address::generate(&env);
+    let negative = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    let mut request = create_wallet_request(&env, budgeted.clone());
+    request.budget = 2_500;
+    requests.push_back(request);
+
+    // Without a budget contract a budget cannot be provisioned
+    let result = client.batch_create_wallets(&admin, &requests);
+    match result.results.get(0).unwrap() {
+        WalletCreateResult::Failure(_, code) => {
+            assert_eq!(code, BatchWalletError::InvalidBudget as u32)
+        }
+        _ => panic!("Expected failure without a budget contract"),
+    }
+
+    let budget_id = env.register(MockBudget, ());
+    let budget = MockBudgetClient::new(&env, &budget_id);
+    client.set_budget_contract(&admin, &budget_id);
+    assert_eq!(client.get_budget_contract(), Some(budget_id));
+
+    let mut request = create_wallet_request(&env, negative.clone());
+    request.budget = -1;
+    requests.push_back(create_wallet_request(&env, unbudgeted));
+    requests.push_back(request);
+    let result = client.batch_create_wallets(&admin, &requests);
+    assert_eq!(result.successful, 2);
+
+    let allocated = budget.allocated();
+    assert_eq!(allocated.len(), 1);
+    assert_eq!(
+        allocated.get(0).unwrap(),
+        BudgetRequest {
+            user: budgeted,
+            amount: 2_500,
+        }
+    );
+    assert!(client.get_wallet(&negative, &0).is_none());
+
+    client.clear_budget_contract(&admin);
+    assert_eq!(client.get_budget_contract(), None);
+}
+
+// Deployment Tests
+
+#[test]
+fn test_wallet_wasm_hash_config() {
+    let (env, admin, client) = setup_test_env();
+
+    assert_eq!(client.get_wallet_wasm_hash(), None);
+
+    // Without a wasm hash no contract is deployed
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    assert_eq!(client.get_wallet(&owner, &0).unwrap().contract, None);
+
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+    client.set_wallet_wasm_hash(&admin, &hash);
+    assert_eq!(client.get_wallet_wasm_hash(), Some(hash));
+
+    client.clear_wallet_wasm_hash(&admin);
+    assert_eq!(client.get_wallet_wasm_hash(), None);
+}
+
+// Status Tests
+
+#[test]
+fn test_freeze_unfreeze_and_close_wallet() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    assert_eq!(client.get_wallet_status(&owner), Some(WalletStatus::Active));
+
+    client.freeze_wallet(&admin, &owner, &0);
+    assert_eq!(client.get_wallet_status(&owner), Some(WalletStatus::Frozen));
+    assert!(client.try_close_wallet(&owner, &0).is_err());
+
+    client.unfreeze_wallet(&admin, &owner, &0);
+    client.close_wallet(&owner, &0);
+    assert_eq!(client.get_wallet_status(&owner), Some(WalletStatus::Closed));
+
+    // Closed is final
+    assert!(client.try_freeze_wallet(&admin, &owner, &0).is_err());
+    assert!(client.try_unfreeze_wallet(&admin, &owner, &0).is_err());
+    assert!(client
+        .try_update_wallet_metadata(
+            &owner,
+            &0,
+            &String::from_str(&env, "Old"),
+            &symbol_short!("personal"),
+        )
+        .is_err());
+
+    let stranger = Address::generate(&env);
+    assert_eq!(client.get_wallet_status(&stranger), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_freeze_wallet_requires_admin() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let stranger = Address::generate(&env);
+    client.freeze_wallet(&stranger, &owner, &0);
+}
+
+// Signer Tests
+
+#[test]
+fn test_wallet_signers_and_threshold() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let signers = client.get_wallet_signers(&owner, &0).unwrap();
+    assert_eq!(signers.signers, Vec::from_array(&env, [owner.clone()]));
+    assert_eq!(signers.threshold, 1);
+
+    let cosigner = Address::generate(&env);
+    let backup = Address::generate(&env);
+    client.add_signer(&owner, &0, &cosigner);
+    client.add_signer(&owner, &0, &backup);
+    assert!(client.try_add_signer(&owner, &0, &backup).is_err());
+    client.set_threshold(&owner, &0, &2);
+    assert!(client.try_set_threshold(&owner, &0, &4).is_err());
+
+    client.remove_signer(&owner, &0, &backup);
+    // Removing another would leave fewer signers than the threshold
+    assert!(client.try_remove_signer(&owner, &0, &cosigner).is_err());
+
+    let signers = client.get_wallet_signers(&owner, &0).unwrap();
+    assert_eq!(signers.signers, Vec::from_array(&env, [owner.clone(), cosigner]));
+    assert_eq!(signers.threshold, 2);
+
+    // A new owner starts with a fresh signer set
+    let new_owner = Address::generate(&env);
+    client.transfer_wallet(&owner, &0, &new_owner);
+    let signers = client.get_wallet_signers(&new_owner, &0).unwrap();
+    assert_eq!(signers.signers, Vec::from_array(&env, [new_owner]));
+    assert_eq!(signers.threshold, 1);
+}
+
+// Deletion Tests
+
+#[test]
+fn test_delete_closed_wallet() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    requests.push_back(create_wallet_request(&env, other.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    assert_eq!(client.get_live_wallet_count(), 2);
+
+    let payments = Address::generate(&env);
+    client.add_trusted_contract(&admin, &payments);
+    client.record_spend(&payments, &owner, &100);
+    client.bump_activity(&payments, &owner);
+    let mut guardians: Vec<Address> = Vec::new(&env);
+    guardians.push_back(Address::generate(&env));
+    client.set_guardians(&owner, &guardians, &1);
+
+    // Active wallets cannot be deleted
+    assert!(client.try_delete_wallet(&admin, &owner, &0).is_err());
+
+    let id = client.get_wallet(&owner, &0).unwrap().id;
+    client.close_wallet(&owner, &0);
+    client.delete_wallet(&admin, &owner, &0);
+
+    // Deleting the owner's last wallet drops their per-owner state
+    assert_eq!(client.get_guardians(&owner), None);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&DataKey::DailySpend(owner.clone())));
+        assert!(!storage.has(&DataKey::Activity(id)));
+    });
+
+    assert!(client.get_wallet(&owner, &0).is_none());
+    assert_eq!(client.get_owner_wallets(&owner).len(), 0);
+    assert_eq!(client.get_wallet_status(&owner), None);
+    assert_eq!(client.get_live_wallet_count(), 1);
+    assert_eq!(client.get_total_wallets_created(), 2);
+    let listed = client.list_wallets(&0, &10);
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed.get(0).unwrap().owner, other);
+}
+
+// Ownership Tests
+
+#[test]
+fn test_transfer_wallet_moves_ownership() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+    let id = client.get_wallet(&owner, &0).unwrap().id;
+    let mut guardians: Vec<Address> = Vec::new(&env);
+    guardians.push_back(Address::generate(&env));
+    client.set_guardians(&owner, &guardians, &1);
+
+    let new_owner = Address::generate(&env);
+    client.transfer_wallet(&owner, &0, &new_owner);
+
+    // Both sides of the transfer must authorize it
+    let auths = env.auths();
+    assert!(auths.iter().any(|(signer, _)| *signer == owner));
+    assert!(auths.iter().any(|(signer, _)| *signer == new_owner));
+
+    assert!(client.get_wallet(&owner, &0).is_none());
+    let wallet = client.get_wallet(&new_owner, &0).unwrap();
+    assert_eq!(wallet.id, id);
+    assert_eq!(wallet.owner, new_owner);
+
+    // The guardians follow the owner's last wallet
+    assert_eq!(client.get_guardians(&owner), None);
+    assert_eq!(client.get_guardians(&new_owner).unwrap().guardians, guardians);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_transfer_wallet_rejects_owner_with_wallet() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    requests.push_back(create_wallet_request(&env, other.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    client.transfer_wallet(&owner, &0, &other);
+}
+
+#[test]
+fn test_recover_wallet_after_timelock() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let new_owner = Address::generate(&env);
+    client.recover_wallet(&admin, &owner, &new_owner);
+    let recovery = client.get_pending_recovery(&owner).unwrap();
+    assert_eq!(recovery.new_owner, new_owner);
+
+    // Still locked
+    assert!(client.try_execute_recovery(&owner).is_err());
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += RECOVERY_TIMELOCK_SECS);
+    client.execute_recovery(&owner);
+
+    assert!(client.get_wallet(&owner, &0).is_none());
+    assert_eq!(client.get_wallet(&new_owner, &0).unwrap().owner, new_owner);
+    assert!(client.get_pending_recovery(&owner).is_none());
+}
+
+#[test]
+fn test_owner_can_cancel_recovery() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let attacker = Address::generate(&env);
+    client.recover_wallet(&admin, &owner, &attacker);
+    client.cancel_recovery(&owner, &owner);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += RECOVERY_TIMELOCK_SECS);
+    assert!(client.try_execute_recovery(&owner).is_err());
+    assert!(client.get_wallet(&owner, &0).is_some());
+    assert!(client.get_wallet(&attacker, &0).is_none());
+}
+
+// Guardian Recovery Tests
+
+#[test]
+fn test_guardian_recovery_needs_threshold_and_timelock() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let guardians = Vec::from_array(&env, [alice.clone(), bob.clone(), carol.clone()]);
+    client.set_guardians(&owner, &guardians, &2);
+    assert_eq!(client.get_guardians(&owner).unwrap().threshold, 2);
+
+    let new_owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    assert!(client
+        .try_initiate_recovery(&stranger, &owner, &new_owner)
+        .is_err());
+    client.initiate_recovery(&alice, &owner, &new_owner);
+
+    // One approval is not enough, even after the timelock
+    env.ledger()
+        .with_mut(|li| li.timestamp += RECOVERY_TIMELOCK_SECS);
+    assert!(client.try_execute_recovery(&owner).is_err());
+
+    client.approve_recovery(&bob, &owner);
+    assert_eq!(client.get_pending_recovery(&owner).unwrap().approvals.len(), 2);
+    client.execute_recovery(&owner);
+
+    assert!(client.get_wallet(&owner, &0).is_none());
+    assert_eq!(client.get_wallet(&new_owner, &0).unwrap().owner, new_owner);
+    assert_eq!(client.get_guardians(&new_owner).unwrap().guardians, guardians);
+    assert!(client.get_guardians(&owner).is_none());
+}
+
+#[test]
+fn test_owner_cancels_guardian_recovery() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let mut requests: Vec<WalletCreateRequest> = Vec::new(&env);
+    requests.push_back(create_wallet_request(&env, owner.clone()));
+    client.batch_create_wallets(&admin, &requests);
+
+    let alice = Address::generate(&env);
+    client.set_guardians(&owner, &Vec::from_array(&env, [alice.clone()]), &1);
+    // The owner cannot be their own guardian
+    assert!(client
+        .try_set_guardians(&owner, &Vec::from_array(&env, [owner.clone()]), &1)
+        .is_err());
+
+    client.initiate_recovery(&alice, &owner, &Address::generate(&env));
+    client.cancel_recovery(&owner, &owner);
+    assert!(client.get_pending_recovery(&owner).is_none());
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += RECOVERY_TIMELOCK_SECS);
+    assert!(client.try_execute_recovery(&owner).is_err());
+    assert!(client.get_wallet(&owner, &0).is_some());
+}
+
+// Invite Tests
+
+/// Hashes an invite code together with the owner it is issued to.
+fn invite_hash(env: &Env, code: &Bytes, owner: &Address) -> BytesN<32> {
+    let mut preimage = code.clone();
+    preimage.append(&owner.clone().to_xdr(env));
+    env.crypto().sha256(&preimage).into()
+}
+
+#[test]
+fn test_invite_codes_create_wallets_once() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let code = Bytes::from_slice(&env, b"welcome-42");
+    let code_hash = invite_hash(&env, &code, &owner);
+    let expiry = env.ledger().timestamp() + 3_600;
+    client.create_invites(&admin, &Vec::from_array(&env, [code_hash.clone()]), &expiry);
+    assert_eq!(client.get_invite_expiry(&code_hash), Some(expiry));
+
+    let wrong = Bytes::from_slice(&env, b"welcome-43");
+    assert!(client.try_create_wallet_with_invite(&owner, &wrong).is_err());
+
+    let wallet = client.create_wallet_with_invite(&owner, &code);
+    assert_eq!(wallet.owner, owner);
+    assert_eq!(wallet.wallet_type, symbol_short!("personal"));
+    assert_eq!(client.get_wallet(&owner, &0), Some(wallet));
+    assert_eq!(client.get_total_wallets_created(), 1);
+
+    // Each code works once
+    assert_eq!(client.get_invite_expiry(&code_hash), None);
+    let other = Address::generate(&env);
+    assert!(client.try_create_wallet_with_invite(&other, &code).is_err());
+}
+
+#[test]
+fn test_invite_code_cannot_be_redeemed_by_another_address() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let code = Bytes::from_slice(&env, b"welcome-7");
+    let code_hash = invite_hash(&env, &code, &owner);
+    let expiry = env.ledger().timestamp() + 3_600;
+    client.create_invites(&admin, &Vec::from_array(&env, [code_hash.clone()]), &expiry);
+
+    // A front-runner replaying the revealed code gets nothing
+    let attacker = Address::generate(&env);
+    let result = client.try_create_wallet_with_invite(&attacker, &code);
+    assert_eq!(result.err(), Some(Ok(BatchWalletError::InvalidInvite.into())));
+    assert_eq!(client.get_invite_expiry(&code_hash), Some(expiry));
+
+    let wallet = client.create_wallet_with_invite(&owner, &code);
+    assert_eq!(wallet.owner, owner);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_expired_invite_is_rejected() {
+    let (env, admin, client) = setup_test_env();
+
+    let owner = Address::generate(&env);
+    let code = Bytes::from_slice(&env, b"late");
+    let code_hash = invite_hash(&env, &code, &owner);
+    let expiry = env.ledger().timestamp() + 60;
+    client.create_invites(&admin, &Vec::from_array(&env, [code_hash]), &expiry);
+
+    env.ledger().with_mut(|li| li.timestamp = expiry);
+    client.create_wallet_with_invite(&owner, &code);
+}
+
 // Admin Tests
 
 #[test]
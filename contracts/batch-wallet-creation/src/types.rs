@@ -1,17 +1,87 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
 
 pub const MAX_BATCH_SIZE: u32 = 100;
 
+/// Maximum length in bytes of a wallet label.
+pub const MAX_LABEL_LEN: u32 = 64;
+
+/// Delay in seconds before an admin-initiated recovery can be executed,
+/// giving the original owner time to cancel it.
+pub const RECOVERY_TIMELOCK_SECS: u64 = 172_800;
+
+/// Maximum number of signers on a wallet, including the owner.
+pub const MAX_SIGNERS: u32 = 10;
+
+/// Maximum number of wallets returned by a listing call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of wallets a single owner can hold.
+pub const MAX_WALLETS_PER_OWNER: u32 = 10;
+
+/// Length of a spend-tracking day in seconds.
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Maximum number of recovery guardians per owner.
+pub const MAX_GUARDIANS: u32 = 10;
+
 #[derive(Clone, Debug)]
 #[contracttype]
 pub struct WalletCreateRequest {
     pub owner: Address,
+    /// Display name chosen by the owner, e.g. "Groceries"
+    pub label: String,
+    /// `personal`, `savings` or `business`
+    pub wallet_type: Symbol,
+    /// Amount of `funding_token` sent from the admin to the new wallet; 0 for none
+    pub initial_funding: i128,
+    pub funding_token: Option<Address>,
+    /// Monthly budget allocated through the budget-allocation contract; 0 for none
+    pub budget: i128,
+}
+
+/// Amount an owner spent on a given day.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DailySpend {
+    /// Day number, the ledger timestamp divided by `SECONDS_PER_DAY`
+    pub day: u64,
+    pub spent: i128,
+}
+
+/// Activity reported for a wallet by trusted contracts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WalletActivity {
+    /// Ledger timestamp of the latest reported activity
+    pub last_active: u64,
+    /// Number of activities reported
+    pub count: u64,
+}
+
+/// Mirror of the budget-allocation contract's `BudgetRequest`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BudgetRequest {
+    pub user: Address,
+    pub amount: i128,
+}
+
+/// Mirror of the budget-allocation contract's `BatchBudgetResult`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchBudgetResult {
+    pub successful: u32,
+    pub failed: u32,
+    pub total_amount: i128,
 }
 
 #[derive(Clone, Debug)]
 #[contracttype]
+#[allow(clippy::large_enum_variant)]
 pub enum WalletCreateResult {
-    Success(Address),
+    /// The wallet as stored, including its ID and deployed contract
+    Success(Wallet),
+    /// Owner and the `BatchWalletError` code the request failed with
     Failure(Address, u32),
 }
 
@@ -24,21 +94,141 @@ pub struct BatchCreateResult {
     pub results: Vec<WalletCreateResult>,
 }
 
+/// Settings to change on one wallet; None leaves a setting untouched.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct WalletUpdateRequest {
+    pub owner: Address,
+    pub wallet_index: u32,
+    /// New tier, applied to all of the owner's wallets
+    pub tier: Option<u32>,
+    pub status: StatusUpdate,
+    pub label: Option<String>,
+}
+
+/// Status change requested by a `WalletUpdateRequest`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum StatusUpdate {
+    Keep,
+    Set(WalletStatus),
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub enum WalletUpdateResult {
+    Success(Address, u32),
+    /// Owner, wallet index and the `BatchWalletError` code the request failed with
+    Failure(Address, u32, u32),
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct BatchUpdateResult {
+    pub total_requests: u32,
+    pub successful: u32,
+    pub failed: u32,
+    pub results: Vec<WalletUpdateResult>,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Admin,
     TotalBatches,
     TotalWalletsCreated,
-    Wallets(Address), // Map of address to wallet id or something
+    /// Number of wallets currently stored, net of deletions
+    LiveWallets,
+    /// Wallet held by an owner at a wallet index
+    Wallets(Address, u32),
+    /// Wallet indexes currently held by an owner, ascending
+    OwnerWallets(Address),
+    PendingRecovery(Address),
+    /// Wasm hash of the account contract deployed per wallet, if enabled
+    WalletWasmHash,
+    /// Current (owner, wallet index) of the wallet with the given ID
+    WalletIndex(u64),
+    /// Limits configured for a tier
+    TierLimits(u32),
+    /// Budget-allocation contract that provisions budgets for new wallets
+    BudgetContract,
+    /// Contract allowed to report activity on wallets, e.g. batch-transfer
+    TrustedContract(Address),
+    /// Amount an owner has spent on the current day
+    DailySpend(Address),
+    /// Guardians able to recover an owner's wallets
+    Guardians(Address),
+    /// Expiry timestamp of an unused invite, keyed by the SHA-256 of its code
+    Invite(BytesN<32>),
+    /// Last-active timestamp and activity count of a wallet, by wallet ID
+    Activity(u64),
 }
 
-#[derive(Clone, Debug)]
+/// Move of an owner's wallets to a new owner, awaiting its timelock.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingRecovery {
+    pub new_owner: Address,
+    pub executable_at: u64,
+    /// Guardians who approved; empty for admin-initiated recoveries
+    pub approvals: Vec<Address>,
+}
+
+/// Guardians an owner trusts to recover their wallets.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GuardianConfig {
+    pub guardians: Vec<Address>,
+    /// Number of guardian approvals a recovery needs
+    pub threshold: u32,
+}
+
+/// Lifecycle state of a wallet. Closed is final.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum WalletStatus {
+    Active,
+    Frozen,
+    Closed,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct Wallet {
     pub id: u64,
     pub owner: Address,
+    /// Position of the wallet in its owner's collection
+    pub index: u32,
     pub created_at: u64,
+    pub label: String,
+    pub wallet_type: Symbol,
+    pub status: WalletStatus,
+    /// Addresses allowed to co-sign high-value actions; starts as the owner
+    pub signers: Vec<Address>,
+    /// Number of signers required for an M-of-N action
+    pub threshold: u32,
+    /// Account contract deployed for the wallet, when deployment is enabled
+    pub contract: Option<Address>,
+    /// Service tier; shared by all of an owner's wallets
+    pub tier: u32,
+}
+
+/// Limits applied to owners in a tier.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TierLimits {
+    /// Maximum amount an owner may spend per day
+    pub max_daily_spend: i128,
+    /// Maximum balance an owner's wallet may hold
+    pub max_balance: i128,
+}
+
+/// Signer set of a wallet, as read by sibling contracts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WalletSigners {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
 }
 
 pub struct WalletEvents;
@@ -54,6 +244,16 @@ impl WalletEvents {
         env.events().publish(topics, (owner.clone(), wallet_id));
     }
 
+    pub fn contract_deployed(env: &Env, owner: &Address, contract: &Address) {
+        let topics = (symbol_short!("wallet"), symbol_short!("deployed"));
+        env.events().publish(topics, (owner.clone(), contract.clone()));
+    }
+
+    pub fn wallet_funded(env: &Env, owner: &Address, token: &Address, amount: i128) {
+        let topics = (symbol_short!("wallet"), symbol_short!("funded"));
+        env.events().publish(topics, (owner.clone(), token.clone(), amount));
+    }
+
     pub fn wallet_creation_failure(
         env: &Env,
         batch_id: u64,
@@ -64,6 +264,113 @@ impl WalletEvents {
         env.events().publish(topics, (owner.clone(), error_code));
     }
 
+    pub fn wallet_update_failure(
+        env: &Env,
+        batch_id: u64,
+        owner: &Address,
+        wallet_index: u32,
+        error_code: u32,
+    ) {
+        let topics = (symbol_short!("wallet"), symbol_short!("upd_fail"), batch_id);
+        env.events()
+            .publish(topics, (owner.clone(), wallet_index, error_code));
+    }
+
+    pub fn metadata_updated(env: &Env, owner: &Address, wallet_id: u64, wallet_type: &Symbol) {
+        let topics = (symbol_short!("wallet"), symbol_short!("updated"));
+        env.events()
+            .publish(topics, (owner.clone(), wallet_id, wallet_type.clone()));
+    }
+
+    pub fn status_changed(env: &Env, owner: &Address, wallet_id: u64, status: WalletStatus) {
+        let topics = (symbol_short!("wallet"), symbol_short!("status"));
+        env.events().publish(topics, (owner.clone(), wallet_id, status));
+    }
+
+    pub fn signers_updated(
+        env: &Env,
+        owner: &Address,
+        wallet_id: u64,
+        signer_count: u32,
+        threshold: u32,
+    ) {
+        let topics = (symbol_short!("wallet"), symbol_short!("signers"));
+        env.events()
+            .publish(topics, (owner.clone(), wallet_id, signer_count, threshold));
+    }
+
+    pub fn wallet_deleted(env: &Env, owner: &Address, wallet_id: u64) {
+        let topics = (symbol_short!("wallet"), symbol_short!("deleted"));
+        env.events().publish(topics, (owner.clone(), wallet_id));
+    }
+
+    pub fn spend_recorded(env: &Env, source: &Address, owner: &Address, amount: i128) {
+        let topics = (symbol_short!("wallet"), symbol_short!("spend"));
+        env.events()
+            .publish(topics, (source.clone(), owner.clone(), amount));
+    }
+
+    pub fn activity_bumped(
+        env: &Env,
+        source: &Address,
+        owner: &Address,
+        wallet_id: u64,
+        count: u64,
+    ) {
+        let topics = (symbol_short!("wallet"), symbol_short!("activity"));
+        env.events()
+            .publish(topics, (source.clone(), owner.clone(), wallet_id, count));
+    }
+
+    pub fn tier_changed(env: &Env, owner: &Address, tier: u32) {
+        let topics = (symbol_short!("wallet"), symbol_short!("tier"));
+        env.events().publish(topics, (owner.clone(), tier));
+    }
+
+    pub fn wallet_transferred(env: &Env, from: &Address, to: &Address, wallet_id: u64) {
+        let topics = (symbol_short!("wallet"), symbol_short!("transfer"));
+        env.events().publish(topics, (from.clone(), to.clone(), wallet_id));
+    }
+
+    pub fn recovery_started(
+        env: &Env,
+        old_owner: &Address,
+        new_owner: &Address,
+        executable_at: u64,
+    ) {
+        let topics = (symbol_short!("recovery"), symbol_short!("started"));
+        env.events()
+            .publish(topics, (old_owner.clone(), new_owner.clone(), executable_at));
+    }
+
+    pub fn recovery_approved(env: &Env, old_owner: &Address, guardian: &Address, approvals: u32) {
+        let topics = (symbol_short!("recovery"), symbol_short!("approved"));
+        env.events()
+            .publish(topics, (old_owner.clone(), guardian.clone(), approvals));
+    }
+
+    pub fn guardians_set(env: &Env, owner: &Address, guardian_count: u32, threshold: u32) {
+        let topics = (symbol_short!("guardian"), symbol_short!("set"));
+        env.events()
+            .publish(topics, (owner.clone(), guardian_count, threshold));
+    }
+
+    pub fn invites_created(env: &Env, count: u32, expiry: u64) {
+        let topics = (symbol_short!("invite"), symbol_short!("created"));
+        env.events().publish(topics, (count, expiry));
+    }
+
+    pub fn invite_redeemed(env: &Env, code_hash: &BytesN<32>, owner: &Address, wallet_id: u64) {
+        let topics = (symbol_short!("invite"), symbol_short!("redeemed"));
+        env.events()
+            .publish(topics, (code_hash.clone(), owner.clone(), wallet_id));
+    }
+
+    pub fn recovery_cancelled(env: &Env, old_owner: &Address) {
+        let topics = (symbol_short!("recovery"), symbol_short!("cancelled"));
+        env.events().publish(topics, old_owner.clone());
+    }
+
     pub fn batch_completed(
         env: &Env,
         batch_id: u64,
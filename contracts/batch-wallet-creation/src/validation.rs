@@ -1,6 +1,7 @@
 //! Validation utilities for batch wallet creation.
 
-use soroban_sdk::{Address, Env};
+use crate::types::{DataKey, Wallet, MAX_LABEL_LEN};
+use soroban_sdk::{symbol_short, Address, Env, String, Symbol, Vec};
 
 /// Validates an owner address.
 pub fn validate_address(_address: &Address) -> Result<(), ()> {
@@ -8,8 +9,49 @@ pub fn validate_address(_address: &Address) -> Result<(), ()> {
     Ok(())
 }
 
-/// Checks if a wallet already exists for the given address.
+/// Validates a wallet label and type.
+pub fn validate_metadata(label: &String, wallet_type: &Symbol) -> Result<(), ()> {
+    if label.len() > MAX_LABEL_LEN {
+        return Err(());
+    }
+    if *wallet_type != symbol_short!("personal")
+        && *wallet_type != symbol_short!("savings")
+        && *wallet_type != symbol_short!("business")
+    {
+        return Err(());
+    }
+    Ok(())
+}
+
+/// Validates the initial funding of a wallet. A positive amount needs a token.
+pub fn validate_funding(amount: i128, token: &Option<Address>) -> Result<(), ()> {
+    if amount < 0 || (amount > 0 && token.is_none()) {
+        return Err(());
+    }
+    Ok(())
+}
+
+/// Checks if the given address holds at least one wallet.
 pub fn wallet_exists(env: &Env, address: &Address) -> bool {
-    use crate::types::DataKey;
-    env.storage().persistent().has(&DataKey::Wallets(address.clone()))
+    env.storage()
+        .persistent()
+        .has(&DataKey::OwnerWallets(address.clone()))
+}
+
+/// Returns the wallet indexes currently held by `owner`.
+pub fn owner_wallet_indexes(env: &Env, owner: &Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerWallets(owner.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Checks if `owner` already holds a wallet of the given type.
+pub fn owner_has_wallet_type(env: &Env, owner: &Address, wallet_type: &Symbol) -> bool {
+    owner_wallet_indexes(env, owner).iter().any(|wallet_index| {
+        env.storage()
+            .persistent()
+            .get::<_, Wallet>(&DataKey::Wallets(owner.clone(), wallet_index))
+            .is_some_and(|wallet| wallet.wallet_type == *wallet_type)
+    })
 }
\ No newline at end of file
@@ -9,6 +9,12 @@
 //! - **Atomic Updates**: Ensures reliable state changes for each user
 //! - **Validation**: Prevents invalid budget amounts
 //! - **Event Emission**: Tracks budget updates and failures
+//! - **Allocator Allowlist**: Lets other contracts allocate alongside the admin
+//!
+//! Both batch-wallet-creation (budgets at onboarding) and
+//! budget-recommendations (`apply_batch_to_budgets`) allocate through
+//! cross-calls. The contract has a single admin, so those contracts are
+//! registered with `add_allocator` rather than each having to be the admin.
 //!
 #![no_std]
 
@@ -31,26 +37,54 @@ impl BudgetAllocationContract {
         env.storage().instance().set(&DataKey::Admin, &admin);
     }
 
+    /// Allows `allocator` (e.g. a sibling contract) to allocate budgets.
+    pub fn add_allocator(env: Env, admin: Address, allocator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allocator(allocator.clone()), &true);
+        env.events()
+            .publish((symbol_short!("alloc"), symbol_short!("added")), allocator);
+    }
+
+    /// Revokes an allocator's permission to allocate budgets.
+    pub fn remove_allocator(env: Env, admin: Address, allocator: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Allocator(allocator.clone()));
+        env.events().publish(
+            (symbol_short!("alloc"), symbol_short!("removed")),
+            allocator,
+        );
+    }
+
+    /// Returns true if `allocator` may allocate budgets.
+    pub fn is_allocator(env: Env, allocator: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Allocator(allocator))
+    }
+
     /// Assigns monthly budgets to multiple users in a single operation.
     ///
     /// # Arguments
     /// * `env` - The contract environment
-    /// * `admin` - The admin address calling the function
+    /// * `caller` - The admin or an allocator calling the function
     /// * `requests` - List of user-budget pairs
     pub fn batch_allocate_budget(
         env: Env,
-        admin: Address,
+        caller: Address,
         requests: Vec<BudgetRequest>,
     ) -> BatchBudgetResult {
-        // Verify admin authority
-        admin.require_auth();
-        let stored_admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .expect("Not initialized");
-        if admin != stored_admin {
-            panic!("Unauthorized");
+        // Verify admin or allocator authority
+        caller.require_auth();
+        if !Self::is_allocator(env.clone(), caller.clone()) {
+            Self::require_admin(&env, &caller);
         }
 
         let mut successful = 0;
@@ -112,3 +146,17 @@ impl BudgetAllocationContract {
             .expect("Not initialized")
     }
 }
+
+impl BudgetAllocationContract {
+    // Internal helper that panics unless `caller` is the admin
+    fn require_admin(env: &Env, caller: &Address) {
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Not initialized");
+        if *caller != stored_admin {
+            panic!("Unauthorized");
+        }
+    }
+}
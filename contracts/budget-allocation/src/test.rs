@@ -99,3 +99,42 @@ fn test_unauthorized_access() {
 
     client.batch_allocate_budget(&not_admin, &requests);
 }
+
+#[test]
+fn test_allocators_can_allocate_alongside_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(BudgetAllocationContract, ());
+    let client = BudgetAllocationContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Two sibling contracts share the allocation role
+    let wallets = Address::generate(&env);
+    let recommendations = Address::generate(&env);
+    client.add_allocator(&admin, &wallets);
+    client.add_allocator(&admin, &recommendations);
+    assert_eq!(env.events().all().len(), 1);
+    assert!(client.is_allocator(&wallets));
+
+    let user = Address::generate(&env);
+    let requests = vec![
+        &env,
+        BudgetRequest {
+            user: user.clone(),
+            amount: 1000,
+        },
+    ];
+    client.batch_allocate_budget(&wallets, &requests);
+    let result = client.batch_allocate_budget(&recommendations, &requests);
+    assert_eq!(result.successful, 1);
+    assert_eq!(client.get_budget(&user).unwrap().amount, 1000);
+
+    client.remove_allocator(&admin, &wallets);
+    assert!(!client.is_allocator(&wallets));
+    assert!(client
+        .try_batch_allocate_budget(&wallets, &requests)
+        .is_err());
+}
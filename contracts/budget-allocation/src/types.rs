@@ -26,6 +26,8 @@ pub enum DataKey {
     Admin,
     Budget(Address),
     TotalAllocated, // Track global stats if needed
+    /// Contract or account allowed to allocate budgets besides the admin
+    Allocator(Address),
 }
 
 /// Result of a batch budget allocation operation
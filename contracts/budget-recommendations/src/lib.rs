@@ -24,12 +24,18 @@ mod recommendations;
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, panic_with_error, Address, Env, String, Vec,
+};
 
-pub use crate::recommendations::{generate_batch_recommendations, generate_recommendation};
+pub use crate::recommendations::{
+    generate_batch_recommendations, generate_recommendation, split_budget_by_category,
+};
 pub use crate::types::{
-    BatchRecommendationMetrics, BatchRecommendationResult, BudgetRecommendation, DataKey,
-    RecommendationEvents, RecommendationResult, UserProfile, MAX_BATCH_SIZE,
+    BatchBudgetResult, BatchFeedback, BatchRecommendationMetrics, BatchRecommendationResult,
+    BudgetRecommendation, BudgetRequest, DataKey, RecommendationEvents, RecommendationRecord,
+    RecommendationRef, RecommendationResponse, RecommendationResult, UserProfile, MAX_BATCH_SIZE,
+    MAX_HISTORY_PER_USER, MAX_NOTE_LEN,
 };
 use crate::validation::validate_batch;
 
@@ -49,6 +55,16 @@ pub enum BudgetRecommendationError {
     BatchTooLarge = 5,
     /// Invalid user profile
     InvalidUserProfile = 6,
+    /// No recommendation exists with the given ID
+    RecommendationNotFound = 7,
+    /// User has already responded to the recommendation
+    AlreadyResponded = 8,
+    /// Response note is too long
+    InvalidNote = 9,
+    /// No stored recommendations exist for the batch ID
+    BatchNotFound = 10,
+    /// Batch has already been applied to budget-allocation
+    BatchAlreadyApplied = 11,
 }
 
 impl From<BudgetRecommendationError> for soroban_sdk::Error {
@@ -57,6 +73,16 @@ impl From<BudgetRecommendationError> for soroban_sdk::Error {
     }
 }
 
+/// Allocation entrypoint exposed by the budget-allocation contract.
+#[contractclient(name = "BudgetClient")]
+pub trait BudgetInterface {
+    fn batch_allocate_budget(
+        env: Env,
+        admin: Address,
+        requests: Vec<BudgetRequest>,
+    ) -> BatchBudgetResult;
+}
+
 #[contract]
 pub struct BudgetRecommendationsContract;
 
@@ -143,10 +169,30 @@ impl BudgetRecommendationsContract {
         let (results, metrics) =
             generate_batch_recommendations(&env, &user_profiles, current_ledger);
 
-        // Emit events for each recommendation
-        for result in results.iter() {
+        let total_recommendations: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalRecommendationsGenerated)
+            .unwrap_or(0);
+
+        // Store and emit events for each recommendation; results are in
+        // profile order, and IDs continue from the lifetime total
+        let recorded_at = env.ledger().timestamp();
+        let mut recommendation_id = total_recommendations;
+        for (index, result) in results.iter().enumerate() {
             match result {
                 RecommendationResult::Success(recommendation) => {
+                    recommendation_id += 1;
+                    let user = user_profiles.get(index as u32).unwrap().address;
+                    Self::store_recommendation(
+                        &env,
+                        recommendation_id,
+                        batch_id,
+                        recorded_at,
+                        &user,
+                        &recommendation,
+                    );
+
                     RecommendationEvents::recommendation_generated(
                         &env,
                         batch_id,
@@ -176,11 +222,6 @@ impl BudgetRecommendationsContract {
             .instance()
             .get(&DataKey::TotalUsersProcessed)
             .unwrap_or(0);
-        let total_recommendations: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalRecommendationsGenerated)
-            .unwrap_or(0);
 
         env.storage()
             .instance()
@@ -196,6 +237,13 @@ impl BudgetRecommendationsContract {
         env.storage()
             .persistent()
             .set(&DataKey::BatchRecommendations(batch_id), &results);
+        let mut users: Vec<Address> = Vec::new(&env);
+        for profile in user_profiles.iter() {
+            users.push_back(profile.address);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::BatchUsers(batch_id), &users);
 
         // Create batch result
         let batch_result = BatchRecommendationResult {
@@ -227,6 +275,206 @@ impl BudgetRecommendationsContract {
             .get(&DataKey::BatchRecommendations(batch_id))
     }
 
+    /// Returns up to `limit` of the user's most recent recommendations,
+    /// newest first.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `user_id` - The user whose history to retrieve
+    /// * `limit` - Maximum number of records to return
+    ///
+    /// # Returns
+    /// * `Vec<RecommendationRecord>` - The stored records, empty if none
+    pub fn get_user_recommendation_history(
+        env: Env,
+        user_id: u64,
+        limit: u32,
+    ) -> Vec<RecommendationRecord> {
+        let history: Vec<RecommendationRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserRecommendations(user_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut records = Vec::new(&env);
+        for record in history.iter().rev().take(limit as usize) {
+            records.push_back(record);
+        }
+        records
+    }
+
+    /// Allocates each successful recommendation of a batch as the user's
+    /// budget in the budget-allocation contract, in a single cross-call.
+    ///
+    /// This contract must be registered with the budget-allocation contract's
+    /// `add_allocator`, which lets it allocate alongside batch-wallet-creation
+    /// without either being the sole admin. A batch can be applied once; if
+    /// the allocation fails, nothing is recorded.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `admin` - The admin address (must be admin)
+    /// * `batch_id` - The batch whose recommendations to apply
+    /// * `budget_contract` - The budget-allocation contract
+    ///
+    /// # Returns
+    /// * `BatchBudgetResult` - The budget-allocation contract's result
+    ///
+    /// # Events Emitted
+    /// * `budget_linked` - For each recommendation sent for allocation
+    /// * `batch_applied` - With the budget contract and allocation result
+    pub fn apply_batch_to_budgets(
+        env: Env,
+        admin: Address,
+        batch_id: u64,
+        budget_contract: Address,
+    ) -> BatchBudgetResult {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        let applied_key = DataKey::BatchApplied(batch_id);
+        if env.storage().persistent().has(&applied_key) {
+            panic_with_error!(&env, BudgetRecommendationError::BatchAlreadyApplied);
+        }
+        let results: Vec<RecommendationResult> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BatchRecommendations(batch_id))
+            .unwrap_or_else(|| panic_with_error!(&env, BudgetRecommendationError::BatchNotFound));
+        let users: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BatchUsers(batch_id))
+            .unwrap_or_else(|| panic_with_error!(&env, BudgetRecommendationError::BatchNotFound));
+
+        let mut requests: Vec<BudgetRequest> = Vec::new(&env);
+        for (index, result) in results.iter().enumerate() {
+            if let RecommendationResult::Success(recommendation) = result {
+                let user = users.get(index as u32).unwrap();
+                let amount = recommendation.recommended_budget;
+                RecommendationEvents::budget_linked(
+                    &env,
+                    batch_id,
+                    recommendation.user_id,
+                    &user,
+                    amount,
+                );
+                requests.push_back(BudgetRequest { user, amount });
+            }
+        }
+
+        let result = BudgetClient::new(&env, &budget_contract)
+            .batch_allocate_budget(&env.current_contract_address(), &requests);
+        env.storage()
+            .persistent()
+            .set(&applied_key, &budget_contract);
+
+        RecommendationEvents::batch_applied(&env, batch_id, &budget_contract, &result);
+        result
+    }
+
+    /// Returns the budget-allocation contract a batch was applied to, if any.
+    pub fn get_batch_budget_contract(env: Env, batch_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BatchApplied(batch_id))
+    }
+
+    /// Records whether a user followed a recommendation made to them.
+    ///
+    /// Each recommendation can be answered once; the response updates the
+    /// acceptance counters of the batch that generated it.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `user` - The address the recommendation was made to
+    /// * `recommendation_id` - ID from the user's recommendation history
+    /// * `accepted` - Whether the user followed the recommendation
+    /// * `note` - Free-form note, up to `MAX_NOTE_LEN` bytes
+    ///
+    /// # Events Emitted
+    /// * `recommendation_responded` - With the batch ID and the response
+    pub fn respond_to_recommendation(
+        env: Env,
+        user: Address,
+        recommendation_id: u64,
+        accepted: bool,
+        note: String,
+    ) {
+        user.require_auth();
+
+        let reference: RecommendationRef = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RecommendationRef(recommendation_id))
+            .unwrap_or_else(|| {
+                panic_with_error!(&env, BudgetRecommendationError::RecommendationNotFound)
+            });
+        if reference.user != user {
+            panic_with_error!(&env, BudgetRecommendationError::Unauthorized);
+        }
+        if note.len() > MAX_NOTE_LEN {
+            panic_with_error!(&env, BudgetRecommendationError::InvalidNote);
+        }
+
+        let response_key = DataKey::RecommendationResponse(recommendation_id);
+        if env.storage().persistent().has(&response_key) {
+            panic_with_error!(&env, BudgetRecommendationError::AlreadyResponded);
+        }
+        let response = RecommendationResponse {
+            accepted,
+            note,
+            responded_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&response_key, &response);
+
+        let mut feedback = Self::get_batch_feedback(env.clone(), reference.batch_id);
+        if accepted {
+            feedback.accepted += 1;
+        } else {
+            feedback.rejected += 1;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::BatchFeedback(reference.batch_id), &feedback);
+
+        RecommendationEvents::recommendation_responded(
+            &env,
+            reference.batch_id,
+            recommendation_id,
+            accepted,
+        );
+    }
+
+    /// Returns the user's response to a recommendation, if any.
+    pub fn get_recommendation_response(
+        env: Env,
+        recommendation_id: u64,
+    ) -> Option<RecommendationResponse> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RecommendationResponse(recommendation_id))
+    }
+
+    /// Returns the accepted and rejected counts for a batch.
+    pub fn get_batch_feedback(env: Env, batch_id: u64) -> BatchFeedback {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BatchFeedback(batch_id))
+            .unwrap_or_default()
+    }
+
+    /// Returns the percentage (0-100) of responses in a batch that accepted
+    /// the recommendation, or 0 if nobody has responded yet.
+    pub fn get_batch_acceptance_rate(env: Env, batch_id: u64) -> u32 {
+        let feedback = Self::get_batch_feedback(env, batch_id);
+        let responses = feedback.accepted + feedback.rejected;
+        if responses == 0 {
+            return 0;
+        }
+        feedback.accepted * 100 / responses
+    }
+
     /// Generates a recommendation for a single user (view-only, no storage).
     ///
     /// Useful for simulating recommendations before committing.
@@ -287,6 +535,42 @@ impl BudgetRecommendationsContract {
             .unwrap_or(0)
     }
 
+    // Internal helper indexing a recommendation by ID and appending it to the
+    // user's history, dropping the oldest record once the history is full
+    fn store_recommendation(
+        env: &Env,
+        id: u64,
+        batch_id: u64,
+        recorded_at: u64,
+        user: &Address,
+        recommendation: &BudgetRecommendation,
+    ) {
+        let reference = RecommendationRef {
+            batch_id,
+            user: user.clone(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::RecommendationRef(id), &reference);
+
+        let key = DataKey::UserRecommendations(recommendation.user_id);
+        let mut history: Vec<RecommendationRecord> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_HISTORY_PER_USER {
+            history.pop_front();
+        }
+        history.push_back(RecommendationRecord {
+            id,
+            batch_id,
+            recorded_at,
+            recommendation: recommendation.clone(),
+        });
+        env.storage().persistent().set(&key, &history);
+    }
+
     // Internal helper to verify admin
     fn require_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -7,12 +7,43 @@
 //! - Batches computations to reduce gas costs
 //! - Implements rule-based AI recommendations (deterministic for blockchain)
 
-use soroban_sdk::{Env, Symbol, Vec};
+use soroban_sdk::{symbol_short, Env, Map, Symbol, Vec};
 
 use crate::types::{
     BatchRecommendationMetrics, BudgetRecommendation, RecommendationResult, UserProfile,
 };
 
+/// Share of essential spending recommended for housing, in percent.
+const HOUSING_PERCENTAGE: i128 = 40;
+/// Share of essential spending recommended for food, in percent.
+const FOOD_PERCENTAGE: i128 = 25;
+/// Share of essential spending recommended for transport, in percent.
+const TRANSPORT_PERCENTAGE: i128 = 15;
+
+/// Splits a recommended budget into per-category envelopes.
+///
+/// Housing, food and transport take fixed shares of the user's current
+/// expenses, capped by the budget when it is lower; whatever is left of the
+/// budget is discretionary, so the categories always sum to the budget.
+pub fn split_budget_by_category(
+    env: &Env,
+    profile: &UserProfile,
+    recommended_budget: i128,
+) -> Map<Symbol, i128> {
+    let essentials_base = profile.monthly_expenses.min(recommended_budget).max(0);
+    let housing = essentials_base * HOUSING_PERCENTAGE / 100;
+    let food = essentials_base * FOOD_PERCENTAGE / 100;
+    let transport = essentials_base * TRANSPORT_PERCENTAGE / 100;
+    let discretionary = recommended_budget - housing - food - transport;
+
+    let mut categories = Map::new(env);
+    categories.set(symbol_short!("housing"), housing);
+    categories.set(symbol_short!("food"), food);
+    categories.set(symbol_short!("transport"), transport);
+    categories.set(Symbol::new(env, "discretionary"), discretionary);
+    categories
+}
+
 /// Generates a budget recommendation for a single user.
 ///
 /// Uses rule-based AI logic to generate recommendations based on:
@@ -103,15 +134,17 @@ pub fn generate_recommendation(
 
     // Generate recommendation notes
     let notes = if disposable_income < 0 {
-        Symbol::new(env, "expenses_exceed_income_review_needed")
+        Symbol::new(env, "expenses_exceed_income_review")
     } else if recommended_savings == 0 {
         Symbol::new(env, "minimal_savings_capacity")
     } else if profile.savings_balance < emergency_fund_target / 2 {
         Symbol::new(env, "build_emergency_fund_priority")
     } else {
-        Symbol::new(env, "on_track_continue_current_strategy")
+        Symbol::new(env, "on_track_continue_strategy")
     };
 
+    let category_budgets = split_budget_by_category(env, profile, recommended_budget);
+
     Ok(BudgetRecommendation {
         user_id: profile.user_id,
         recommended_budget,
@@ -121,6 +154,7 @@ pub fn generate_recommendation(
         confidence_score,
         recommendation_type,
         notes,
+        category_budgets,
     })
 }
 
@@ -187,7 +221,7 @@ pub fn generate_batch_recommendations(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_profile(env: &Env, user_id: u64, income: i128, expenses: i128) -> UserProfile {
         UserProfile {
@@ -196,7 +230,7 @@ mod tests {
             monthly_income: income,
             monthly_expenses: expenses,
             savings_balance: 100000,
-            spending_categories: Symbol::new(env, "food,transport"),
+            spending_categories: Symbol::new(env, "food_transport"),
             risk_tolerance: 3,
         }
     }
@@ -244,6 +278,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_category_budgets_sum_to_budget() {
+        let env = Env::default();
+        let profile = create_test_profile(&env, 1, 100000, 50000);
+
+        let recommendation = generate_recommendation(&env, &profile).unwrap();
+        let categories = recommendation.category_budgets;
+
+        assert_eq!(categories.len(), 4);
+        assert_eq!(categories.get(symbol_short!("housing")), Some(20000));
+        assert_eq!(categories.get(symbol_short!("food")), Some(12500));
+        assert_eq!(categories.get(symbol_short!("transport")), Some(7500));
+        let total: i128 = categories.values().iter().sum();
+        assert_eq!(total, recommendation.recommended_budget);
+    }
+
+    #[test]
+    fn test_category_budgets_capped_by_budget() {
+        let env = Env::default();
+        let profile = create_test_profile(&env, 1, 40000, 60000);
+
+        let recommendation = generate_recommendation(&env, &profile).unwrap();
+        let categories = recommendation.category_budgets;
+
+        // Essentials are sized from the budget when it is below expenses
+        assert_eq!(recommendation.recommended_budget, 40000);
+        assert_eq!(categories.get(symbol_short!("housing")), Some(16000));
+        assert_eq!(
+            categories.get(Symbol::new(&env, "discretionary")),
+            Some(8000)
+        );
+    }
+
     #[test]
     fn test_generate_batch_recommendations() {
         let env = Env::default();
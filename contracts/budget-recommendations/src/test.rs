@@ -3,12 +3,13 @@
 #![cfg(test)]
 
 use crate::{
-    BudgetRecommendationsContract, BudgetRecommendationsContractClient, RecommendationResult,
-    UserProfile,
+    BatchBudgetResult, BudgetRecommendationsContract, BudgetRecommendationsContractClient,
+    BudgetRequest, RecommendationResult, UserProfile, MAX_HISTORY_PER_USER,
 };
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    Address, Env, Symbol, Vec,
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, String, Symbol, Vec,
 };
 
 /// Creates a test environment with the contract deployed and initialized.
@@ -32,7 +33,7 @@ fn create_user_profile(
     income: i128,
     expenses: i128,
     savings: i128,
-    risk_tolerance: u8,
+    risk_tolerance: u32,
 ) -> UserProfile {
     UserProfile {
         user_id,
@@ -40,7 +41,7 @@ fn create_user_profile(
         monthly_income: income,
         monthly_expenses: expenses,
         savings_balance: savings,
-        spending_categories: Symbol::new(env, "food,transport,utilities"),
+        spending_categories: Symbol::new(env, "food_transport_utilities"),
         risk_tolerance,
     }
 }
@@ -56,7 +57,7 @@ fn test_initialize_contract() {
     assert_eq!(client.get_admin(), admin);
     assert_eq!(client.get_last_batch_id(), 0);
     assert_eq!(client.get_total_users_processed(), 0);
-    assert_eq!(client.get_total_recommendations_generated(), 0);
+    assert_eq!(client.get_total_recommendations(), 0);
 }
 
 #[test]
@@ -175,17 +176,17 @@ fn test_generate_batch_recommendations_accumulates_stats() {
 
     assert_eq!(client.get_last_batch_id(), 0);
     assert_eq!(client.get_total_users_processed(), 0);
-    assert_eq!(client.get_total_recommendations_generated(), 0);
+    assert_eq!(client.get_total_recommendations(), 0);
 
     client.generate_batch_recommendations(&admin, &profiles1);
     assert_eq!(client.get_last_batch_id(), 1);
     assert_eq!(client.get_total_users_processed(), 1);
-    assert_eq!(client.get_total_recommendations_generated(), 1);
+    assert_eq!(client.get_total_recommendations(), 1);
 
     client.generate_batch_recommendations(&admin, &profiles2);
     assert_eq!(client.get_last_batch_id(), 2);
     assert_eq!(client.get_total_users_processed(), 2);
-    assert_eq!(client.get_total_recommendations_generated(), 2);
+    assert_eq!(client.get_total_recommendations(), 2);
 }
 
 #[test]
@@ -242,7 +243,7 @@ fn test_generate_batch_recommendations_low_income_scenario() {
     // Should have notes about expenses exceeding income
     assert_eq!(
         rec.notes,
-        Symbol::new(&env, "expenses_exceed_income_review_needed")
+        Symbol::new(&env, "expenses_exceed_income_review")
     );
 }
 
@@ -312,6 +313,219 @@ fn test_generate_batch_recommendations_large_batch() {
     assert_eq!(result.successful, 50);
 }
 
+#[test]
+fn test_user_recommendation_history() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut first: Vec<UserProfile> = Vec::new(&env);
+    first.push_back(create_user_profile(&env, 1, 100000, 50000, 10000, 3));
+    first.push_back(create_user_profile(&env, 2, 100000, 50000, 10000, 3));
+    client.generate_batch_recommendations(&admin, &first);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    let mut second: Vec<UserProfile> = Vec::new(&env);
+    second.push_back(create_user_profile(&env, 1, 200000, 50000, 10000, 1));
+    client.generate_batch_recommendations(&admin, &second);
+
+    // Newest first, with the batch and time each was generated
+    let history = client.get_user_recommendation_history(&1, &10);
+    assert_eq!(history.len(), 2);
+    let latest = history.get(0).unwrap();
+    assert_eq!(latest.batch_id, 2);
+    assert_eq!(latest.recorded_at, env.ledger().timestamp());
+    assert_eq!(
+        latest.recommendation.recommendation_type,
+        Symbol::new(&env, "conservative")
+    );
+    assert_eq!(history.get(1).unwrap().batch_id, 1);
+
+    assert_eq!(client.get_user_recommendation_history(&1, &1).len(), 1);
+    assert_eq!(client.get_user_recommendation_history(&2, &10).len(), 1);
+    assert_eq!(client.get_user_recommendation_history(&3, &10).len(), 0);
+}
+
+#[test]
+fn test_user_recommendation_history_is_capped() {
+    let (env, admin, client) = setup_test_env();
+
+    let mut profiles: Vec<UserProfile> = Vec::new(&env);
+    profiles.push_back(create_user_profile(&env, 1, 100000, 50000, 10000, 3));
+    for _ in 0..=MAX_HISTORY_PER_USER {
+        client.generate_batch_recommendations(&admin, &profiles);
+    }
+
+    let history = client.get_user_recommendation_history(&1, &u32::MAX);
+    assert_eq!(history.len(), MAX_HISTORY_PER_USER);
+    assert_eq!(
+        history.get(0).unwrap().batch_id,
+        MAX_HISTORY_PER_USER as u64 + 1
+    );
+    assert_eq!(history.get(MAX_HISTORY_PER_USER - 1).unwrap().batch_id, 2);
+}
+
+// ============================================================================
+// Feedback Tests
+// ============================================================================
+
+#[test]
+fn test_respond_to_recommendation_tracks_acceptance() {
+    let (env, admin, client) = setup_test_env();
+
+    let first = create_user_profile(&env, 1, 100000, 50000, 10000, 3);
+    let second = create_user_profile(&env, 2, 100000, 50000, 10000, 3);
+    let third = create_user_profile(&env, 3, 100000, 50000, 10000, 3);
+    let mut profiles: Vec<UserProfile> = Vec::new(&env);
+    profiles.push_back(first.clone());
+    profiles.push_back(second.clone());
+    profiles.push_back(third.clone());
+    let result = client.generate_batch_recommendations(&admin, &profiles);
+
+    let record = client
+        .get_user_recommendation_history(&1, &1)
+        .get(0)
+        .unwrap();
+    assert_eq!(record.id, 1);
+    assert_eq!(client.get_batch_acceptance_rate(&result.batch_id), 0);
+
+    let note = String::from_str(&env, "cut dining out");
+    client.respond_to_recommendation(&first.address, &1, &true, &note);
+    client.respond_to_recommendation(&second.address, &2, &false, &String::from_str(&env, ""));
+    client.respond_to_recommendation(&third.address, &3, &true, &String::from_str(&env, ""));
+
+    let response = client.get_recommendation_response(&1).unwrap();
+    assert!(response.accepted);
+    assert_eq!(response.note, note);
+    assert!(client.get_recommendation_response(&4).is_none());
+
+    let feedback = client.get_batch_feedback(&result.batch_id);
+    assert_eq!(feedback.accepted, 2);
+    assert_eq!(feedback.rejected, 1);
+    assert_eq!(client.get_batch_acceptance_rate(&result.batch_id), 66);
+}
+
+#[test]
+fn test_respond_to_recommendation_rejects_invalid_responses() {
+    let (env, admin, client) = setup_test_env();
+
+    let profile = create_user_profile(&env, 1, 100000, 50000, 10000, 3);
+    let mut profiles: Vec<UserProfile> = Vec::new(&env);
+    profiles.push_back(profile.clone());
+    client.generate_batch_recommendations(&admin, &profiles);
+    let note = String::from_str(&env, "");
+
+    // Only the user the recommendation was made to can respond
+    let stranger = Address::generate(&env);
+    assert!(client
+        .try_respond_to_recommendation(&stranger, &1, &true, &note)
+        .is_err());
+    assert!(client
+        .try_respond_to_recommendation(&profile.address, &2, &true, &note)
+        .is_err());
+
+    client.respond_to_recommendation(&profile.address, &1, &true, &note);
+    assert!(client
+        .try_respond_to_recommendation(&profile.address, &1, &false, &note)
+        .is_err());
+    assert_eq!(client.get_batch_feedback(&1).accepted, 1);
+    assert_eq!(client.get_batch_feedback(&1).rejected, 0);
+}
+
+// ============================================================================
+// Budget Allocation Tests
+// ============================================================================
+
+/// Stands in for the budget-allocation contract, remembering the last batch.
+#[contract]
+struct MockBudget;
+
+#[contractimpl]
+impl MockBudget {
+    pub fn batch_allocate_budget(
+        env: Env,
+        admin: Address,
+        requests: Vec<BudgetRequest>,
+    ) -> BatchBudgetResult {
+        admin.require_auth();
+        let mut total_amount = 0;
+        for request in requests.iter() {
+            total_amount += request.amount;
+        }
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("requests"), &requests);
+        BatchBudgetResult {
+            successful: requests.len(),
+            failed: 0,
+            total_amount,
+        }
+    }
+
+    pub fn allocated(env: Env) -> Vec<BudgetRequest> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("requests"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_apply_batch_to_budgets() {
+    let (env, admin, client) = setup_test_env();
+    let budget_id = env.register(MockBudget, ());
+    let budget = MockBudgetClient::new(&env, &budget_id);
+
+    let first = create_user_profile(&env, 1, 100000, 50000, 10000, 3);
+    let second = create_user_profile(&env, 2, 200000, 80000, 10000, 1);
+    let mut profiles: Vec<UserProfile> = Vec::new(&env);
+    profiles.push_back(first.clone());
+    profiles.push_back(second.clone());
+    let batch = client.generate_batch_recommendations(&admin, &profiles);
+    assert_eq!(client.get_batch_budget_contract(&batch.batch_id), None);
+
+    let result = client.apply_batch_to_budgets(&admin, &batch.batch_id, &budget_id);
+    assert_eq!(result.successful, 2);
+
+    let allocated = budget.allocated();
+    assert_eq!(allocated.len(), 2);
+    for (index, profile) in [first, second].iter().enumerate() {
+        let request = allocated.get(index as u32).unwrap();
+        match batch.results.get(index as u32).unwrap() {
+            RecommendationResult::Success(rec) => {
+                assert_eq!(request.user, profile.address);
+                assert_eq!(request.amount, rec.recommended_budget);
+            }
+            _ => panic!("Expected success"),
+        }
+    }
+    assert_eq!(
+        client.get_batch_budget_contract(&batch.batch_id),
+        Some(budget_id.clone())
+    );
+
+    // A batch is applied only once
+    assert!(client
+        .try_apply_batch_to_budgets(&admin, &batch.batch_id, &budget_id)
+        .is_err());
+}
+
+#[test]
+fn test_apply_batch_to_budgets_rejects_invalid_calls() {
+    let (env, admin, client) = setup_test_env();
+    let budget_id = env.register(MockBudget, ());
+
+    let mut profiles: Vec<UserProfile> = Vec::new(&env);
+    profiles.push_back(create_user_profile(&env, 1, 100000, 50000, 10000, 3));
+    let batch = client.generate_batch_recommendations(&admin, &profiles);
+
+    let stranger = Address::generate(&env);
+    assert!(client
+        .try_apply_batch_to_budgets(&stranger, &batch.batch_id, &budget_id)
+        .is_err());
+    assert!(client
+        .try_apply_batch_to_budgets(&admin, &(batch.batch_id + 1), &budget_id)
+        .is_err());
+}
+
 // ============================================================================
 // Simulation Tests
 // ============================================================================
@@ -330,6 +544,21 @@ fn test_simulate_recommendation() {
     assert!(recommendation.confidence_score > 0);
 }
 
+#[test]
+fn test_simulate_recommendation_category_budgets() {
+    let (env, _admin, client) = setup_test_env();
+
+    let profile = create_user_profile(&env, 1, 100000, 50000, 10000, 5);
+    let recommendation = client.simulate_recommendation(&profile).unwrap();
+
+    // Aggressive profiles save less, leaving more discretionary spending
+    let discretionary = recommendation
+        .category_budgets
+        .get(Symbol::new(&env, "discretionary"))
+        .unwrap();
+    assert_eq!(discretionary, 10000 + 50000 - 7500);
+}
+
 #[test]
 fn test_simulate_recommendation_no_storage() {
     let (env, admin, client) = setup_test_env();
@@ -1,10 +1,16 @@
 //! Data types and events for batch budget recommendations.
 
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Map, String, Symbol, Vec};
 
 /// Maximum number of users in a single batch for optimization.
 pub const MAX_BATCH_SIZE: u32 = 100;
 
+/// Maximum number of past recommendations kept per user; older ones are dropped.
+pub const MAX_HISTORY_PER_USER: u32 = 50;
+
+/// Maximum length in bytes of a note attached to a recommendation response.
+pub const MAX_NOTE_LEN: u32 = 256;
+
 /// Represents a user's financial profile for budget recommendations.
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -19,7 +25,7 @@ pub struct UserProfile {
     pub monthly_expenses: i128,
     /// Current savings balance in stroops
     pub savings_balance: i128,
-    /// Spending category preferences (comma-separated categories)
+    /// Spending category preferences (underscore-separated categories)
     pub spending_categories: Symbol,
     /// Risk tolerance level (1-5, where 1 is conservative, 5 is aggressive)
     pub risk_tolerance: u32,
@@ -45,6 +51,72 @@ pub struct BudgetRecommendation {
     pub recommendation_type: Symbol,
     /// Additional recommendation notes
     pub notes: Symbol,
+    /// Split of `recommended_budget` into housing, food, transport and
+    /// discretionary spending
+    pub category_budgets: Map<Symbol, i128>,
+}
+
+/// A stored recommendation in a user's history.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RecommendationRecord {
+    /// Recommendation ID, used to respond to it
+    pub id: u64,
+    /// Batch that generated the recommendation
+    pub batch_id: u64,
+    /// Ledger timestamp when the recommendation was generated
+    pub recorded_at: u64,
+    /// The recommendation itself
+    pub recommendation: BudgetRecommendation,
+}
+
+/// Batch and user a recommendation ID belongs to.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RecommendationRef {
+    /// Batch that generated the recommendation
+    pub batch_id: u64,
+    /// Address of the user the recommendation is for
+    pub user: Address,
+}
+
+/// A user's response to a recommendation.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RecommendationResponse {
+    /// Whether the user followed the recommendation
+    pub accepted: bool,
+    /// Free-form note from the user
+    pub note: String,
+    /// Ledger timestamp of the response
+    pub responded_at: u64,
+}
+
+/// Response counters for a batch of recommendations.
+#[derive(Clone, Debug, Default)]
+#[contracttype]
+pub struct BatchFeedback {
+    /// Number of recommendations accepted
+    pub accepted: u32,
+    /// Number of recommendations rejected
+    pub rejected: u32,
+}
+
+/// Mirror of the budget-allocation contract's `BudgetRequest`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BudgetRequest {
+    pub user: Address,
+    pub amount: i128,
+}
+
+/// Mirror of the budget-allocation contract's `BatchBudgetResult`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchBudgetResult {
+    pub successful: u32,
+    pub failed: u32,
+    pub total_amount: i128,
 }
 
 /// Aggregated metrics for a batch of recommendations.
@@ -68,6 +140,7 @@ pub struct BatchRecommendationMetrics {
 }
 
 /// Result of processing a single user's recommendation.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
 #[contracttype]
 pub enum RecommendationResult {
@@ -107,6 +180,18 @@ pub enum DataKey {
     TotalUsersProcessed,
     /// Total recommendations generated lifetime
     TotalRecommendationsGenerated,
+    /// Recommendation history for a user ID, oldest first
+    UserRecommendations(u64),
+    /// Batch and user of a recommendation ID
+    RecommendationRef(u64),
+    /// User response to a recommendation ID
+    RecommendationResponse(u64),
+    /// Response counters for a batch ID
+    BatchFeedback(u64),
+    /// User addresses of a batch ID, in the order of its results
+    BatchUsers(u64),
+    /// Budget-allocation contract a batch ID was applied to
+    BatchApplied(u64),
 }
 
 /// Events emitted by the budget recommendations contract.
@@ -151,6 +236,44 @@ impl RecommendationEvents {
         env.events().publish(topics, metrics.clone());
     }
 
+    /// Event emitted when a user accepts or rejects a recommendation.
+    pub fn recommendation_responded(
+        env: &Env,
+        batch_id: u64,
+        recommendation_id: u64,
+        accepted: bool,
+    ) {
+        let topics = (
+            symbol_short!("recommend"),
+            symbol_short!("response"),
+            batch_id,
+        );
+        env.events().publish(topics, (recommendation_id, accepted));
+    }
+
+    /// Event emitted when a recommendation is sent to budget-allocation.
+    pub fn budget_linked(env: &Env, batch_id: u64, user_id: u64, user: &Address, amount: i128) {
+        let topics = (
+            symbol_short!("recommend"),
+            symbol_short!("linked"),
+            batch_id,
+        );
+        env.events()
+            .publish(topics, (user_id, user.clone(), amount));
+    }
+
+    /// Event emitted when a batch has been applied to budget-allocation.
+    pub fn batch_applied(
+        env: &Env,
+        batch_id: u64,
+        budget_contract: &Address,
+        result: &BatchBudgetResult,
+    ) {
+        let topics = (symbol_short!("batch"), symbol_short!("applied"), batch_id);
+        env.events()
+            .publish(topics, (budget_contract.clone(), result.clone()));
+    }
+
     /// Event emitted for high-confidence recommendations.
     pub fn high_confidence_recommendation(
         env: &Env,
@@ -86,7 +86,7 @@ pub fn validate_batch(profiles: &Vec<UserProfile>) -> Result<(), &'static str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_profile(env: &Env, user_id: u64, income: i128, expenses: i128) -> UserProfile {
         UserProfile {
@@ -95,7 +95,7 @@ mod tests {
             monthly_income: income,
             monthly_expenses: expenses,
             savings_balance: 0,
-            spending_categories: Symbol::new(env, "food,transport"),
+            spending_categories: Symbol::new(env, "food_transport"),
             risk_tolerance: 3,
         }
     }
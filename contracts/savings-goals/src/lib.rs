@@ -23,7 +23,7 @@
 mod types;
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Vec};
+use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
 
 pub use crate::types::{
     BatchGoalMetrics, BatchGoalResult, DataKey, ErrorCode, GoalEvents, GoalResult, SavingsGoal,
@@ -45,6 +45,14 @@ pub enum SavingsGoalError {
     EmptyBatch = 4,
     /// Batch exceeds maximum size
     BatchTooLarge = 5,
+    /// Goal does not exist
+    GoalNotFound = 6,
+    /// Goal is no longer active
+    GoalInactive = 7,
+    /// Amount must be positive
+    InvalidAmount = 8,
+    /// Token is not the configured contribution token
+    InvalidToken = 9,
 }
 
 impl From<SavingsGoalError> for soroban_sdk::Error {
@@ -293,6 +301,83 @@ impl SavingsGoalsContract {
         env.storage().persistent().get(&DataKey::Goal(goal_id))
     }
 
+    /// Contributes tokens towards an existing savings goal.
+    ///
+    /// Moves `amount` of `token` from the contributor to the goal owner and
+    /// adds it to the goal's saved amount. Used directly by savers and by
+    /// payout contracts settling employer savings matches. `token` must be
+    /// the admin-configured contribution token.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `contributor` - The address funding the contribution
+    /// * `goal_id` - The ID of the goal to contribute to
+    /// * `token` - The token contract to transfer
+    /// * `amount` - The amount to contribute (in stroops)
+    ///
+    /// # Returns
+    /// * `i128` - The goal's new saved amount
+    ///
+    /// # Errors
+    /// * `GoalNotFound` - If the goal does not exist
+    /// * `GoalInactive` - If the goal is no longer active
+    /// * `InvalidAmount` - If the amount is not positive
+    /// * `InvalidToken` - If `token` is not the contribution token
+    pub fn contribute(
+        env: Env,
+        contributor: Address,
+        goal_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> i128 {
+        contributor.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, SavingsGoalError::InvalidAmount);
+        }
+        if Self::get_contribution_token(env.clone()) != Some(token.clone()) {
+            panic_with_error!(&env, SavingsGoalError::InvalidToken);
+        }
+
+        let mut goal: SavingsGoal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Goal(goal_id))
+            .unwrap_or_else(|| panic_with_error!(&env, SavingsGoalError::GoalNotFound));
+        if !goal.is_active {
+            panic_with_error!(&env, SavingsGoalError::GoalInactive);
+        }
+
+        token::Client::new(&env, &token).transfer(&contributor, &goal.user, &amount);
+
+        goal.current_amount = goal
+            .current_amount
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(&env, SavingsGoalError::InvalidAmount));
+        env.storage()
+            .persistent()
+            .set(&DataKey::Goal(goal_id), &goal);
+
+        GoalEvents::goal_contributed(&env, goal_id, &contributor, amount);
+
+        goal.current_amount
+    }
+
+    /// Sets the token accepted by `contribute`.
+    pub fn set_contribution_token(env: Env, admin: Address, token: Address) {
+        admin.require_auth();
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ContributionToken, &token);
+    }
+
+    /// Returns the token accepted by `contribute`, if configured.
+    pub fn get_contribution_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ContributionToken)
+    }
+
     /// Retrieves all goal IDs for a specific user.
     ///
     /// # Arguments
@@ -3,7 +3,7 @@
 #![cfg(test)]
 
 use crate::{SavingsGoalsContract, SavingsGoalsContractClient};
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Symbol, Vec};
+use soroban_sdk::{testutils::Address as _, token, Address, Env, Symbol, Vec};
 
 use crate::types::{ErrorCode, GoalResult, SavingsGoalRequest};
 
@@ -81,7 +81,7 @@ fn test_batch_set_savings_goals_single_user() {
 
 #[test]
 fn test_batch_set_savings_goals_multiple_users() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
@@ -118,7 +118,7 @@ fn test_batch_set_savings_goals_multiple_users() {
 
 #[test]
 fn test_batch_set_savings_goals_with_invalid_requests() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
@@ -155,7 +155,7 @@ fn test_batch_set_savings_goals_with_invalid_requests() {
 
 #[test]
 fn test_batch_set_savings_goals_invalid_deadline() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -178,7 +178,7 @@ fn test_batch_set_savings_goals_invalid_deadline() {
 
 #[test]
 fn test_batch_set_savings_goals_invalid_initial_contribution() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -202,7 +202,7 @@ fn test_batch_set_savings_goals_invalid_initial_contribution() {
 #[test]
 #[should_panic]
 fn test_batch_set_savings_goals_empty_batch() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let requests: Vec<SavingsGoalRequest> = Vec::new(&env);
     client.batch_set_savings_goals(&admin, &requests);
 }
@@ -210,7 +210,7 @@ fn test_batch_set_savings_goals_empty_batch() {
 #[test]
 #[should_panic]
 fn test_batch_set_savings_goals_batch_too_large() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -229,7 +229,7 @@ fn test_batch_set_savings_goals_batch_too_large() {
 
 #[test]
 fn test_get_goal() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -249,7 +249,7 @@ fn test_get_goal() {
 
 #[test]
 fn test_get_user_goals() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -266,7 +266,7 @@ fn test_get_user_goals() {
 
 #[test]
 fn test_batch_metrics() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
@@ -287,7 +287,7 @@ fn test_batch_metrics() {
 
 #[test]
 fn test_multiple_batches() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
 
     // First batch
     let user1 = Address::generate(&env);
@@ -311,7 +311,7 @@ fn test_multiple_batches() {
 
 #[test]
 fn test_high_value_goal_event() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -331,7 +331,7 @@ fn test_high_value_goal_event() {
 
 #[test]
 fn test_set_admin() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let new_admin = Address::generate(&env);
 
     client.set_admin(&admin, &new_admin);
@@ -341,7 +341,7 @@ fn test_set_admin() {
 
 #[test]
 fn test_mixed_valid_and_invalid_requests() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
@@ -378,7 +378,7 @@ fn test_mixed_valid_and_invalid_requests() {
 
 #[test]
 fn test_zero_initial_contribution() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -397,7 +397,7 @@ fn test_zero_initial_contribution() {
 
 #[test]
 fn test_full_initial_contribution() {
-    let (env, client, admin) = setup_test_contract();
+    let (env, admin, client) = setup_test_contract();
     let user = Address::generate(&env);
 
     let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
@@ -413,3 +413,56 @@ fn test_full_initial_contribution() {
     assert_eq!(goal.current_amount, 100_000_000);
     assert_eq!(goal.target_amount, 100_000_000);
 }
+
+#[test]
+fn test_contribute_with_contribution_token() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let saver = Address::generate(&env);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &token).mint(&saver, &50_000_000);
+    client.set_contribution_token(&admin, &token);
+    assert_eq!(client.get_contribution_token(), Some(token.clone()));
+
+    let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
+    requests.push_back(create_valid_request(&env, &user, "vacation", 100_000_000));
+    client.batch_set_savings_goals(&admin, &requests);
+
+    assert_eq!(
+        client.contribute(&saver, &1, &token, &30_000_000),
+        40_000_000
+    );
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 30_000_000);
+    assert_eq!(client.get_goal(&1).unwrap().current_amount, 40_000_000);
+
+    assert!(client.try_contribute(&saver, &1, &token, &0).is_err());
+    assert!(client.try_contribute(&saver, &2, &token, &1).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_contribute_rejects_other_tokens() {
+    let (env, admin, client) = setup_test_contract();
+    let user = Address::generate(&env);
+    let saver = Address::generate(&env);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.set_contribution_token(&admin, &token);
+
+    // Anyone can issue a token; it must not count towards goals
+    let junk = env
+        .register_stellar_asset_contract_v2(saver.clone())
+        .address();
+    token::StellarAssetClient::new(&env, &junk).mint(&saver, &100_000_000);
+
+    let mut requests: Vec<SavingsGoalRequest> = Vec::new(&env);
+    requests.push_back(create_valid_request(&env, &user, "vacation", 100_000_000));
+    client.batch_set_savings_goals(&admin, &requests);
+
+    client.contribute(&saver, &1, &junk, &90_000_000);
+}
@@ -113,6 +113,8 @@ pub enum DataKey {
     TotalGoalsCreated,
     /// Total batches processed lifetime
     TotalBatchesProcessed,
+    /// Token accepted for contributions
+    ContributionToken,
 }
 
 /// Error codes for goal validation and creation.
@@ -167,6 +169,12 @@ impl GoalEvents {
             .publish(topics, (successful, failed, total_amount));
     }
 
+    /// Event emitted when tokens are contributed to an existing goal.
+    pub fn goal_contributed(env: &Env, goal_id: u64, contributor: &Address, amount: i128) {
+        let topics = (symbol_short!("goal"), symbol_short!("contrib"), goal_id);
+        env.events().publish(topics, (contributor.clone(), amount));
+    }
+
     /// Event emitted for high-value goals (>= 10,000 XLM).
     pub fn high_value_goal(env: &Env, batch_id: u64, goal_id: u64, amount: i128) {
         let topics = (symbol_short!("goal"), symbol_short!("highval"), batch_id);
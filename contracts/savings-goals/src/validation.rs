@@ -129,7 +129,11 @@ pub fn validate_batch(requests: &soroban_sdk::Vec<SavingsGoalRequest>) -> Result
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{symbol_short, testutils::Address as _, Env};
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Ledger},
+        Env,
+    };
 
     fn create_valid_request(env: &Env) -> SavingsGoalRequest {
         SavingsGoalRequest {
@@ -216,6 +220,7 @@ mod tests {
     #[test]
     fn test_is_valid_deadline() {
         let env = Env::default();
+        env.ledger().with_mut(|li| li.sequence_number = 1_000);
         let current = env.ledger().sequence() as u64;
 
         assert!(is_valid_deadline(&env, current + 100));